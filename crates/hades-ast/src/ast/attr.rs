@@ -0,0 +1,138 @@
+/// An `@name` or `@name(arg)` annotation placed before a function, struct or field.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Attribute {
+    /// `@inline`: LLVM `alwaysinline`.
+    Inline,
+    /// `@noinline`: LLVM `noinline`.
+    NoInline,
+    /// `@cold`: LLVM `cold`, the function is unlikely to be called.
+    Cold,
+    /// `@repr(C)`: fields are laid out in declaration order with C alignment.
+    ReprC,
+    /// `@packed`: fields are laid out without padding.
+    Packed,
+    /// `@deprecated` or `@deprecated("note")`: every use is reported as a warning.
+    Deprecated(Option<String>),
+    /// `@link("name")` on an `extern fn`: the C library that provides the symbol.
+    Link(String),
+    /// `@cfg(...)`: the declaration is dropped after parsing unless the condition holds.
+    Cfg(Cfg),
+}
+
+/// The condition of `@cfg(...)`: a bare flag such as `debug`, or a `key = "value"` pair
+/// such as `os = "linux"` or `feature = "simd"`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Cfg {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl Cfg {
+    pub fn new(key: impl Into<String>, value: Option<String>) -> Self {
+        Self {
+            key: key.into(),
+            value,
+        }
+    }
+}
+
+impl std::fmt::Display for Cfg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{} = \"{value}\"", self.key),
+            None => write!(f, "{}", self.key),
+        }
+    }
+}
+
+/// Which kind of declaration an attribute is written on.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AttributeTarget {
+    Function,
+    ExternFunction,
+    Struct,
+    Field,
+}
+
+impl std::fmt::Display for AttributeTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeTarget::Function => write!(f, "a function"),
+            AttributeTarget::ExternFunction => write!(f, "an extern function"),
+            AttributeTarget::Struct => write!(f, "a struct"),
+            AttributeTarget::Field => write!(f, "a field"),
+        }
+    }
+}
+
+impl Attribute {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Attribute::Inline => "inline",
+            Attribute::NoInline => "noinline",
+            Attribute::Cold => "cold",
+            Attribute::ReprC => "repr",
+            Attribute::Packed => "packed",
+            Attribute::Deprecated(_) => "deprecated",
+            Attribute::Link(_) => "link",
+            Attribute::Cfg(_) => "cfg",
+        }
+    }
+
+    pub fn applies_to(&self, target: AttributeTarget) -> bool {
+        match self {
+            Attribute::Inline | Attribute::NoInline => target == AttributeTarget::Function,
+            Attribute::Cold => matches!(
+                target,
+                AttributeTarget::Function | AttributeTarget::ExternFunction
+            ),
+            Attribute::Link(_) => target == AttributeTarget::ExternFunction,
+            Attribute::ReprC | Attribute::Packed => target == AttributeTarget::Struct,
+            Attribute::Deprecated(_) | Attribute::Cfg(_) => true,
+        }
+    }
+}
+
+/// The attributes of one declaration, in source order.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Attributes(Vec<Attribute>);
+
+impl Attributes {
+    pub fn new(attrs: Vec<Attribute>) -> Self {
+        Self(attrs)
+    }
+
+    pub fn has(&self, attr: &Attribute) -> bool {
+        self.0.contains(attr)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Attribute> {
+        self.0.iter()
+    }
+
+    /// `Some` for a `@deprecated` declaration, holding the note if one was given.
+    pub fn deprecation(&self) -> Option<Option<&str>> {
+        self.0.iter().find_map(|attr| match attr {
+            Attribute::Deprecated(note) => Some(note.as_deref()),
+            _ => None,
+        })
+    }
+
+    pub fn cfgs(&self) -> impl Iterator<Item = &Cfg> {
+        self.0.iter().filter_map(|attr| match attr {
+            Attribute::Cfg(cfg) => Some(cfg),
+            _ => None,
+        })
+    }
+
+    pub fn link(&self) -> Option<&str> {
+        self.0.iter().find_map(|attr| match attr {
+            Attribute::Link(library) => Some(library.as_str()),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,13 @@
+use crate::ast::Expr;
+
+use super::AssignTarget;
+
+/// `asm("template", [inputs], [outputs], [clobbers])`. Each operand pairs an LLVM
+/// constraint string with an expression; output operands must be assignable places.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsmExpr {
+    pub template: String,
+    pub inputs: Vec<(String, Expr)>,
+    pub outputs: Vec<(String, AssignTarget)>,
+    pub clobbers: Vec<String>,
+}
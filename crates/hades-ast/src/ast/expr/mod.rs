@@ -1,11 +1,13 @@
+mod asm;
 mod assignment;
 
+pub use asm::*;
 pub use assignment::*;
 
 use indexmap::IndexMap;
 
 use super::value::Value;
-use crate::ast::Types;
+use crate::ast::{Block, If, Loop, Types};
 use hades_tokens::{Ident, Name, Op};
 
 /// A `null` literal with the pointer type expected by the surrounding context.
@@ -25,6 +27,8 @@ impl NullExpr {
 pub struct FunctionCall {
     pub func: Name,
     pub args: Vec<Expr>,
+    /// `name: value` arguments, which follow the positional ones.
+    pub named_args: IndexMap<Ident, Expr>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -32,6 +36,7 @@ pub struct MethodCall {
     pub receiver: Box<Expr>,
     pub func: Name,
     pub args: Vec<Expr>,
+    pub named_args: IndexMap<Ident, Expr>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -39,6 +44,7 @@ pub struct QualifiedCall {
     pub path: Vec<Ident>,
     pub func: Name,
     pub args: Vec<Expr>,
+    pub named_args: IndexMap<Ident, Expr>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -72,10 +78,60 @@ pub enum Expr {
     As(AsExpression),
     FieldAccess(FieldAccessExpr),
     Call(CallKind),
+    If(Box<If>),
+    Block(Box<Block>),
+    Loop(Box<Loop>),
+    Asm(AsmExpr),
     Null,
+    Type(Types),
+}
+
+/// Compile-time builtins whose first argument is a type rather than a value, parsed into
+/// `Expr::Type` as in `sizeof(int)`.
+pub const TYPE_BUILTINS: [&str; 4] = ["sizeof", "alignof", "offsetof", "type_name"];
+
+pub fn is_type_builtin(name: &str) -> bool {
+    TYPE_BUILTINS.contains(&name)
+}
+
+/// `splat(vec4f, x)` fills every lane with `x`; `shuffle(a, b, 0, 5, ...)` builds a vector from
+/// lanes of `a` and `b` picked by constant index.
+pub const VECTOR_BUILTINS: [&str; 2] = ["splat", "shuffle"];
+
+/// Builtins an unqualified call resolves to before any function, so no function may take
+/// their names.
+pub fn is_reserved_builtin(name: &str) -> bool {
+    is_type_builtin(name) || VECTOR_BUILTINS.contains(&name)
+}
+
+/// Builtins whose first argument is parsed as a type.
+pub fn takes_type_arg(name: &str) -> bool {
+    is_type_builtin(name) || name == "splat"
 }
 
 impl Expr {
+    /// Block-like expressions may stand as statements without a trailing semicolon.
+    pub fn is_block_like(&self) -> bool {
+        matches!(self, Expr::If(_) | Expr::Block(_) | Expr::Loop(_))
+    }
+
+    /// Literals, negated numbers, `null` and arrays of them: values that mean the same wherever
+    /// they are walked.
+    pub fn is_literal(&self) -> bool {
+        match self {
+            Expr::Value(Value::Array(arr)) => {
+                arr.elem.iter().all(Expr::is_literal)
+                    && arr.fill.as_deref().is_none_or(Expr::is_literal)
+            }
+            Expr::Value(_) | Expr::Null => true,
+            Expr::Unary {
+                op: Op::Minus,
+                expr,
+            } => matches!(**expr, Expr::Value(Value::Number(_) | Value::Float(_))),
+            _ => false,
+        }
+    }
+
     pub fn unwrap_binary(&self) -> BinaryExpr {
         if let Expr::Binary(bin_expr) = self {
             bin_expr.clone()
@@ -1,3 +1,4 @@
+mod attr;
 mod expr;
 mod program;
 mod stmt;
@@ -5,6 +6,7 @@ mod types;
 mod value;
 mod walk;
 
+pub use attr::*;
 pub use expr::*;
 
 pub use program::*;
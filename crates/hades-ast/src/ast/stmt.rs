@@ -1,9 +1,11 @@
-use crate::ast::{AssignExpr, BinaryExpr, Program, Types};
+use crate::ast::{AssignExpr, BinaryExpr, CWidths, Program, Types};
 use derive_more::Debug;
 use hades_common::impl_span;
 use hades_error::Span;
 use hades_tokens::{Ident, Name, ParamKind};
-use indexmap::IndexMap;
+
+use super::attr::{Attributes, Cfg};
+use indexmap::{IndexMap, IndexSet};
 
 use super::expr::Expr;
 
@@ -22,12 +24,26 @@ pub struct Receiver {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Block {
     pub stmts: Program,
+    /// Trailing expression without a semicolon; its value is the value of the block.
+    pub tail: Option<Box<ExprAst>>,
     pub span: Span,
 }
 
 impl Block {
     pub fn new(stmts: Program, span: Span) -> Self {
-        Self { stmts, span }
+        Self {
+            stmts,
+            tail: None,
+            span,
+        }
+    }
+
+    pub fn with_tail(stmts: Program, tail: Option<ExprAst>, span: Span) -> Self {
+        Self {
+            stmts,
+            tail: tail.map(Box::new),
+            span,
+        }
     }
 }
 
@@ -48,6 +64,7 @@ pub struct Continue {
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct Break {
+    pub value: Option<ExprAst>,
     #[debug(skip)]
     pub span: Span,
 }
@@ -73,6 +90,21 @@ pub struct While {
     pub span: Span,
 }
 
+/// `for binding in iterable { ... }` over an array or a struct with an `iter()` method.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ForEach {
+    pub binding: Ident,
+    pub iterable: ExprAst,
+    pub body: Block,
+    pub span: Span,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Loop {
+    pub body: Block,
+    pub span: Span,
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct For {
     pub init: Let,
@@ -86,6 +118,23 @@ pub struct For {
 pub struct StructDef {
     pub name: Name,
     pub fields: IndexMap<Ident, FieldKind>,
+    pub export: bool,
+    /// `pub struct` can be named by importing modules.
+    pub public: bool,
+    pub attributes: Attributes,
+    /// Attributes of the data fields that have any.
+    pub field_attributes: IndexMap<Ident, Attributes>,
+    /// Data fields declared `pub`, the only ones importing modules can read or initialize.
+    pub public_fields: IndexSet<Ident>,
+    pub span: Span,
+}
+
+/// `extern type Name;` names a C type whose layout Hades does not know.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExternType {
+    pub name: Name,
+    /// Declared `pub`: visible to importing modules.
+    pub public: bool,
     pub span: Span,
 }
 
@@ -98,7 +147,13 @@ pub enum FieldKind {
 #[derive(Clone, PartialEq, Debug)]
 pub enum FuncBody {
     Block(Block),
-    Extern { variadic: bool },
+    /// `link` names the C library from `@link("name")` that provides the symbol, and `widths`
+    /// the C types of a declaration imported from a header that are narrower than Hades'.
+    Extern {
+        variadic: bool,
+        link: Option<String>,
+        widths: CWidths,
+    },
     Intrinsic(String),
 }
 
@@ -107,8 +162,17 @@ pub struct FuncDef {
     pub name: Name,
     pub receiver: Option<Receiver>,
     pub params: Vec<(ParamKind, Types)>,
+    /// Default values of the parameters that have one, `width: int = 1`.
+    pub defaults: IndexMap<Ident, Expr>,
+    /// A last parameter `nums: ...int` taking the rest of a call's arguments.
+    pub variadic: Option<(Ident, Types)>,
     pub return_type: Types,
     pub body: FuncBody,
+    /// `export fn` keeps its source name as the symbol so C code can call it.
+    pub export: bool,
+    /// `pub fn` can be called from importing modules.
+    pub public: bool,
+    pub attributes: Attributes,
     pub span: Span,
 }
 
@@ -128,12 +192,47 @@ pub struct ModuleDecl {
 pub enum ImportPrefix {
     Std,
     Local,
+    /// `import c "foo.h"`; the import's module is the header path.
+    C,
+    /// `import geometry::shapes`, where `geometry` is a dependency in `hades.toml`.
+    Package(String),
+}
+
+/// What an import brings into scope besides the module itself.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ImportItems {
+    /// `import std::io`: items are only reachable qualified, as `io::printf`.
+    Module,
+    /// `import std::io::{printf, fopen as open}`
+    Names(Vec<ImportName>),
+    /// `import std::io::*`: every public function and struct of the module.
+    Glob,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ImportName {
+    pub name: Ident,
+    pub alias: Option<Ident>,
+}
+
+impl ImportName {
+    /// The name the item is used by in the importing module.
+    pub fn local(&self) -> &Ident {
+        self.alias.as_ref().unwrap_or(&self.name)
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct Import {
     pub prefix: ImportPrefix,
+    /// Directories above the module: `net` in `import self::net::http`.
+    pub parents: Vec<String>,
     pub module: String,
+    /// `import std::math as m` qualifies the module's items as `m::sqrt`.
+    pub alias: Option<Ident>,
+    pub items: ImportItems,
+    /// `pub import` re-exports what it brings in to this module's importers.
+    pub public: bool,
     pub span: Span,
 }
 
@@ -143,6 +242,15 @@ pub struct DeferStmt {
     pub span: Span,
 }
 
+/// A statement behind `@cfg(...)`. The module loader replaces it with `stmt` when every
+/// condition holds and drops it otherwise, so it never reaches type checking.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CfgStmt {
+    pub cfgs: Vec<Cfg>,
+    pub stmt: Box<Stmt>,
+    pub span: Span,
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum Stmt {
     Let(Let),
@@ -151,7 +259,9 @@ pub enum Stmt {
     If(If),
     While(While),
     For(For),
+    ForEach(ForEach),
     StructDef(StructDef),
+    ExternType(ExternType),
     FuncDef(FuncDef),
     ModuleDecl(ModuleDecl),
     Import(Import),
@@ -159,6 +269,7 @@ pub enum Stmt {
     Return(Return),
     Break(Break),
     Defer(DeferStmt),
+    Cfg(CfgStmt),
 }
 
 impl_span!(Let);
@@ -168,13 +279,17 @@ impl_span!(ExprAst);
 impl_span!(If);
 impl_span!(While);
 impl_span!(For);
+impl_span!(ForEach);
+impl_span!(Loop);
 impl_span!(StructDef);
+impl_span!(ExternType);
 impl_span!(FuncDef);
 impl_span!(Return);
 impl_span!(Block);
 impl_span!(ModuleDecl);
 impl_span!(Import);
 impl_span!(DeferStmt);
+impl_span!(CfgStmt);
 
 impl Stmt {
     pub fn unwrap_let(self) -> Let {
@@ -201,7 +316,9 @@ impl Stmt {
             Stmt::If(i) => i.span(),
             Stmt::While(w) => w.span(),
             Stmt::For(f) => f.span(),
+            Stmt::ForEach(f) => f.span(),
             Stmt::StructDef(s) => s.span(),
+            Stmt::ExternType(e) => e.span(),
             Stmt::FuncDef(f) => f.span(),
             Stmt::Block(b) => b.span(),
             Stmt::Return(r) => r.span(),
@@ -209,6 +326,7 @@ impl Stmt {
             Stmt::Import(i) => i.span(),
             Stmt::Break(b) => b.span(),
             Stmt::Defer(d) => d.span(),
+            Stmt::Cfg(c) => c.span(),
         }
     }
 }
@@ -218,6 +336,8 @@ impl std::fmt::Display for ImportPrefix {
         match self {
             ImportPrefix::Std => write!(f, "std"),
             ImportPrefix::Local => write!(f, "local"),
+            ImportPrefix::C => write!(f, "c"),
+            ImportPrefix::Package(name) => write!(f, "{}", name),
         }
     }
 }
@@ -25,8 +25,37 @@ pub enum Types {
     Struct(Name),
     Self_,
     Pointer(Box<Types>),
+    /// A fixed-width SIMD vector such as `vec4f`, lowered to an LLVM vector of its lanes.
+    Vector(Box<Types>, usize),
 }
 
+/// A C scalar narrower than the Hades type a function imported from a C header sees it as.
+/// The value is converted where the function is called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CScalar {
+    /// An integer of `bits` bits, seen as `int`.
+    Int { bits: u8, signed: bool },
+    /// A 32-bit `float`, seen as the 64-bit Hades `float`.
+    Float,
+}
+
+/// The narrower C types of an extern function's return value and parameters; `None` where the
+/// Hades type is the C type.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CWidths {
+    pub ret: Option<CScalar>,
+    pub params: Vec<Option<CScalar>>,
+}
+
+impl CWidths {
+    pub fn param(&self, index: usize) -> Option<CScalar> {
+        self.params.get(index).copied().flatten()
+    }
+}
+
+/// Lane counts accepted in vector type names.
+pub const VECTOR_LANES: [usize; 4] = [2, 4, 8, 16];
+
 impl std::fmt::Display for Types {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -49,12 +78,19 @@ impl std::fmt::Display for Types {
             Types::Char => write!(f, "char"),
             Types::Self_ => write!(f, "self"),
             Types::Pointer(inner) => write!(f, "&{inner}"),
+            Types::Vector(elem, lanes) => match **elem {
+                Types::Float => write!(f, "vec{lanes}f"),
+                _ => write!(f, "vec{lanes}i"),
+            },
         }
     }
 }
 
 impl Types {
     pub fn from_ident(type_str: &Ident) -> Self {
+        if let Some(vector) = Types::vector_from_name(type_str.inner()) {
+            return vector;
+        }
         match type_str.inner() {
             "int" => Types::Int,
             "float" => Types::Float,
@@ -74,6 +110,18 @@ impl Types {
         }
     }
 
+    /// Parses `vec<lanes><f|i>`, e.g. `vec4f` or `vec8i`.
+    pub fn vector_from_name(name: &str) -> Option<Self> {
+        let spec = name.strip_prefix("vec")?;
+        let (lanes, elem) = match spec.split_at_checked(spec.len().checked_sub(1)?)? {
+            (lanes, "f") => (lanes, Types::Float),
+            (lanes, "i") => (lanes, Types::Int),
+            _ => return None,
+        };
+        let lanes = lanes.parse().ok().filter(|n| VECTOR_LANES.contains(n))?;
+        Some(Types::Vector(Box::new(elem), lanes))
+    }
+
     pub fn array_type(&self, size: usize) -> ArrayType {
         match self {
             Self::Int => ArrayType::IntArray(size),
@@ -87,6 +135,63 @@ impl Types {
         }
     }
 
+    /// Whether `array_type` can build an array of this type.
+    pub fn is_array_element(&self) -> bool {
+        matches!(
+            self,
+            Self::Int
+                | Self::Float
+                | Self::String
+                | Self::Bool
+                | Self::Struct(_)
+                | Self::Pointer(_)
+                | Self::Char
+        )
+    }
+
+    /// Whether a value of this type may be used where `target` is expected. Any pointer
+    /// converts implicitly to `&void`; the other direction needs an explicit `as`.
+    pub fn coerces_to(&self, target: &Types) -> bool {
+        match (self, target) {
+            (Types::Pointer(_), Types::Pointer(inner)) if **inner == Types::Void => true,
+            _ => self == target,
+        }
+    }
+
+    /// The type spelled as it is written in source, without module qualification.
+    pub fn source_name(&self) -> String {
+        match self {
+            Types::Struct(name) => name.link_name().to_string(),
+            Types::Pointer(inner) => format!("&{}", inner.source_name()),
+            Types::Array(_) => format!(
+                "[{}]{}",
+                self.get_array_size(),
+                self.get_array_elem_type().source_name()
+            ),
+            other => other.to_string(),
+        }
+    }
+
+    /// The type spelled with identifier characters only, for symbol names: `ptr_int`,
+    /// `arr4_float` or a struct's qualified name.
+    pub fn mangled(&self) -> String {
+        match self {
+            Types::Struct(name) => name.inner().to_string(),
+            Types::Pointer(inner) => format!("ptr_{}", inner.mangled()),
+            Types::Array(_) => format!(
+                "arr{}_{}",
+                self.get_array_size(),
+                self.get_array_elem_type().mangled()
+            ),
+            Types::Generic(types) => types
+                .iter()
+                .map(Types::mangled)
+                .collect::<Vec<_>>()
+                .join("_or_"),
+            other => other.to_string(),
+        }
+    }
+
     pub fn get_array_size(&self) -> usize {
         if let Types::Array(arr_type) = self {
             match arr_type {
@@ -132,18 +237,10 @@ impl Types {
         }
     }
 
-    pub fn qualify(&self, module: Option<&str>) -> Self {
-        match self {
-            Types::Struct(name) => Types::Struct(name.full_name_optional(module)),
-            Types::Array(ArrayType::StructArray(size, name)) => Types::Array(
-                ArrayType::StructArray(*size, name.full_name_optional(module)),
-            ),
-            Types::Pointer(inner) => Types::Pointer(Box::new(inner.qualify(module))),
-            other => other.clone(),
-        }
-    }
-
     pub fn with_module(type_str: &Ident, module: Option<&str>) -> Self {
+        if let Some(vector) = Types::vector_from_name(type_str.inner()) {
+            return vector;
+        }
         match type_str.inner() {
             "int" => Types::Int,
             "float" => Types::Float,
@@ -1,60 +1,338 @@
-use crate::ast::{Expr, FunctionCall, WalkAst};
-use crate::typed_ast::{CompilerContext, TypedExpr};
+use crate::ast::walk::walk_sequence_use;
+use crate::ast::{Expr, FunctionCall, Types, VECTOR_BUILTINS, WalkAst, is_type_builtin};
+use crate::typed_ast::{
+    CompilerContext, FunctionSignature, Params, TypedArrayIndex, TypedArrayLiteral, TypedExpr,
+    TypedValue,
+};
 use hades_error::{SemanticError, Span};
+use hades_tokens::{Ident, Name, Op};
+use indexmap::IndexMap;
 
 impl WalkAst for FunctionCall {
     type Output = TypedExpr;
 
     fn walk(&self, ctx: &mut CompilerContext, span: Span) -> Result<Self::Output, SemanticError> {
+        if is_type_builtin(self.func.inner()) {
+            return self.walk_type_builtin(ctx, span);
+        }
+        if VECTOR_BUILTINS.contains(&self.func.inner()) {
+            return self.walk_vector_builtin(ctx, span);
+        }
+        if let Some(length) = self.sequence_length(ctx) {
+            return Ok(TypedExpr::Ident {
+                ident: length,
+                typ: Types::Int,
+            });
+        }
+
         let resolved = ctx
             .module_name()
             .map(|m| self.func.full_name(m))
-            .filter(|n| ctx.get_function_signature(n).is_ok())
+            .filter(|n| !ctx.overloads(n).is_empty())
+            .or_else(|| ctx.imported_item(&self.func))
             .unwrap_or_else(|| self.func.clone());
-        let sig = ctx.get_function_signature(&resolved)?;
-        let return_type = sig.return_type().clone();
-        let params = sig.params();
-        sig.check_arg_count(self.args.len())
-            .then_some(())
-            .ok_or_else(|| {
-                SemanticError::argument_count_mismatch(
-                    sig.param_count(),
-                    self.args.len(),
-                    resolved.inner().to_string(),
-                    span.clone(),
-                )
-            })?;
-        walk_typed_args(&params, &self.args, ctx, span).map(|typed_args| TypedExpr::Call {
+        let (resolved, sig, typed_args) = walk_call(
+            "function",
+            resolved,
+            &self.args,
+            &self.named_args,
+            ctx,
+            span.clone(),
+        )?;
+        if let Some(warning) = sig.deprecation_warning(self.func.inner(), span) {
+            ctx.warn(warning);
+        }
+        Ok(TypedExpr::Call {
             func: resolved,
             args: typed_args,
             receiver: None,
-            typ: return_type,
+            typ: sig.return_type,
         })
     }
 }
 
-pub fn walk_typed_args(
-    params: &crate::typed_ast::Params,
+impl FunctionCall {
+    /// `len(nums)` on a variadic parameter reads the length passed along with it.
+    fn sequence_length(&self, ctx: &CompilerContext) -> Option<Ident> {
+        match self.args.as_slice() {
+            [Expr::Ident(sequence)] if self.func.inner() == "len" && self.named_args.is_empty() => {
+                ctx.sequence_length(sequence)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Picks the overload of `func` the arguments fit and walks them into its parameter order.
+/// Overloads that take every argument as it is win over those that need a coercion. `kind`
+/// names what is called in errors, "function" or "method".
+pub fn walk_call(
+    kind: &str,
+    func: Name,
     args: &[Expr],
+    named_args: &IndexMap<Ident, Expr>,
     ctx: &mut CompilerContext,
     span: Span,
-) -> Result<Vec<TypedExpr>, SemanticError> {
-    args.iter()
-        .enumerate()
-        .map(|(i, arg)| {
-            arg.walk(ctx, span.clone()).and_then(|typed| {
-                params
-                    .type_match(i, &typed.get_type())
-                    .then(|| typed.clone())
-                    .ok_or_else(|| {
-                        let expected = params.type_at(i).map(|t| t.to_string()).unwrap_or_default();
-                        SemanticError::type_mismatch(
-                            expected,
-                            typed.get_type().to_string(),
-                            span.clone(),
-                        )
-                    })
+) -> Result<(Name, FunctionSignature, Vec<TypedExpr>), SemanticError> {
+    let overloads: Vec<(Name, FunctionSignature)> = ctx
+        .overloads(&func)
+        .into_iter()
+        .map(|(name, sig)| (name.clone(), sig.clone()))
+        .collect();
+    let single = match overloads.as_slice() {
+        [] => Some(ctx.get_signature(kind, &func)?.clone()),
+        [(_, sig)] => Some(sig.clone()),
+        _ => None,
+    };
+    let typed_args = args
+        .iter()
+        .map(|arg| walk_sequence_use(arg, ctx, span.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let typed_named = named_args
+        .iter()
+        .map(|(name, arg)| Ok((name.clone(), arg.walk(ctx, span.clone())?)))
+        .collect::<Result<IndexMap<_, _>, SemanticError>>()?;
+
+    if let Some(sig) = single {
+        let func = resolved_name(func, &sig);
+        let typed_args = arrange_args(&sig, &func, typed_args, typed_named, ctx, span)?;
+        return Ok((func, sig, typed_args));
+    }
+
+    let costs: Vec<(usize, &Name, &FunctionSignature)> = overloads
+        .iter()
+        .filter_map(|(name, sig)| {
+            let arranged = arrange_args(
+                sig,
+                name,
+                typed_args.clone(),
+                typed_named.clone(),
+                ctx,
+                span.clone(),
+            );
+            arranged.ok().map(|arranged| {
+                let params = sig.params();
+                let coerced = params
+                    .types()
+                    .zip(&arranged)
+                    .filter(|(typ, arg)| **typ != arg.get_type())
+                    .count();
+                (coerced, name, sig)
             })
         })
-        .collect()
+        .collect();
+    let best = costs.iter().map(|(cost, _, _)| *cost).min();
+    let matching: Vec<_> = costs
+        .iter()
+        .filter(|(cost, _, _)| Some(*cost) == best)
+        .collect();
+
+    let with_candidates = |error: SemanticError, sigs: Vec<(&Name, &FunctionSignature)>| {
+        sigs.into_iter().fold(error, |error, (name, sig)| {
+            let params: Vec<_> = sig.params.types().map(Types::source_name).collect();
+            error.with_related(
+                name.span().clone(),
+                format!("candidate {}({})", func.link_name(), params.join(", ")),
+            )
+        })
+    };
+    let described = typed_args
+        .iter()
+        .map(|arg| arg.get_type().source_name())
+        .chain(
+            typed_named
+                .iter()
+                .map(|(name, arg)| format!("{}: {}", name, arg.get_type().source_name())),
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match matching.as_slice() {
+        [(_, _, sig)] => {
+            let resolved = resolved_name(func.clone(), sig);
+            let typed_args = arrange_args(sig, &resolved, typed_args, typed_named, ctx, span)?;
+            Ok((resolved, (*sig).clone(), typed_args))
+        }
+        [] => Err(with_candidates(
+            SemanticError::no_matching_overload(func.link_name().to_string(), described, span),
+            overloads.iter().map(|(name, sig)| (name, sig)).collect(),
+        )),
+        _ => Err(with_candidates(
+            SemanticError::ambiguous_overload(func.link_name().to_string(), described, span),
+            matching
+                .iter()
+                .map(|(_, name, sig)| (*name, *sig))
+                .collect(),
+        )),
+    }
+}
+
+/// The name a call to `func` resolved to `sig` is made through, spanned at the call.
+fn resolved_name(func: Name, sig: &FunctionSignature) -> Name {
+    match &sig.overload_of {
+        Some(_) => func.overload(&sig.params.mangled()),
+        None => func,
+    }
+}
+
+/// The arguments of a call to `sig` in parameter order. Parameters after the positional
+/// arguments take their named argument, or their default if the call leaves them out. The
+/// positional arguments left over go to the variadic parameter, if there is one.
+///
+/// Arguments are evaluated in the order this returns them, so named arguments run in the order
+/// of their parameters rather than the order they are written in: `f(b: g(), a: h())` calls
+/// `h` first.
+fn arrange_args(
+    sig: &FunctionSignature,
+    func: &Name,
+    mut args: Vec<TypedExpr>,
+    mut named_args: IndexMap<Ident, TypedExpr>,
+    ctx: &CompilerContext,
+    span: Span,
+) -> Result<Vec<TypedExpr>, SemanticError> {
+    let source = sig.overload_of.as_ref().unwrap_or(func).link_name();
+    let params = sig.params();
+    let names: Vec<&Ident> = params.names().collect();
+    if let Some(unknown) = named_args.keys().find(|name| !names.contains(name)) {
+        return Err(SemanticError::unknown_argument(
+            unknown.to_string(),
+            source.to_string(),
+            unknown.span().clone(),
+        ));
+    }
+    if let Some(given) = names
+        .iter()
+        .take(args.len())
+        .find_map(|name| named_args.get_key_value(*name).map(|(key, _)| key))
+    {
+        return Err(SemanticError::duplicate_argument(
+            given.to_string(),
+            given.span().clone(),
+        ));
+    }
+    if named_args.is_empty()
+        && (args.len() >= names.len() || sig.defaults.is_empty())
+        && !sig.check_arg_count(args.len())
+    {
+        return Err(SemanticError::argument_count_mismatch(
+            sig.param_count(),
+            args.len(),
+            source.to_string(),
+            span,
+        ));
+    }
+
+    let extra = match params.element() {
+        Some(_) if args.len() > names.len() => args.split_off(names.len()),
+        _ => Vec::new(),
+    };
+
+    let mut arranged = Vec::with_capacity(args.len().max(names.len()));
+    for (i, arg) in args.into_iter().enumerate() {
+        arranged.push(check_arg(&params, i, arg, ctx, span.clone())?);
+    }
+    for (i, name) in names.iter().enumerate().skip(arranged.len()) {
+        let typed = match (named_args.swap_remove(*name), sig.defaults.get(*name)) {
+            (Some(arg), _) => check_arg(&params, i, arg, ctx, span.clone())?,
+            (None, Some(default)) => default.clone(),
+            (None, None) => {
+                return Err(SemanticError::missing_argument(
+                    name.to_string(),
+                    source.to_string(),
+                    span,
+                ));
+            }
+        };
+        arranged.push(typed);
+    }
+    if let Some(element) = params.element() {
+        if let Some(forwarded) = forward_sequence(element, &extra, ctx) {
+            arranged.extend(forwarded);
+            return Ok(arranged);
+        }
+        let extra = extra
+            .into_iter()
+            .enumerate()
+            .map(|(i, arg)| check_arg(&params, names.len() + i, arg, ctx, span.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        arranged.extend(pack_variadic(element, extra));
+    }
+    Ok(arranged)
+}
+
+/// A variadic parameter of the caller passed as the only argument of a variadic parameter of
+/// the same element type is handed on as it is: its pointer and length.
+fn forward_sequence(
+    element: &Types,
+    extra: &[TypedExpr],
+    ctx: &CompilerContext,
+) -> Option<[TypedExpr; 2]> {
+    let [TypedExpr::Ident { ident, typ }] = extra else {
+        return None;
+    };
+    let length = ctx.sequence_length(ident)?;
+    (*typ == Types::Pointer(Box::new(element.clone()))).then(|| {
+        [
+            extra[0].clone(),
+            TypedExpr::Ident {
+                ident: length,
+                typ: Types::Int,
+            },
+        ]
+    })
+}
+
+/// A variadic parameter takes its arguments as the address of the first one and their count.
+/// They are stored in a temporary array; with none, the address is null.
+fn pack_variadic(element: &Types, args: Vec<TypedExpr>) -> [TypedExpr; 2] {
+    let pointer = Types::Pointer(Box::new(element.clone()));
+    let length = TypedExpr::Value(TypedValue::Number(args.len() as i64));
+    if args.is_empty() {
+        return [TypedExpr::Null(pointer), length];
+    }
+
+    let size = args.len();
+    let array_type = Types::Array(element.array_type(size));
+    let array = TypedExpr::Value(TypedValue::Array(TypedArrayLiteral {
+        elements: args,
+        size,
+        elem_typ: array_type.clone(),
+        fill: None,
+    }));
+    let first = TypedExpr::ArrayIndex(TypedArrayIndex {
+        expr: Box::new(array),
+        index: Box::new(TypedExpr::Value(TypedValue::Number(0))),
+        typ: array_type,
+    });
+    let address = TypedExpr::Unary {
+        op: Op::Ref,
+        expr: Box::new(first),
+        typ: pointer,
+    };
+    [address, length]
+}
+
+/// A variadic parameter of the caller can only be passed on whole to another variadic
+/// parameter, which [`forward_sequence`] takes care of before this.
+fn check_arg(
+    params: &Params,
+    i: usize,
+    arg: TypedExpr,
+    ctx: &CompilerContext,
+    span: Span,
+) -> Result<TypedExpr, SemanticError> {
+    if let TypedExpr::Ident { ident, .. } = &arg
+        && ctx.sequence_length(ident).is_some()
+    {
+        return Err(SemanticError::invalid_sequence_use(ident.to_string(), span));
+    }
+    if params.type_match(i, &arg.get_type()) {
+        return Ok(arg);
+    }
+    let expected = params.type_at(i).map(|t| t.to_string()).unwrap_or_default();
+    Err(SemanticError::type_mismatch(
+        expected,
+        arg.get_type().to_string(),
+        span,
+    ))
 }
@@ -1,46 +1,62 @@
-use crate::ast::{MethodCall, WalkAst};
+use crate::ast::{Expr, MethodCall, WalkAst};
 use crate::typed_ast::{CompilerContext, TypedExpr};
 use hades_error::{SemanticError, Span};
-use hades_tokens::Ident;
+use hades_tokens::{Ident, Name};
+use indexmap::IndexMap;
 
-use super::func::walk_typed_args;
+use super::func::walk_call;
 
 impl WalkAst for MethodCall {
     type Output = TypedExpr;
 
     fn walk(&self, ctx: &mut CompilerContext, span: Span) -> Result<Self::Output, SemanticError> {
         let typed_receiver = self.receiver.walk(ctx, span.clone())?;
-        let receiver_type = typed_receiver.get_type();
-        let struct_name = receiver_type.unwrap_struct_name();
-        let bare_struct_ident = Ident::new(struct_name.link_name().to_string(), span.clone());
-        let mangled = self.func.mangle(&bare_struct_ident);
+        walk_method(
+            typed_receiver,
+            &self.func,
+            &self.args,
+            &self.named_args,
+            ctx,
+            span,
+        )
+    }
+}
+
+/// A call of the method `func` on a receiver that has already been walked.
+pub fn walk_method(
+    typed_receiver: TypedExpr,
+    func: &Name,
+    args: &[Expr],
+    named_args: &IndexMap<Ident, Expr>,
+    ctx: &mut CompilerContext,
+    span: Span,
+) -> Result<TypedExpr, SemanticError> {
+    let receiver_type = typed_receiver.get_type();
+    let resolved = method_name(receiver_type.unwrap_struct_name(), func, ctx, &span);
+    let (resolved, sig, typed_args) =
+        walk_call("method", resolved, args, named_args, ctx, span.clone())?;
+    if let Some(warning) = sig.deprecation_warning(func.inner(), span) {
+        ctx.warn(warning);
+    }
+    Ok(TypedExpr::Call {
+        func: resolved,
+        args: typed_args,
+        receiver: Some(Box::new(typed_receiver)),
+        typ: sig.return_type,
+    })
+}
+
+/// The name the method `func` of `struct_name` is registered under.
+pub fn method_name(struct_name: &Name, func: &Name, ctx: &CompilerContext, span: &Span) -> Name {
+    let bare_struct_ident = Ident::new(struct_name.link_name().to_string(), span.clone());
+    let mangled = func.mangle(&bare_struct_ident);
 
-        let resolved = if let Some(struct_module) = struct_name.module() {
-            mangled.full_name(struct_module)
-        } else {
-            ctx.module_name()
-                .map(|m| mangled.full_name(m))
-                .filter(|n| ctx.get_function_signature(n).is_ok())
-                .unwrap_or_else(|| mangled.clone())
-        };
-        let sig = ctx.get_function_signature(&resolved)?;
-        let return_type = sig.return_type().clone();
-        let params = sig.params();
-        sig.check_arg_count(self.args.len())
-            .then_some(())
-            .ok_or_else(|| {
-                SemanticError::argument_count_mismatch(
-                    sig.param_count(),
-                    self.args.len(),
-                    resolved.inner().to_string(),
-                    span.clone(),
-                )
-            })?;
-        walk_typed_args(&params, &self.args, ctx, span).map(|typed_args| TypedExpr::Call {
-            func: resolved,
-            args: typed_args,
-            receiver: Some(Box::new(typed_receiver)),
-            typ: return_type,
-        })
+    if let Some(struct_module) = struct_name.module() {
+        mangled.full_name(struct_module)
+    } else {
+        ctx.module_name()
+            .map(|m| mangled.full_name(m))
+            .filter(|n| ctx.get_function_signature(n).is_ok())
+            .unwrap_or_else(|| mangled.clone())
     }
 }
@@ -1,3 +1,5 @@
 pub mod func;
 pub mod method;
 pub mod qualified;
+pub mod type_builtin;
+pub mod vector_builtin;
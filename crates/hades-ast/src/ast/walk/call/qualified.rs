@@ -1,9 +1,9 @@
 use crate::ast::{QualifiedCall, WalkAst};
 use crate::typed_ast::{CompilerContext, TypedExpr};
 use hades_error::{SemanticError, Span};
-use hades_tokens::Name;
+use hades_tokens::{Ident, Name};
 
-use super::func::walk_typed_args;
+use super::func::walk_call;
 
 impl WalkAst for QualifiedCall {
     type Output = TypedExpr;
@@ -11,21 +11,33 @@ impl WalkAst for QualifiedCall {
     fn walk(&self, ctx: &mut CompilerContext, span: Span) -> Result<Self::Output, SemanticError> {
         let resolved = match self.path.as_slice() {
             [qualifier] => {
-                let struct_key = Name::new(qualifier.to_string(), qualifier.span().clone())
-                    .full_name_optional(ctx.module_name());
+                let struct_key =
+                    ctx.struct_name(&Name::new(qualifier.to_string(), qualifier.span().clone()));
                 if ctx.structs().fields(&struct_key).is_some() {
-                    let mangled = self.func.mangle(qualifier);
-                    ctx.module_name()
+                    let bare_struct =
+                        Ident::new(struct_key.link_name().to_string(), qualifier.span().clone());
+                    let mangled = self.func.mangle(&bare_struct);
+                    struct_key
+                        .module()
                         .map(|m| mangled.full_name(m))
                         .filter(|n| ctx.get_function_signature(n).is_ok())
                         .unwrap_or_else(|| mangled.clone())
                 } else {
-                    self.func.full_name(qualifier.inner())
+                    ctx.through_reexports(self.func.full_name(ctx.resolve_module(qualifier)?))
                 }
             }
             [module, struct_name] => {
-                let mangled = self.func.mangle(struct_name);
-                mangled.full_name(module.inner())
+                let struct_key = ctx.through_reexports(
+                    Name::new(struct_name.to_string(), struct_name.span().clone())
+                        .full_name(ctx.resolve_module(module)?),
+                );
+                let bare_struct = Ident::new(
+                    struct_key.link_name().to_string(),
+                    struct_name.span().clone(),
+                );
+                self.func
+                    .mangle(&bare_struct)
+                    .full_name_optional(struct_key.module())
             }
             _ => {
                 return Err(SemanticError::undefined_function(
@@ -34,24 +46,22 @@ impl WalkAst for QualifiedCall {
                 ));
             }
         };
-        let sig = ctx.get_function_signature(&resolved)?;
-        let return_type = sig.return_type().clone();
-        let params = sig.params();
-        sig.check_arg_count(self.args.len())
-            .then_some(())
-            .ok_or_else(|| {
-                SemanticError::argument_count_mismatch(
-                    sig.param_count(),
-                    self.args.len(),
-                    resolved.inner().to_string(),
-                    span.clone(),
-                )
-            })?;
-        walk_typed_args(&params, &self.args, ctx, span).map(|typed_args| TypedExpr::Call {
+        let (resolved, sig, typed_args) = walk_call(
+            "function",
+            resolved,
+            &self.args,
+            &self.named_args,
+            ctx,
+            span.clone(),
+        )?;
+        if let Some(warning) = sig.deprecation_warning(self.func.inner(), span) {
+            ctx.warn(warning);
+        }
+        Ok(TypedExpr::Call {
             func: resolved,
             args: typed_args,
             receiver: None,
-            typ: return_type,
+            typ: sig.return_type,
         })
     }
 }
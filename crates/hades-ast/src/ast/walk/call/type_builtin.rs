@@ -0,0 +1,84 @@
+use crate::ast::{ArrayType, Expr, FunctionCall, Types, WalkAst};
+use crate::typed_ast::{CompilerContext, TypedExpr, TypedFieldKind, TypedValue};
+use hades_error::{SemanticError, Span};
+
+impl FunctionCall {
+    /// `sizeof`, `alignof`, `offsetof` and `type_name` take a type, so they are checked here
+    /// instead of against a signature. Codegen folds them to constants.
+    pub(super) fn walk_type_builtin(
+        &self,
+        ctx: &mut CompilerContext,
+        span: Span,
+    ) -> Result<TypedExpr, SemanticError> {
+        let name = self.func.inner();
+        let expected_args = if name == "offsetof" { 2 } else { 1 };
+        if self.args.len() != expected_args {
+            return Err(SemanticError::argument_count_mismatch(
+                expected_args,
+                self.args.len(),
+                name.to_string(),
+                span,
+            ));
+        }
+
+        let typed_type = self.args[0].walk(ctx, span.clone())?;
+        let typ = typed_type.get_type();
+        if let Types::Struct(struct_name) | Types::Array(ArrayType::StructArray(_, struct_name)) =
+            &typ
+            && !ctx.structs().is_opaque(struct_name)
+        {
+            ctx.get_struct_type(struct_name, span.clone())?;
+        }
+        if name != "type_name" {
+            ctx.check_sized(&typ, span.clone())?;
+        }
+
+        let mut args = vec![typed_type];
+        if name == "offsetof" {
+            args.push(offsetof_field(&typ, &self.args[1], ctx, span.clone())?);
+        }
+
+        let return_type = match name {
+            "type_name" => Types::String,
+            _ => Types::Int,
+        };
+
+        Ok(TypedExpr::Call {
+            func: self.func.clone(),
+            args,
+            receiver: None,
+            typ: return_type,
+        })
+    }
+}
+
+/// The field operand of `offsetof(Struct, field)`, carried to codegen by name.
+fn offsetof_field(
+    typ: &Types,
+    field: &Expr,
+    ctx: &CompilerContext,
+    span: Span,
+) -> Result<TypedExpr, SemanticError> {
+    let Types::Struct(struct_name) = typ else {
+        return Err(SemanticError::not_a_struct(typ.to_string(), span));
+    };
+    let Expr::Ident(field) = field else {
+        return Err(SemanticError::type_mismatch(
+            "field name".to_string(),
+            "expression".to_string(),
+            span,
+        ));
+    };
+
+    let fields = ctx.get_struct_type(struct_name, span.clone())?;
+    match fields.get(field) {
+        Some(TypedFieldKind::Var(_)) => Ok(TypedExpr::Value(TypedValue::String(
+            field.inner().to_string(),
+        ))),
+        _ => Err(SemanticError::unknown_field(
+            struct_name.inner().to_string(),
+            field.inner().to_string(),
+            span,
+        )),
+    }
+}
@@ -0,0 +1,119 @@
+use crate::ast::{FunctionCall, Types, VECTOR_LANES, WalkAst};
+use crate::typed_ast::{CompilerContext, TypedExpr, TypedValue};
+use hades_error::{SemanticError, Span};
+
+impl FunctionCall {
+    /// `splat(vec4f, x)` and `shuffle(a, b, i, ...)`. Codegen lowers them to a vector splat
+    /// and a `shufflevector` with a constant mask.
+    pub(super) fn walk_vector_builtin(
+        &self,
+        ctx: &mut CompilerContext,
+        span: Span,
+    ) -> Result<TypedExpr, SemanticError> {
+        let name = self.func.inner();
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.walk(ctx, span.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let return_type = match name {
+            "splat" => splat_type(&args, span)?,
+            _ => shuffle_type(&args, span)?,
+        };
+
+        Ok(TypedExpr::Call {
+            func: self.func.clone(),
+            args,
+            receiver: None,
+            typ: return_type,
+        })
+    }
+}
+
+fn splat_type(args: &[TypedExpr], span: Span) -> Result<Types, SemanticError> {
+    if args.len() != 2 {
+        return Err(SemanticError::argument_count_mismatch(
+            2,
+            args.len(),
+            "splat".to_string(),
+            span,
+        ));
+    }
+    let typ = args[0].get_type();
+    let Types::Vector(elem, _) = &typ else {
+        return Err(SemanticError::type_mismatch(
+            "vector type".to_string(),
+            typ.to_string(),
+            span,
+        ));
+    };
+    let value_type = args[1].get_type();
+    if value_type != **elem {
+        return Err(SemanticError::type_mismatch(
+            elem.to_string(),
+            value_type.to_string(),
+            span,
+        ));
+    }
+    Ok(typ)
+}
+
+fn shuffle_type(args: &[TypedExpr], span: Span) -> Result<Types, SemanticError> {
+    if args.len() < 3 {
+        return Err(SemanticError::argument_count_mismatch(
+            3,
+            args.len(),
+            "shuffle".to_string(),
+            span,
+        ));
+    }
+    let typ = args[0].get_type();
+    let Types::Vector(elem, lanes) = &typ else {
+        return Err(SemanticError::type_mismatch(
+            "vector type".to_string(),
+            typ.to_string(),
+            span,
+        ));
+    };
+    let other = args[1].get_type();
+    if other != typ {
+        return Err(SemanticError::type_mismatch(
+            typ.to_string(),
+            other.to_string(),
+            span,
+        ));
+    }
+
+    // Indices below `lanes` pick from the first vector, the rest from the second.
+    let limit = lanes * 2;
+    let mask = &args[2..];
+    for index in mask {
+        match index {
+            TypedExpr::Value(TypedValue::Number(n)) if (*n as usize) < limit => {}
+            TypedExpr::Value(TypedValue::Number(n)) => {
+                return Err(SemanticError::invalid_shuffle_index(
+                    n.to_string(),
+                    limit,
+                    span,
+                ));
+            }
+            other => {
+                return Err(SemanticError::invalid_shuffle_index(
+                    other.get_type().to_string(),
+                    limit,
+                    span,
+                ));
+            }
+        }
+    }
+    if !VECTOR_LANES.contains(&mask.len()) {
+        return Err(SemanticError::argument_count_mismatch(
+            lanes + 2,
+            args.len(),
+            "shuffle".to_string(),
+            span,
+        ));
+    }
+    Ok(Types::Vector(elem.clone(), mask.len()))
+}
@@ -1,15 +1,19 @@
 pub mod call;
 mod walk_as;
+mod walk_asm;
 mod walk_block;
 mod walk_break;
 mod walk_continue;
 mod walk_defer;
 mod walk_expr;
+mod walk_extern_type;
 mod walk_for;
+mod walk_for_each;
 mod walk_func;
 mod walk_if;
 mod walk_import;
 mod walk_let;
+mod walk_loop;
 mod walk_moduledecl;
 mod walk_null;
 mod walk_program;
@@ -28,6 +32,24 @@ pub trait WalkAst {
     fn walk(&self, ctx: &mut CompilerContext, span: Span) -> Result<Self::Output, SemanticError>;
 }
 
+/// Walks `expr` where a variadic parameter may stand on its own: as what is indexed, iterated or
+/// passed on to a call. Its name alone is rejected anywhere else.
+pub(super) fn walk_sequence_use(
+    expr: &Expr,
+    ctx: &mut CompilerContext,
+    span: Span,
+) -> Result<TypedExpr, SemanticError> {
+    match expr {
+        Expr::Ident(ident) if ctx.sequence_length(ident).is_some() => ctx
+            .get_variable_type(ident, span)
+            .map(|typ| TypedExpr::Ident {
+                ident: ident.clone(),
+                typ,
+            }),
+        _ => expr.walk(ctx, span),
+    }
+}
+
 pub(super) fn walk_possibly_null(
     expr: &Expr,
     expected: Option<Types>,
@@ -19,6 +19,18 @@ impl WalkAst for AsExpression {
         let expr = self.expr.walk(ctx, span.clone())?;
         let source_type = expr.get_type();
 
+        // Pointers convert to and from the pointer-sized `int`, and between pointee types.
+        let is_pointer_cast = matches!(
+            (&source_type, &self.target_type),
+            (Types::Pointer(_), Types::Int | Types::Pointer(_)) | (Types::Int, Types::Pointer(_))
+        );
+        if is_pointer_cast {
+            return Ok(TypedAsExpression {
+                expr: Box::new(expr),
+                target_type: ctx.qualify(&self.target_type),
+            });
+        }
+
         CONVERT_MAP
             .get(&source_type.to_string())
             .ok_or_else(|| {
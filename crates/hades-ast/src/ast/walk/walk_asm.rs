@@ -0,0 +1,66 @@
+use crate::ast::{AsmExpr, Types, WalkAst};
+use crate::typed_ast::{CompilerContext, TypedAsm, TypedAsmOutput};
+use hades_error::{SemanticError, Span};
+
+impl WalkAst for AsmExpr {
+    type Output = TypedAsm;
+
+    fn walk(&self, ctx: &mut CompilerContext, span: Span) -> Result<Self::Output, SemanticError> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|(constraint, expr)| {
+                if constraint.is_empty() || constraint.starts_with(['=', '+']) {
+                    return Err(SemanticError::invalid_asm_constraint(
+                        constraint.clone(),
+                        "input constraints cannot be empty or start with '=' or '+'",
+                        span.clone(),
+                    ));
+                }
+                let typed = expr.walk(ctx, span.clone())?;
+                check_operand(&typed.get_type(), span.clone())?;
+                Ok((constraint.clone(), typed))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let outputs = self
+            .outputs
+            .iter()
+            .map(|(constraint, target)| {
+                if !constraint.starts_with('=') {
+                    return Err(SemanticError::invalid_asm_constraint(
+                        constraint.clone(),
+                        "output constraints must start with '='",
+                        span.clone(),
+                    ));
+                }
+                let (target, typ) = target.walk_place(ctx, span.clone())?;
+                check_operand(&typ, span.clone())?;
+                Ok(TypedAsmOutput {
+                    constraint: constraint.clone(),
+                    target,
+                    typ,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(TypedAsm {
+            template: self.template.clone(),
+            inputs,
+            outputs,
+            clobbers: self.clobbers.clone(),
+        })
+    }
+}
+
+/// Operands live in registers or memory operands LLVM can address directly, so
+/// aggregates and `bool` (an `i1`) are rejected.
+fn check_operand(typ: &Types, span: Span) -> Result<(), SemanticError> {
+    match typ {
+        Types::Int | Types::Float | Types::Char | Types::String | Types::Pointer(_) => Ok(()),
+        other => Err(SemanticError::invalid_asm_operand(
+            other.source_name(),
+            span,
+        )),
+    }
+}
@@ -11,9 +11,14 @@ impl WalkAst for Block {
     ) -> Result<Self::Output, SemanticError> {
         ctx.enter_scope();
         let typed_stmts = self.stmts.walk(ctx, span)?;
+        let typed_tail = match &self.tail {
+            Some(tail) => Some(Box::new(tail.walk(ctx, tail.span.clone())?)),
+            None => None,
+        };
         ctx.exit_scope();
         Ok(TypedBlock {
             stmts: typed_stmts,
+            tail: typed_tail,
             span: self.span().clone(),
         })
     }
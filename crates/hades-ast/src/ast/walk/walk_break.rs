@@ -4,8 +4,19 @@ use hades_error::{SemanticError, Span};
 
 impl WalkAst for Break {
     type Output = TypedBreak;
-    fn walk(&self, _ctx: &mut CompilerContext, _span: Span) -> Result<Self::Output, SemanticError> {
+    fn walk(&self, ctx: &mut CompilerContext, _span: Span) -> Result<Self::Output, SemanticError> {
+        let typed_value = match &self.value {
+            Some(value) => Some(value.walk(ctx, self.span.clone())?),
+            None => None,
+        };
+
+        ctx.record_break(
+            typed_value.as_ref().map(|v| v.get_type()),
+            self.span.clone(),
+        )?;
+
         Ok(TypedBreak {
+            value: typed_value,
             span: self.span.clone(),
         })
     }
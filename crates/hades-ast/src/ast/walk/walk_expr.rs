@@ -4,13 +4,13 @@ use crate::ast::{
 };
 use crate::typed_ast::{
     CompilerContext, TypedArrayIndex, TypedAssignExpr, TypedAssignTarget, TypedBinaryExpr,
-    TypedExpr, TypedExprAst, TypedFieldAccess,
+    TypedExpr, TypedExprAst, TypedFieldAccess, TypedValue,
 };
 use hades_error::{SemanticError, Span};
 use hades_tokens::{Ident, Name};
 use indexmap::IndexMap;
 
-use super::walk_possibly_null;
+use super::{walk_possibly_null, walk_sequence_use};
 
 impl WalkAst for Expr {
     type Output = TypedExpr;
@@ -18,7 +18,11 @@ impl WalkAst for Expr {
     fn walk(&self, ctx: &mut CompilerContext, span: Span) -> Result<Self::Output, SemanticError> {
         match self {
             Expr::Null => NullExpr::new(None).walk(ctx, span),
+            Expr::Type(typ) => Ok(TypedExpr::Type(ctx.qualify(typ))),
             Expr::Value(value) => Ok(TypedExpr::Value(value.walk(ctx, span)?)),
+            Expr::Ident(ident) if ctx.sequence_length(ident).is_some() => {
+                Err(SemanticError::invalid_sequence_use(ident.to_string(), span))
+            }
             Expr::Ident(ident) => ctx
                 .get_variable_type(ident, span)
                 .map(|typ| TypedExpr::Ident {
@@ -27,12 +31,14 @@ impl WalkAst for Expr {
                 }),
             Expr::StructInit(StructInitExpr { path, fields }) => {
                 let name = match path.as_slice() {
-                    [struct_name] => Name::new(struct_name.to_string(), struct_name.span().clone())
-                        .full_name_optional(ctx.module_name()),
-                    [module, struct_name] => {
+                    [struct_name] => ctx.struct_name(&Name::new(
+                        struct_name.to_string(),
+                        struct_name.span().clone(),
+                    )),
+                    [module, struct_name] => ctx.through_reexports(
                         Name::new(struct_name.to_string(), struct_name.span().clone())
-                            .full_name(module.inner())
-                    }
+                            .full_name(ctx.resolve_module(module)?),
+                    ),
                     _ => {
                         return Err(SemanticError::undefined_struct(
                             path.last()
@@ -44,6 +50,7 @@ impl WalkAst for Expr {
                     }
                 };
                 let struct_type = ctx.get_struct_type(&name, span.clone())?;
+                ctx.check_deprecated_struct(&name, None, span.clone());
 
                 fields
                     .iter()
@@ -55,7 +62,9 @@ impl WalkAst for Expr {
                                 span.clone(),
                             )
                         })?;
+                        ctx.check_field_visible(&name, field_name, span.clone())?;
                         let expected_type = expected.get_type();
+                        ctx.check_deprecated_struct(&name, Some(field_name), span.clone());
 
                         let typed = walk_possibly_null(
                             field_expr,
@@ -65,7 +74,7 @@ impl WalkAst for Expr {
                         )?;
 
                         let typed_type = typed.get_type();
-                        if typed_type != expected_type {
+                        if !typed_type.coerces_to(&expected_type) {
                             return Err(SemanticError::type_mismatch(
                                 expected_type.to_string(),
                                 typed_type.to_string(),
@@ -104,6 +113,12 @@ impl WalkAst for Expr {
             Expr::FieldAccess(field) => field.walk(ctx, span).map(TypedExpr::FieldAccess),
             Expr::ArrayIndex(index) => index.walk(ctx, span).map(TypedExpr::ArrayIndex),
             Expr::As(as_expr) => as_expr.walk(ctx, span).map(TypedExpr::As),
+            Expr::If(if_expr) => if_expr.walk(ctx, span).map(|i| TypedExpr::If(Box::new(i))),
+            Expr::Block(block) => block.walk(ctx, span).map(|b| TypedExpr::Block(Box::new(b))),
+            Expr::Loop(loop_expr) => loop_expr
+                .walk(ctx, span)
+                .map(|l| TypedExpr::Loop(Box::new(l))),
+            Expr::Asm(asm) => asm.walk(ctx, span).map(|a| TypedExpr::Asm(Box::new(a))),
         }
     }
 }
@@ -123,59 +138,56 @@ impl WalkAst for crate::ast::ExprAst {
 impl WalkAst for AssignExpr {
     type Output = TypedAssignExpr;
     fn walk(&self, ctx: &mut CompilerContext, span: Span) -> Result<Self::Output, SemanticError> {
-        match self.target {
-            AssignTarget::Ident(ref ident) => {
-                let var_type = ctx.get_variable_type(ident, span.clone())?;
-                let typed_value =
-                    walk_possibly_null(&self.value, Some(var_type.clone()), ctx, span.clone())?;
-                ctx.infer_binary_type(&var_type, &self.op, &typed_value.get_type(), span)
-                    .map(|typ| TypedAssignExpr {
-                        target: TypedAssignTarget::Ident(ident.clone()),
-                        op: self.op.clone(),
-                        value: Box::new(typed_value),
-                        typ,
-                    })
+        let (target, target_type) = self.target.walk_place(ctx, span.clone())?;
+        let value = walk_possibly_null(&self.value, Some(target_type.clone()), ctx, span.clone())?;
+        ctx.infer_binary_type(&target_type, &self.op, &value.get_type(), span)
+            .map(|typ| TypedAssignExpr {
+                target,
+                op: self.op.clone(),
+                value: Box::new(value),
+                typ,
+            })
+    }
+}
+
+impl AssignTarget {
+    /// Types the place being written to, along with the type of the value it holds.
+    pub(crate) fn walk_place(
+        &self,
+        ctx: &mut CompilerContext,
+        span: Span,
+    ) -> Result<(TypedAssignTarget, Types), SemanticError> {
+        match self {
+            AssignTarget::Ident(ident) => {
+                if ctx.sequence_length(ident).is_some() {
+                    return Err(SemanticError::invalid_sequence_use(ident.to_string(), span));
+                }
+                let var_type = ctx.get_variable_type(ident, span)?;
+                Ok((TypedAssignTarget::Ident(ident.clone()), var_type))
             }
-            AssignTarget::FieldAccess(ref field) => {
-                let field = field.walk(ctx, span.clone())?;
-                let value = walk_possibly_null(
-                    &self.value,
-                    Some(field.field_type.clone()),
-                    ctx,
-                    span.clone(),
-                )?;
-                ctx.infer_binary_type(&field.field_type, &self.op, &value.get_type(), span)
-                    .map(|typ| TypedAssignExpr {
-                        target: TypedAssignTarget::FieldAccess(field.clone()),
-                        op: self.op.clone(),
-                        value: Box::new(value),
-                        typ,
-                    })
+            AssignTarget::FieldAccess(field) => {
+                let field = field.walk(ctx, span)?;
+                let field_type = field.field_type.clone();
+                Ok((TypedAssignTarget::FieldAccess(field), field_type))
             }
-            AssignTarget::ArrayIndex(ref index) => {
-                let typed_index = index.walk(ctx, span.clone())?;
-                let elem_type = typed_index.typ.get_array_elem_type();
-                let value = self.value.walk(ctx, span.clone())?;
-                ctx.infer_binary_type(&elem_type, &self.op, &value.get_type(), span)
-                    .map(|typ| TypedAssignExpr {
-                        target: TypedAssignTarget::ArrayIndex(typed_index),
-                        op: self.op.clone(),
-                        value: Box::new(value),
-                        typ,
-                    })
+            AssignTarget::ArrayIndex(index) => {
+                let typed_index = index.walk(ctx, span)?;
+                let elem_type = typed_index.elem_type();
+                Ok((TypedAssignTarget::ArrayIndex(typed_index), elem_type))
             }
-            AssignTarget::Deref(ref inner_expr) => {
+            AssignTarget::Deref(inner_expr) => {
                 let typed_inner = inner_expr.walk(ctx, span.clone())?;
                 let pointee_type = typed_inner.get_deref_type(span.clone())?;
-                let value =
-                    walk_possibly_null(&self.value, Some(pointee_type.clone()), ctx, span.clone())?;
-                ctx.infer_binary_type(&pointee_type, &self.op, &value.get_type(), span)
-                    .map(|typ| TypedAssignExpr {
-                        target: TypedAssignTarget::Deref(Box::new(typed_inner)),
-                        op: self.op.clone(),
-                        value: Box::new(value),
-                        typ,
-                    })
+                if !ctx.is_sized(&pointee_type) {
+                    return Err(SemanticError::invalid_dereference(
+                        typed_inner.get_type().to_string(),
+                        span,
+                    ));
+                }
+                Ok((
+                    TypedAssignTarget::Deref(Box::new(typed_inner)),
+                    pointee_type,
+                ))
             }
         }
     }
@@ -185,10 +197,27 @@ impl WalkAst for ArrayIndexExpr {
     type Output = TypedArrayIndex;
 
     fn walk(&self, ctx: &mut CompilerContext, span: Span) -> Result<Self::Output, SemanticError> {
-        let typed_expr = self.expr.walk(ctx, span.clone())?;
+        let typed_expr = walk_sequence_use(&self.expr, ctx, span.clone())?;
         let expr_type = typed_expr.get_type();
+        match &expr_type {
+            Types::Array(_) | Types::Vector(..) => {}
+            Types::Pointer(pointee) => ctx.check_sized(pointee, span.clone())?,
+            _ => {
+                return Err(SemanticError::type_mismatch(
+                    "Array, Pointer or Vector".to_string(),
+                    expr_type.to_string(),
+                    span,
+                ));
+            }
+        }
         let index = self.index.walk(ctx, span.clone())?;
         let index_type = index.get_type();
+        if let (Types::Vector(_, lanes), TypedExpr::Value(TypedValue::Number(n))) =
+            (&expr_type, &index)
+            && !(0..*lanes as i64).contains(n)
+        {
+            return Err(SemanticError::lane_out_of_range(*n, *lanes, span));
+        }
 
         (Types::Int == index_type)
             .then(|| TypedArrayIndex {
@@ -243,7 +272,7 @@ impl WalkAst for FieldAccessExpr {
                         })
                 })
         };
-        match &strc {
+        let field = match &strc {
             Types::Struct(struct_name) | Types::Array(ArrayType::StructArray(_, struct_name)) => {
                 walk_struct(struct_name)
             }
@@ -253,14 +282,18 @@ impl WalkAst for FieldAccessExpr {
                 other => Err(SemanticError::type_mismatch(
                     "Struct or pointer to struct".to_string(),
                     other.to_string(),
-                    span,
+                    span.clone(),
                 )),
             },
             _ => Err(SemanticError::type_mismatch(
                 "Struct".to_string(),
                 strc.to_string(),
-                span,
+                span.clone(),
             )),
-        }
+        }?;
+        let struct_name = field.struct_type.unwrap_struct_name();
+        ctx.check_field_visible(struct_name, &field.field, span.clone())?;
+        ctx.check_deprecated_struct(struct_name, Some(&field.field), span);
+        Ok(field)
     }
 }
@@ -0,0 +1,20 @@
+use crate::ast::{ExternType, WalkAst};
+use crate::typed_ast::{CompilerContext, TypedExternType};
+use hades_error::{SemanticError, Span};
+
+impl WalkAst for ExternType {
+    type Output = TypedExternType;
+
+    fn walk(&self, ctx: &mut CompilerContext, _span: Span) -> Result<Self::Output, SemanticError> {
+        let name = self.name.full_name_optional(ctx.module_name());
+        ctx.insert_opaque(name.clone(), self.span.clone())?;
+        if !self.public {
+            ctx.mark_private(name.clone(), self.name.span().clone());
+        }
+
+        Ok(TypedExternType {
+            name,
+            span: self.span.clone(),
+        })
+    }
+}
@@ -13,7 +13,9 @@ impl WalkAst for For {
         let typed_cond = self.cond.walk(ctx, self.span.clone())?;
         let typed_update = self.update.walk(ctx, self.span.clone())?;
 
+        ctx.enter_loop(false);
         let typed_body = self.body.walk(ctx, self.span.clone())?;
+        ctx.exit_loop();
         ctx.exit_scope();
         Ok(TypedFor {
             init: typed_init,
@@ -0,0 +1,245 @@
+use super::call::method::{method_name, walk_method};
+use super::walk_sequence_use;
+use crate::ast::{
+    ArrayIndexExpr, AssignExpr, AssignTarget, BinaryExpr, Block, Break, CallKind, Expr, ExprAst,
+    For, ForEach, FunctionCall, If, Let, MethodCall, Stmt, Types, Value, WalkAst, While,
+};
+use crate::typed_ast::{
+    CompilerContext, TypedBlock, TypedExpr, TypedExprAst, TypedLet, TypedProgram, TypedStmt,
+};
+use hades_error::{SemanticError, Span};
+use hades_tokens::{Ident, Name, Op};
+use indexmap::IndexMap;
+
+/// A for-each loop is desugared into a block built from the loops we already lower.
+///
+/// Arrays and variadic parameters are walked by index. Structs follow the iteration protocol:
+/// `iter()` returns an iterator whose `next(self: &Self)` yields a pointer to the next
+/// element, or `null` once it is exhausted.
+///
+/// The iterable is walked once, and bound to a hidden local when the loop needs to name it.
+impl WalkAst for ForEach {
+    type Output = TypedBlock;
+
+    fn walk(&self, ctx: &mut CompilerContext, _span: Span) -> Result<Self::Output, SemanticError> {
+        let iterable = walk_sequence_use(&self.iterable.expr, ctx, self.iterable.span.clone())?;
+        let is_sequence = matches!(
+            &iterable,
+            TypedExpr::Ident { ident, .. } if ctx.sequence_length(ident).is_some()
+        );
+
+        ctx.enter_scope();
+        let stmts = match iterable.get_type() {
+            Types::Array(_) => self.walk_array(iterable, ctx),
+            Types::Pointer(_) if is_sequence => self.walk_array(iterable, ctx),
+            Types::Struct(_) => self.walk_iterator(iterable, ctx),
+            other => Err(SemanticError::not_iterable(
+                other.to_string(),
+                self.iterable.span.clone(),
+            )),
+        };
+        ctx.exit_scope();
+
+        Ok(TypedBlock {
+            stmts: TypedProgram::new(stmts?),
+            tail: None,
+            span: self.span.clone(),
+        })
+    }
+}
+
+impl ForEach {
+    /// ```text
+    /// let arr = <iterable>;
+    /// for let i = 0; i < len(arr); i += 1 { let x = arr[i]; <body> }
+    /// ```
+    fn walk_array(
+        &self,
+        iterable: TypedExpr,
+        ctx: &mut CompilerContext,
+    ) -> Result<Vec<TypedStmt>, SemanticError> {
+        let mut stmts = Vec::new();
+
+        let array = match iterable {
+            TypedExpr::Ident { ident, .. } => ident,
+            other => {
+                let array = ctx.hidden_ident("for.array", self.span.clone());
+                stmts.push(self.bind(array.clone(), other, ctx));
+                array
+            }
+        };
+        let index = ctx.hidden_ident("for.index", self.span.clone());
+
+        let element = Expr::ArrayIndex(ArrayIndexExpr {
+            expr: Box::new(Expr::Ident(array.clone())),
+            index: Box::new(Expr::Ident(index.clone())),
+        });
+        let length = Expr::Call(CallKind::Function(FunctionCall {
+            func: Name::new("len".to_string(), self.span.clone()),
+            args: vec![Expr::Ident(array)],
+            named_args: IndexMap::new(),
+        }));
+
+        let the_loop = Stmt::For(For {
+            init: Let {
+                name: index.clone(),
+                declared_type: Some(Types::Int),
+                value: self.expr_ast(Expr::Value(Value::Number(0))),
+                span: self.span.clone(),
+            },
+            cond: BinaryExpr {
+                left: Box::new(Expr::Ident(index.clone())),
+                op: Op::Less,
+                right: Box::new(length),
+            },
+            update: AssignExpr {
+                target: AssignTarget::Ident(index),
+                op: Op::PlusEqual,
+                value: Box::new(Expr::Value(Value::Number(1))),
+            },
+            body: self.body_with(vec![self.let_stmt(self.binding.clone(), element)]),
+            span: self.span.clone(),
+        });
+        stmts.push(the_loop.walk(ctx, self.span.clone())?);
+
+        Ok(stmts)
+    }
+
+    /// ```text
+    /// let it = <iterable>.iter();
+    /// while true { let item = it.next(); if item == null { break; } let x = *item; <body> }
+    /// ```
+    fn walk_iterator(
+        &self,
+        iterable: TypedExpr,
+        ctx: &mut CompilerContext,
+    ) -> Result<Vec<TypedStmt>, SemanticError> {
+        let span = self.iterable.span.clone();
+        let iterable_type = iterable.get_type();
+
+        let iter_call = walk_method(
+            iterable,
+            &Name::new("iter".to_string(), span.clone()),
+            &[],
+            &IndexMap::new(),
+            ctx,
+            span.clone(),
+        )
+        .map_err(|_| SemanticError::not_iterable(iterable_type.to_string(), span.clone()))?;
+
+        let iterator_type = iter_call.get_type();
+        let Types::Struct(iterator_struct) = &iterator_type else {
+            return Err(SemanticError::invalid_iterator(
+                iterator_type.to_string(),
+                span,
+            ));
+        };
+        let next = method_name(
+            iterator_struct,
+            &Name::new("next".to_string(), span.clone()),
+            ctx,
+            &span,
+        );
+        let yields_pointers = ctx.get_function_signature(&next).is_ok_and(|sig| {
+            sig.param_count() == 0 && matches!(sig.return_type, Types::Pointer(_))
+        });
+        if !yields_pointers {
+            return Err(SemanticError::invalid_iterator(
+                iterator_type.to_string(),
+                span,
+            ));
+        }
+
+        let iterator = ctx.hidden_ident("for.iter", self.span.clone());
+        let item = ctx.hidden_ident("for.item", self.span.clone());
+
+        let exhausted = If {
+            cond: self.expr_ast(Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Ident(item.clone())),
+                op: Op::EqualEqual,
+                right: Box::new(Expr::Null),
+            })),
+            then_branch: Block::new(
+                vec![Stmt::Break(Break {
+                    value: None,
+                    span: self.span.clone(),
+                })]
+                .into(),
+                self.span.clone(),
+            ),
+            else_branch: None,
+            span: self.span.clone(),
+        };
+        let element = Expr::Unary {
+            op: Op::Deref,
+            expr: Box::new(Expr::Ident(item.clone())),
+        };
+
+        let body = self.body_with(vec![
+            self.let_stmt(
+                item,
+                method_call(Expr::Ident(iterator.clone()), "next", &span),
+            ),
+            Stmt::If(exhausted),
+            self.let_stmt(self.binding.clone(), element),
+        ]);
+
+        let bound = self.bind(iterator, iter_call, ctx);
+        let the_loop = Stmt::While(While {
+            cond: Expr::Value(Value::Boolean(true)),
+            body,
+            span: self.span.clone(),
+        })
+        .walk(ctx, self.span.clone())?;
+        Ok(vec![bound, the_loop])
+    }
+
+    /// Binds an already walked value to a hidden local.
+    fn bind(&self, name: Ident, value: TypedExpr, ctx: &mut CompilerContext) -> TypedStmt {
+        let typ = value.get_type();
+        ctx.insert_variable(name.clone(), typ.clone());
+        TypedStmt::Let(TypedLet {
+            name,
+            typ,
+            value: TypedExprAst {
+                expr: value,
+                span: self.iterable.span.clone(),
+            },
+            span: self.span.clone(),
+        })
+    }
+
+    fn body_with(&self, mut stmts: Vec<Stmt>) -> Block {
+        stmts.extend(self.body.stmts.iter().cloned());
+        Block::with_tail(
+            stmts.into(),
+            self.body.tail.as_deref().cloned(),
+            self.body.span.clone(),
+        )
+    }
+
+    fn let_stmt(&self, name: Ident, value: Expr) -> Stmt {
+        Stmt::Let(Let {
+            name,
+            declared_type: None,
+            value: self.expr_ast(value),
+            span: self.span.clone(),
+        })
+    }
+
+    fn expr_ast(&self, expr: Expr) -> ExprAst {
+        ExprAst {
+            expr,
+            span: self.span.clone(),
+        }
+    }
+}
+
+fn method_call(receiver: Expr, name: &str, span: &Span) -> Expr {
+    Expr::Call(CallKind::Method(MethodCall {
+        receiver: Box::new(receiver),
+        func: Name::new(name.to_string(), span.clone()),
+        args: vec![],
+        named_args: IndexMap::new(),
+    }))
+}
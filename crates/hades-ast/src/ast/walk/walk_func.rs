@@ -1,6 +1,8 @@
-use crate::ast::{FuncBody, FuncDef, ReceiverKind, Types, WalkAst};
+use super::walk_possibly_null;
+use crate::ast::{FuncBody, FuncDef, ReceiverKind, Types, WalkAst, is_reserved_builtin};
 use crate::typed_ast::{
-    CompilerContext, FunctionSignature, TypedBlock, TypedFuncDef, TypedReceiver, TypedStmt,
+    CompilerContext, FunctionSignature, Params, TypedBlock, TypedExpr, TypedFuncDef, TypedReceiver,
+    TypedStmt,
 };
 use hades_common::consts::ENTRY_POINT;
 use hades_error::SemanticError;
@@ -8,12 +10,19 @@ use hades_tokens::{Ident, Name, ParamKind};
 use indexmap::IndexMap;
 
 impl FuncDef {
-    pub fn register(&self, ctx: &mut CompilerContext) -> Result<(), SemanticError> {
+    /// Returns the name the function is registered under, which an overload mangles.
+    pub fn register(&self, ctx: &mut CompilerContext) -> Result<Name, SemanticError> {
+        if self.receiver.is_none() && is_reserved_builtin(self.name.link_name()) {
+            return Err(SemanticError::reserved_builtin(
+                self.name.link_name().to_string(),
+                self.name.span().clone(),
+            ));
+        }
         let name = self.full_name(ctx);
         let params_map = self
             .params
             .iter()
-            .map(|(k, v)| (k.clone(), v.qualify(ctx.module_name())))
+            .map(|(k, v)| (k.clone(), ctx.qualify(v)))
             .collect::<IndexMap<_, _>>();
         let receiver = self.receiver.as_ref().map(|r| {
             let qualified_struct = r.struct_name.full_name_optional(ctx.module_name());
@@ -28,20 +37,140 @@ impl FuncDef {
                 },
             }
         });
-        let qualified_return = self.return_type.qualify(ctx.module_name());
-        let sig = match &self.body {
-            FuncBody::Extern { variadic } => {
-                FunctionSignature::new_extern(params_map, qualified_return, *variadic)
+        for typ in params_map.values() {
+            ctx.check_sized(typ, self.span.clone())?;
+        }
+        let variadic = self
+            .variadic
+            .as_ref()
+            .map(|(name, typ)| (name.clone(), ctx.qualify(typ)));
+        if let Some((name, element)) = &variadic {
+            if !element.is_array_element() {
+                return Err(SemanticError::invalid_variadic_type(
+                    element.source_name(),
+                    name.span().clone(),
+                ));
+            }
+            ctx.check_sized(element, name.span().clone())?;
+            // Positional arguments fill the parameters before the variadic one first, so a
+            // default there would only apply to calls passing nothing to the variadic one.
+            if let Some(param) = self.defaults.keys().next() {
+                return Err(SemanticError::default_before_variadic(
+                    param.to_string(),
+                    name.to_string(),
+                    param.span().clone(),
+                ));
+            }
+        }
+        let qualified_return = ctx.qualify(&self.return_type);
+        if qualified_return != Types::Void {
+            ctx.check_sized(&qualified_return, self.span.clone())?;
+        }
+        if self.export {
+            if self.variadic.is_some() {
+                return Err(SemanticError::variadic_export(
+                    self.name.link_name().to_string(),
+                    self.span.clone(),
+                ));
             }
+            self.check_exportable(params_map.values().chain([&qualified_return]))?;
+        }
+        let mut sig = match &self.body {
+            FuncBody::Extern {
+                variadic, widths, ..
+            } => FunctionSignature::new_extern(
+                params_map,
+                qualified_return,
+                *variadic,
+                widths.clone(),
+            ),
             FuncBody::Intrinsic(llvm_name) => {
                 FunctionSignature::new_intrinsic(params_map, qualified_return, llvm_name.clone())
             }
             FuncBody::Block(_) => FunctionSignature::new(params_map, qualified_return, receiver),
         };
-        ctx.register_function(name, sig)
+        if let Some((name, element)) = variadic {
+            sig.params = Params::sequence(sig.to_fixed_params(), name, element);
+        }
+        sig.defaults = self.typed_defaults(ctx, &sig)?;
+        sig.export = self.export;
+        sig.attributes = self.attributes.clone();
+        let name = if ctx.is_overloaded(&name) {
+            if !sig.can_overload() {
+                return Err(SemanticError::redefined_function(
+                    name.inner().to_string(),
+                    self.name.span().clone(),
+                ));
+            }
+            sig.overload(name)
+        } else {
+            name
+        };
+        let overload_of = sig.overload_of.clone();
+        ctx.register_function(name.clone(), sig)?;
+        // `export fn` is already visible to every C caller, so it needs no `pub`.
+        if !self.public && !self.export {
+            ctx.mark_private(name.clone(), self.name.span().clone());
+            // Callers name an overload by the name it shares, so that is where they look.
+            if let Some(source) = overload_of {
+                ctx.mark_private(source, self.name.span().clone());
+            }
+        }
+        Ok(name)
+    }
+
+    /// Defaults are walked once, here, and copied into every call that leaves them out, so only
+    /// literals are allowed: they mean the same in the caller's module as in this one.
+    fn typed_defaults(
+        &self,
+        ctx: &mut CompilerContext,
+        sig: &FunctionSignature,
+    ) -> Result<IndexMap<Ident, TypedExpr>, SemanticError> {
+        let params = sig.params();
+        let mut defaults = IndexMap::new();
+        for (i, param) in params.names().enumerate() {
+            let Some(value) = self.defaults.get(param) else {
+                continue;
+            };
+            if !value.is_literal() {
+                return Err(SemanticError::non_literal_default(
+                    param.to_string(),
+                    param.span().clone(),
+                ));
+            }
+            let expected = params.type_at(i).cloned();
+            let typed = walk_possibly_null(value, expected.clone(), ctx, param.span().clone())?;
+            if !params.type_match(i, &typed.get_type()) {
+                return Err(SemanticError::type_mismatch(
+                    expected.map(|t| t.to_string()).unwrap_or_default(),
+                    typed.get_type().to_string(),
+                    param.span().clone(),
+                ));
+            }
+            defaults.insert(param.clone(), typed);
+        }
+        Ok(defaults)
     }
 
-    fn full_name(&self, ctx: &CompilerContext) -> Name {
+    /// Exported functions are called with the C calling convention, which Hades only
+    /// follows for scalars and pointers.
+    fn check_exportable<'a>(
+        &self,
+        types: impl Iterator<Item = &'a Types>,
+    ) -> Result<(), SemanticError> {
+        for typ in types {
+            if matches!(typ, Types::Struct(_) | Types::Array(_)) {
+                return Err(SemanticError::unexportable_type(
+                    self.name.link_name().to_string(),
+                    typ.source_name(),
+                    self.span.clone(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn full_name(&self, ctx: &CompilerContext) -> Name {
         match &self.receiver {
             Some(r) => {
                 let bare_struct = Ident::new(
@@ -69,21 +198,34 @@ impl WalkAst for FuncDef {
         ctx: &mut CompilerContext,
         _span: hades_error::Span,
     ) -> Result<Self::Output, SemanticError> {
-        let name = self.full_name(ctx);
-
-        if self.receiver.is_none() {
-            self.register(ctx)?;
-        }
+        let name = match self.receiver {
+            Some(_) => self.full_name(ctx),
+            None => self.register(ctx)?,
+        };
 
         let sig = ctx.get_function_signature(&name)?.clone();
+        let receiver_private = sig
+            .receiver
+            .as_ref()
+            .is_some_and(|r| ctx.is_private(&r.struct_name));
+        if self.public && !receiver_private {
+            let item = format!("function {}", self.name.link_name());
+            for typ in sig.params.types().chain([&sig.return_type]) {
+                ctx.check_public_type(&item, typ, self.name.span().clone())?;
+            }
+        }
 
         match &self.body {
-            FuncBody::Extern { .. } => {
-                if !ctx.is_stdlib() {
-                    return Err(SemanticError::extern_outside_stdlib(
-                        self.name.link_name().to_string(),
-                        self.span.clone(),
-                    ));
+            FuncBody::Extern { link, .. } => {
+                match link {
+                    Some(library) => ctx.add_link(library.clone()),
+                    None if !ctx.is_stdlib() && !ctx.is_c_header() => {
+                        return Err(SemanticError::extern_outside_stdlib(
+                            self.name.link_name().to_string(),
+                            self.span.clone(),
+                        ));
+                    }
+                    None => {}
                 }
                 Ok(TypedFuncDef {
                     name,
@@ -107,7 +249,7 @@ impl WalkAst for FuncDef {
                 })
             }
             FuncBody::Block(block) => {
-                ctx.set_current_function(name.clone(), self.return_type.qualify(ctx.module_name()));
+                ctx.set_current_function(name.clone(), ctx.qualify(&self.return_type));
 
                 for (param, declared_type) in &self.params {
                     let resolved_type = match param {
@@ -118,15 +260,24 @@ impl WalkAst for FuncDef {
                                 })?
                                 .typ
                         }
-                        ParamKind::Ident(_) => declared_type.qualify(ctx.module_name()),
+                        ParamKind::Ident(_) => ctx.qualify(declared_type),
                     };
                     ctx.insert_variable(param.name(), resolved_type);
                 }
+                if let Some((name, element)) = &self.variadic {
+                    ctx.insert_sequence(name.clone(), ctx.qualify(element));
+                }
 
                 let typed_body = block.walk(ctx, self.span.clone())?;
 
                 if !self.return_type.eq(&Types::Void) {
-                    check_return_path(&typed_body)?;
+                    match &typed_body.tail {
+                        Some(tail) if !tail.expr.diverges() => {
+                            ctx.check_return_type(tail.get_type(), tail.span.clone())?
+                        }
+                        Some(_) => {}
+                        None => check_return_path(&typed_body)?,
+                    }
                 }
 
                 ctx.exit_function();
@@ -146,6 +297,7 @@ fn check_return_path(body: &TypedBlock) -> Result<(), SemanticError> {
     for stmt in &body.stmts {
         match stmt {
             TypedStmt::Return(_) => return Ok(()),
+            TypedStmt::TypedExpr(expr) if expr.expr.diverges() => return Ok(()),
             TypedStmt::If(if_stmt) => {
                 if check_return_path(&if_stmt.then_branch).is_ok()
                     && let Some(else_branch) = &if_stmt.else_branch
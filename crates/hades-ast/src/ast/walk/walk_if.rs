@@ -1,7 +1,7 @@
 use crate::ast::If;
 use crate::ast::{Types, WalkAst};
-use crate::typed_ast::{CompilerContext, TypedIf};
-use hades_error::SemanticError;
+use crate::typed_ast::{CompilerContext, TypedBlock, TypedIf};
+use hades_error::{SemanticError, Span};
 
 impl WalkAst for If {
     type Output = TypedIf;
@@ -25,11 +25,52 @@ impl WalkAst for If {
             None => None,
         };
 
+        let typ = match &typed_else {
+            Some(typed_else) => branch_type(&typed_then, typed_else, self.span.clone())?,
+            None => {
+                let then_type = typed_then.get_type();
+                if then_type != Types::Void && !typed_then.diverges() {
+                    return Err(SemanticError::if_without_else(
+                        then_type.to_string(),
+                        self.span.clone(),
+                    ));
+                }
+                Types::Void
+            }
+        };
+
         Ok(TypedIf {
             cond: typed_cond,
             then_branch: typed_then,
             else_branch: typed_else,
+            typ,
             span: self.span.clone(),
         })
     }
 }
+
+/// The value type of an `if`/`else`. A branch that never falls through
+/// takes the type of the other one.
+fn branch_type(
+    then_branch: &TypedBlock,
+    else_branch: &TypedBlock,
+    span: Span,
+) -> Result<Types, SemanticError> {
+    if then_branch.diverges() {
+        return Ok(else_branch.get_type());
+    }
+    if else_branch.diverges() {
+        return Ok(then_branch.get_type());
+    }
+
+    let then_type = then_branch.get_type();
+    let else_type = else_branch.get_type();
+    if then_type != else_type {
+        return Err(SemanticError::type_mismatch(
+            then_type.to_string(),
+            else_type.to_string(),
+            span,
+        ));
+    }
+    Ok(then_type)
+}
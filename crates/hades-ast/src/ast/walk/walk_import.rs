@@ -1,17 +1,78 @@
-use crate::ast::{Import, WalkAst};
-use crate::typed_ast::{CompilerContext, TypedImport};
+use crate::ast::{Import, ImportItems, ImportPrefix, WalkAst};
+use crate::typed_ast::{CompilerContext, ModulePath, TypedImport};
+use hades_tokens::{Ident, Name};
 
 impl WalkAst for Import {
     type Output = TypedImport;
     fn walk(
         &self,
-        _ctx: &mut CompilerContext,
+        ctx: &mut CompilerContext,
         _span: hades_error::Span,
     ) -> Result<Self::Output, hades_error::SemanticError> {
+        let module = self.module_key(ctx);
+        ctx.import_module(&module);
+        if let Some(alias) = &self.alias {
+            ctx.alias_module(alias, &module)?;
+        } else if module != self.module && !matches!(self.prefix, ImportPrefix::C) {
+            let last = Ident::new(self.module.clone(), self.span.clone());
+            ctx.alias_module(&last, &module)?;
+        }
+        match &self.items {
+            ImportItems::Module => {
+                if self.public {
+                    for item in ctx.module_items(&module) {
+                        let local = Ident::new(item.link_name().to_string(), self.span.clone());
+                        let item = ctx.through_reexports(item);
+                        ctx.reexport(&local, item)?;
+                    }
+                }
+            }
+            ImportItems::Names(names) => {
+                for name in names {
+                    let item = Name::new(name.name.inner().to_string(), name.name.span().clone())
+                        .full_name(&module);
+                    let item = ctx.through_reexports(item);
+                    ctx.check_importable(&item, &module, name.name.span().clone())?;
+                    if self.public {
+                        ctx.reexport(name.local(), item.clone())?;
+                    }
+                    ctx.import_item(name.local(), item, false)?;
+                }
+            }
+            ImportItems::Glob => {
+                for item in ctx.module_items(&module) {
+                    let local = Ident::new(item.link_name().to_string(), self.span.clone());
+                    let item = ctx.through_reexports(item);
+                    if self.public {
+                        ctx.reexport(&local, item.clone())?;
+                    }
+                    ctx.import_item(&local, item, true)?;
+                }
+            }
+        }
         Ok(TypedImport {
-            module: self.module.clone(),
+            module,
             prefix: self.prefix.clone(),
             span: self.span.clone(),
         })
     }
 }
+
+impl Import {
+    /// The name the imported module's items are mangled under: `net.http` for
+    /// `import self::net::http`, or `geometry$shapes` for a dependency's module.
+    fn module_key(&self, ctx: &CompilerContext) -> String {
+        let mut segments = self.parents.clone();
+        segments.push(self.module.clone());
+        let path = match (&self.prefix, ctx.package()) {
+            (ImportPrefix::Local, Some(package)) => {
+                ModulePath::Package(package.to_string(), segments)
+            }
+            (ImportPrefix::Local, None) => ModulePath::Local(segments),
+            (ImportPrefix::Package(package), _) => ModulePath::Package(package.clone(), segments),
+            (ImportPrefix::C, _) => ModulePath::C(self.module.clone()),
+            (ImportPrefix::Std, _) => return self.module.clone(),
+        };
+        path.name().into_owned()
+    }
+}
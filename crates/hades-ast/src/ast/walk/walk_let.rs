@@ -13,9 +13,7 @@ impl WalkAst for Let {
 
         let typed_expr = walk_possibly_null(
             &self.value.expr,
-            self.declared_type
-                .as_ref()
-                .map(|t| t.qualify(ctx.module_name())),
+            self.declared_type.as_ref().map(|t| ctx.qualify(t)),
             ctx,
             span.clone(),
         )?;
@@ -23,8 +21,8 @@ impl WalkAst for Let {
 
         let final_type = match self.declared_type.as_ref() {
             Some(declared) => {
-                let qualified = declared.qualify(ctx.module_name());
-                if qualified != inferred_type {
+                let qualified = ctx.qualify(declared);
+                if !inferred_type.coerces_to(&qualified) {
                     return Err(SemanticError::type_mismatch(
                         qualified.to_string(),
                         inferred_type.to_string(),
@@ -43,6 +41,7 @@ impl WalkAst for Let {
             ));
         }
 
+        ctx.check_sized(&final_type, span.clone())?;
         ctx.insert_variable(name.clone(), final_type.clone());
         Ok(TypedLet {
             name: name.clone(),
@@ -0,0 +1,19 @@
+use crate::ast::{Loop, Types, WalkAst};
+use crate::typed_ast::{CompilerContext, TypedLoop};
+use hades_error::{SemanticError, Span};
+
+impl WalkAst for Loop {
+    type Output = TypedLoop;
+    fn walk(&self, ctx: &mut CompilerContext, _span: Span) -> Result<Self::Output, SemanticError> {
+        ctx.enter_loop(true);
+        let typed_body = self.body.walk(ctx, self.span.clone())?;
+        let frame = ctx.exit_loop();
+
+        Ok(TypedLoop {
+            body: typed_body,
+            typ: frame.value.unwrap_or(Types::Void),
+            breaks: frame.breaks,
+            span: self.span.clone(),
+        })
+    }
+}
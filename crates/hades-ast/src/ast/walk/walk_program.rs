@@ -1,6 +1,7 @@
-use crate::ast::{Program, WalkAst};
+use crate::ast::{Program, Stmt, WalkAst};
 use crate::typed_ast::{CompilerContext, TypedProgram};
 use hades_error::SemanticError;
+use indexmap::IndexSet;
 
 impl WalkAst for Program {
     type Output = TypedProgram;
@@ -17,3 +18,39 @@ impl WalkAst for Program {
         Ok(crate::typed_ast::TypedProgram::new(typed_stmts))
     }
 }
+
+impl Program {
+    /// Registers the imports, structs and function signatures without checking any bodies, so
+    /// that modules importing each other can see one another's items. Errors are left for the
+    /// full walk to report.
+    pub fn declare(&self, ctx: &mut CompilerContext) {
+        for stmt in self.iter() {
+            let _ = match stmt {
+                Stmt::Import(import) => import.walk(ctx, import.span.clone()).map(drop),
+                Stmt::StructDef(def) => def.register(ctx).map(drop),
+                Stmt::ExternType(ext) => ext.walk(ctx, ext.span.clone()).map(drop),
+                Stmt::FuncDef(func) => func.register(ctx).map(drop),
+                _ => Ok(()),
+            };
+        }
+    }
+
+    /// Marks the names several of this module's functions are declared under, so that each of
+    /// them is registered as an overload whichever comes first. Runs before both
+    /// [`Program::declare`] and the full walk.
+    pub fn mark_overloads(&self, ctx: &mut CompilerContext) {
+        let mut declared = IndexSet::new();
+        let mut overloaded = IndexSet::new();
+        for stmt in self.iter() {
+            if let Stmt::FuncDef(func) = stmt
+                && func.receiver.is_none()
+            {
+                let name = func.full_name(ctx);
+                if !declared.insert(name.clone()) {
+                    overloaded.insert(name);
+                }
+            }
+        }
+        ctx.set_overloaded(overloaded);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::ast::{Return, Types, WalkAst};
-use crate::typed_ast::{CompilerContext, TypedReturn};
+use crate::typed_ast::{CompilerContext, TypedExprAst, TypedReturn};
+
+use super::walk_possibly_null;
 
 impl WalkAst for Return {
     type Output = TypedReturn;
@@ -11,7 +13,13 @@ impl WalkAst for Return {
         let expr = self.expr.as_ref();
         let span = self.span.clone();
         let typed_expr = match expr {
-            Some(e) => Some(e.walk(ctx, span.clone())?),
+            Some(e) => {
+                let expected = ctx.current_function().map(|(_, typ)| typ.clone());
+                Some(TypedExprAst {
+                    expr: walk_possibly_null(&e.expr, expected, ctx, span.clone())?,
+                    span: e.span.clone(),
+                })
+            }
             None => None,
         };
 
@@ -17,7 +17,9 @@ impl WalkAst for Stmt {
             Stmt::If(i) => i.walk(ctx, span).map(TypedStmt::If),
             Stmt::While(whil) => whil.walk(ctx, span).map(TypedStmt::While),
             Stmt::For(fo) => fo.walk(ctx, span).map(|f| TypedStmt::For(Box::new(f))),
+            Stmt::ForEach(each) => each.walk(ctx, span).map(TypedStmt::Block),
             Stmt::StructDef(st) => st.walk(ctx, span).map(TypedStmt::StructDef),
+            Stmt::ExternType(ext) => ext.walk(ctx, span).map(TypedStmt::ExternType),
             Stmt::FuncDef(f) => f.walk(ctx, span).map(TypedStmt::FuncDef),
             Stmt::Block(block) => block.walk(ctx, span).map(TypedStmt::Block),
             Stmt::Return(ret) => ret.walk(ctx, span).map(TypedStmt::Return),
@@ -25,6 +27,14 @@ impl WalkAst for Stmt {
             Stmt::Import(import) => import.walk(ctx, span).map(TypedStmt::Import),
             Stmt::Break(b) => b.walk(ctx, span).map(TypedStmt::Break),
             Stmt::Defer(d) => d.walk(ctx, span).map(TypedStmt::Defer),
+            Stmt::Cfg(c) => Err(SemanticError::unevaluated_cfg(
+                c.cfgs
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                c.span.clone(),
+            )),
         }
     }
 }
@@ -1,6 +1,7 @@
-use crate::ast::{FieldKind, StructDef, WalkAst};
+use crate::ast::{Attribute, FieldKind, StructDef, Types, WalkAst};
 use crate::typed_ast::{TypedFieldKind, TypedStructDef};
 use hades_error::SemanticError;
+use hades_tokens::Name;
 use indexmap::IndexMap;
 
 impl WalkAst for FieldKind {
@@ -11,7 +12,11 @@ impl WalkAst for FieldKind {
         span: hades_error::Span,
     ) -> Result<Self::Output, SemanticError> {
         match self {
-            FieldKind::Var(typ) => Ok(TypedFieldKind::Var(typ.qualify(ctx.module_name()))),
+            FieldKind::Var(typ) => {
+                let typ = ctx.qualify(typ);
+                ctx.check_sized(&typ, span)?;
+                Ok(TypedFieldKind::Var(typ))
+            }
             FieldKind::Func(func_def) => {
                 Ok(TypedFieldKind::Func(Box::new(func_def.walk(ctx, span)?)))
             }
@@ -19,26 +24,48 @@ impl WalkAst for FieldKind {
     }
 }
 
-impl WalkAst for StructDef {
-    type Output = TypedStructDef;
-    fn walk(
+impl StructDef {
+    /// Declares the struct and its method signatures without checking the method bodies.
+    pub fn register(
         &self,
         ctx: &mut crate::typed_ast::CompilerContext,
-        span: hades_error::Span,
-    ) -> Result<Self::Output, SemanticError> {
+    ) -> Result<Name, SemanticError> {
         let name = self.name.full_name_optional(ctx.module_name());
 
         let var_fields = self
             .fields
             .iter()
             .filter_map(|(k, v)| match v {
-                FieldKind::Var(t) => {
-                    Some((k.clone(), TypedFieldKind::Var(t.qualify(ctx.module_name()))))
-                }
+                FieldKind::Var(t) => Some((k.clone(), TypedFieldKind::Var(ctx.qualify(t)))),
                 FieldKind::Func(_) => None,
             })
             .collect();
         ctx.insert_struct(name.clone(), var_fields);
+        if self.export {
+            ctx.export_struct(name.clone());
+        }
+        if self.attributes.has(&Attribute::Packed) {
+            ctx.pack_struct(name.clone());
+        }
+        if !self.public && !self.export {
+            ctx.mark_private(name.clone(), self.name.span().clone());
+        }
+        for (field, kind) in &self.fields {
+            if matches!(kind, FieldKind::Var(_))
+                && !self.export
+                && !self.public_fields.contains(field)
+            {
+                ctx.hide_field(name.clone(), field.clone());
+            }
+        }
+        if let Some(note) = self.attributes.deprecation() {
+            ctx.deprecate_struct(name.clone(), None, note.map(str::to_string));
+        }
+        for (field, attrs) in &self.field_attributes {
+            if let Some(note) = attrs.deprecation() {
+                ctx.deprecate_struct(name.clone(), Some(field.clone()), note.map(str::to_string));
+            }
+        }
 
         for (_, v) in &self.fields {
             if let FieldKind::Func(func_def) = v {
@@ -46,6 +73,44 @@ impl WalkAst for StructDef {
             }
         }
 
+        Ok(name)
+    }
+
+    /// Importers name the `pub` fields of a public struct and lay out all of them, so neither
+    /// may hold a private struct; a private field may only point to one.
+    fn check_public_fields(
+        &self,
+        ctx: &crate::typed_ast::CompilerContext,
+    ) -> Result<(), SemanticError> {
+        for (field, kind) in &self.fields {
+            let FieldKind::Var(typ) = kind else {
+                continue;
+            };
+            let typ = ctx.qualify(typ);
+            if self.public_fields.contains(field) || !matches!(typ, Types::Pointer(_)) {
+                ctx.check_public_type(
+                    &format!("field {}.{}", self.name.link_name(), field),
+                    &typ,
+                    field.span().clone(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WalkAst for StructDef {
+    type Output = TypedStructDef;
+    fn walk(
+        &self,
+        ctx: &mut crate::typed_ast::CompilerContext,
+        span: hades_error::Span,
+    ) -> Result<Self::Output, SemanticError> {
+        let name = self.register(ctx)?;
+        if self.public || self.export {
+            self.check_public_fields(ctx)?;
+        }
+
         let fields = self
             .fields
             .iter()
@@ -55,6 +120,7 @@ impl WalkAst for StructDef {
         Ok(TypedStructDef {
             name,
             fields,
+            packed: self.attributes.has(&Attribute::Packed),
             span: self.span.clone(),
         })
     }
@@ -19,7 +19,9 @@ impl WalkAst for While {
             ));
         }
 
+        ctx.enter_loop(false);
         let typed_body = self.body.walk(ctx, self.span.clone())?;
+        ctx.exit_loop();
         Ok(TypedWhile {
             cond: typed_cond,
             body: typed_body,
@@ -15,10 +15,26 @@ use super::{TypedProgram, expr::TypedExpr};
 #[derive(Debug, Clone, PartialEq)]
 pub struct TypedBlock {
     pub stmts: TypedProgram,
+    pub tail: Option<Box<TypedExprAst>>,
     #[debug(skip)]
     pub span: Span,
 }
 
+impl TypedBlock {
+    pub fn get_type(&self) -> Types {
+        self.tail
+            .as_ref()
+            .map(|tail| tail.get_type())
+            .unwrap_or(Types::Void)
+    }
+
+    /// Whether control can never reach the end of this block.
+    pub fn diverges(&self) -> bool {
+        self.stmts.iter().any(TypedStmt::diverges)
+            || self.tail.as_ref().is_some_and(|tail| tail.expr.diverges())
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct TypedLet {
     pub name: Ident,
@@ -36,6 +52,14 @@ pub struct TypedContinue {
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct TypedBreak {
+    pub value: Option<TypedExprAst>,
+    #[debug(skip)]
+    pub span: Span,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TypedExternType {
+    pub name: Name,
     #[debug(skip)]
     pub span: Span,
 }
@@ -69,6 +93,26 @@ pub struct TypedIf {
     pub cond: TypedExprAst,
     pub then_branch: TypedBlock,
     pub else_branch: Option<TypedBlock>,
+    pub typ: Types,
+    pub span: Span,
+}
+
+impl TypedIf {
+    pub fn diverges(&self) -> bool {
+        self.then_branch.diverges()
+            && self
+                .else_branch
+                .as_ref()
+                .is_some_and(|else_branch| else_branch.diverges())
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TypedLoop {
+    pub body: TypedBlock,
+    pub typ: Types,
+    /// Whether any `break` targets this loop; without one it never falls through.
+    pub breaks: bool,
     pub span: Span,
 }
 
@@ -92,6 +136,8 @@ pub struct TypedFor {
 pub struct TypedStructDef {
     pub name: Name,
     pub fields: IndexMap<Ident, TypedFieldKind>,
+    /// `@packed`: laid out without padding between fields.
+    pub packed: bool,
     pub span: Span,
 }
 
@@ -143,6 +189,7 @@ pub enum TypedStmt {
     While(TypedWhile),
     For(Box<TypedFor>),
     StructDef(TypedStructDef),
+    ExternType(TypedExternType),
     FuncDef(TypedFuncDef),
     Block(TypedBlock),
     Return(TypedReturn),
@@ -151,6 +198,18 @@ pub enum TypedStmt {
     Defer(TypedDefer),
 }
 
+impl TypedStmt {
+    pub fn diverges(&self) -> bool {
+        match self {
+            TypedStmt::Return(_) | TypedStmt::Break(_) | TypedStmt::Continue(_) => true,
+            TypedStmt::TypedExpr(e) => e.expr.diverges(),
+            TypedStmt::If(i) => i.diverges(),
+            TypedStmt::Block(b) => b.diverges(),
+            _ => false,
+        }
+    }
+}
+
 impl TypedFieldKind {
     pub fn get_type(&self) -> Types {
         match self {
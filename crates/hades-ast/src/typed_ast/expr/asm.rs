@@ -0,0 +1,18 @@
+use crate::ast::Types;
+use crate::typed_ast::expr::{TypedAssignTarget, TypedExpr};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedAsm {
+    pub template: String,
+    pub inputs: Vec<(String, TypedExpr)>,
+    pub outputs: Vec<TypedAsmOutput>,
+    pub clobbers: Vec<String>,
+}
+
+/// An output operand and the type of the place it is written back to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedAsmOutput {
+    pub constraint: String,
+    pub target: TypedAssignTarget,
+    pub typ: Types,
+}
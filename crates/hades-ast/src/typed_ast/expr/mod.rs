@@ -1,10 +1,13 @@
+mod asm;
 mod assign;
 
-use crate::ast::Types;
+use crate::ast::{Types, is_type_builtin};
+pub use asm::*;
 pub use assign::*;
 use hades_error::{SemanticError, Span};
 use indexmap::IndexMap;
 
+use super::ast::{TypedBlock, TypedIf, TypedLoop};
 use super::value::TypedValue;
 use hades_tokens::{Ident, Name, Op};
 
@@ -37,8 +40,15 @@ pub enum TypedExpr {
         receiver: Option<Box<TypedExpr>>,
         typ: Types,
     },
+    If(Box<TypedIf>),
+    Block(Box<TypedBlock>),
+    Loop(Box<TypedLoop>),
+    /// Inline assembly; its outputs are written back to places, so it has no value.
+    Asm(Box<TypedAsm>),
     /// Null pointer literal with the concrete pointer type inferred from context.
     Null(Types),
+    /// Type argument of a type builtin; it has no runtime value.
+    Type(Types),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -70,6 +80,16 @@ pub struct TypedAsExpression {
     pub target_type: Types,
 }
 
+impl TypedArrayIndex {
+    /// Type of the indexed element, for arrays, pointers and vector lanes.
+    pub fn elem_type(&self) -> Types {
+        match &self.typ {
+            Types::Pointer(inner) | Types::Vector(inner, _) => *inner.clone(),
+            typ => typ.get_array_elem_type(),
+        }
+    }
+}
+
 impl TypedExpr {
     pub fn is_const(&self) -> bool {
         match self {
@@ -89,8 +109,13 @@ impl TypedExpr {
             TypedExpr::ArrayIndex(_) => false,
             TypedExpr::Assign(_) => false,
             TypedExpr::As(_) => false,
-            TypedExpr::Call { .. } => false,
+            TypedExpr::Call { func, .. } => is_type_builtin(func.inner()),
+            TypedExpr::If(_) => false,
+            TypedExpr::Block(_) => false,
+            TypedExpr::Loop(_) => false,
+            TypedExpr::Asm(_) => false,
             TypedExpr::Null(_) => false,
+            TypedExpr::Type(_) => true,
         }
     }
 
@@ -104,9 +129,24 @@ impl TypedExpr {
             TypedExpr::Assign(TypedAssignExpr { typ, .. }) => typ.clone(),
             TypedExpr::Call { typ, .. } => typ.clone(),
             TypedExpr::FieldAccess(TypedFieldAccess { field_type, .. }) => field_type.clone(),
-            TypedExpr::ArrayIndex(TypedArrayIndex { typ, .. }) => typ.get_array_elem_type(),
+            TypedExpr::ArrayIndex(index) => index.elem_type(),
             TypedExpr::As(TypedAsExpression { target_type, .. }) => target_type.clone(),
+            TypedExpr::If(if_expr) => if_expr.typ.clone(),
+            TypedExpr::Block(block) => block.get_type(),
+            TypedExpr::Loop(loop_expr) => loop_expr.typ.clone(),
+            TypedExpr::Asm(_) => Types::Void,
             TypedExpr::Null(typ) => typ.clone(),
+            TypedExpr::Type(typ) => typ.clone(),
+        }
+    }
+
+    /// Whether evaluating this expression never completes normally.
+    pub fn diverges(&self) -> bool {
+        match self {
+            TypedExpr::If(if_expr) => if_expr.diverges(),
+            TypedExpr::Block(block) => block.diverges(),
+            TypedExpr::Loop(loop_expr) => !loop_expr.breaks,
+            _ => false,
         }
     }
 
@@ -1,7 +1,8 @@
+use super::TypedExpr;
 use super::builtins::BUILTIN_FUNCTIONS;
-use crate::ast::{ReceiverKind, Types};
-use hades_error::SemanticError;
-use hades_tokens::{Name, ParamKind};
+use crate::ast::{Attributes, CScalar, CWidths, ReceiverKind, Types};
+use hades_error::{SemanticError, Span};
+use hades_tokens::{Ident, Name, ParamKind};
 use indexmap::IndexMap;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -15,32 +16,62 @@ pub struct TypedReceiver {
 pub enum Params {
     Fixed(IndexMap<ParamKind, Types>),
     Variadic(IndexMap<ParamKind, Types>),
+    /// Ends in a `name: ...T` parameter, passed as a `&T` to the extra arguments followed by
+    /// how many there are.
+    Sequence(IndexMap<ParamKind, Types>),
 }
 
 impl Params {
+    /// The parameters of a function whose extra arguments of type `element` are gathered as
+    /// `name`.
+    pub fn sequence(mut params: IndexMap<ParamKind, Types>, name: Ident, element: Types) -> Self {
+        let length = Params::length_of(&name);
+        params.insert(ParamKind::Ident(name), Types::Pointer(Box::new(element)));
+        params.insert(ParamKind::Ident(length), Types::Int);
+        Params::Sequence(params)
+    }
+
+    /// The hidden parameter holding the length of the variadic parameter `name`.
+    pub fn length_of(name: &Ident) -> Ident {
+        Ident::new(format!("{}.len", name.inner()), name.span().clone())
+    }
+
+    /// The type of each argument a `name: ...T` parameter gathers.
+    pub fn element(&self) -> Option<&Types> {
+        let Params::Sequence(m) = self else {
+            return None;
+        };
+        match m.get_index(m.len() - 2) {
+            Some((_, Types::Pointer(inner))) => Some(inner),
+            _ => None,
+        }
+    }
+
     fn map(&self) -> &IndexMap<ParamKind, Types> {
         match self {
-            Params::Fixed(m) | Params::Variadic(m) => m,
+            Params::Fixed(m) | Params::Variadic(m) | Params::Sequence(m) => m,
         }
     }
 
-    pub fn type_at(&self, num: usize) -> Option<&Types> {
-        self.map()
-            .iter()
+    /// The parameters a call passes an argument for each of: those after `self`, without the
+    /// pointer and length a variadic parameter is lowered to.
+    fn declared(&self) -> impl Iterator<Item = (&ParamKind, &Types)> {
+        let hidden = match self {
+            Params::Sequence(_) => 2,
+            _ => 0,
+        };
+        let map = self.map();
+        map.iter()
+            .take(map.len() - hidden)
             .filter(|(k, _)| !matches!(k, ParamKind::Self_(_)))
-            .map(|(_, v)| v)
-            .nth(num)
     }
 
-    pub fn type_match(&self, num: usize, other_type: &Types) -> bool {
-        let entry = self
-            .map()
-            .iter()
-            .filter(|(k, _)| !matches!(k, ParamKind::Self_(_)))
-            .map(|(_, v)| v)
-            .nth(num);
+    pub fn type_at(&self, num: usize) -> Option<&Types> {
+        self.types().nth(num).or_else(|| self.element())
+    }
 
-        match entry {
+    pub fn type_match(&self, num: usize, other_type: &Types) -> bool {
+        match self.type_at(num) {
             None => matches!(self, Params::Variadic(_)),
             Some(expected) => match expected {
                 Types::Generic(typs) => typs.iter().any(|t| match (t, other_type) {
@@ -49,16 +80,44 @@ impl Params {
                     }
                     _ => t == other_type,
                 }),
-                _ => other_type == expected,
+                _ => other_type.coerces_to(expected),
             },
         }
     }
 
+    /// The parameters after `self`, the ones a call passes arguments for.
+    pub fn names(&self) -> impl Iterator<Item = &Ident> {
+        self.declared().filter_map(|(p, _)| match p {
+            ParamKind::Ident(ident) => Some(ident),
+            ParamKind::Self_(_) => None,
+        })
+    }
+
+    /// The types of the parameters after `self`.
+    pub fn types(&self) -> impl Iterator<Item = &Types> {
+        self.declared().map(|(_, v)| v)
+    }
+
     pub fn named_count(&self) -> usize {
-        self.map()
-            .keys()
-            .filter(|p| !matches!(p, ParamKind::Self_(_)))
-            .count()
+        self.declared().count()
+    }
+
+    /// The parameter types as an overload's name spells them: `int_float`, `etc_int` for a
+    /// `...int` parameter, or `void` when there are none.
+    pub fn mangled(&self) -> String {
+        let types: Vec<String> = self
+            .types()
+            .map(Types::mangled)
+            .chain(
+                self.element()
+                    .map(|element| format!("etc_{}", element.mangled())),
+            )
+            .collect();
+        if types.is_empty() {
+            "void".to_string()
+        } else {
+            types.join("_")
+        }
     }
 }
 
@@ -68,7 +127,9 @@ impl IntoIterator for Params {
 
     fn into_iter(self) -> Self::IntoIter {
         match self {
-            Params::Fixed(m) | Params::Variadic(m) => m.into_iter().collect::<Vec<_>>().into_iter(),
+            Params::Fixed(m) | Params::Variadic(m) | Params::Sequence(m) => {
+                m.into_iter().collect::<Vec<_>>().into_iter()
+            }
         }
     }
 }
@@ -76,7 +137,7 @@ impl IntoIterator for Params {
 #[derive(Debug, Clone, PartialEq)]
 pub enum FuncKind {
     Normal,
-    Extern { variadic: bool },
+    Extern { variadic: bool, widths: CWidths },
     Intrinsic(String),
 }
 
@@ -84,8 +145,16 @@ pub enum FuncKind {
 pub struct FunctionSignature {
     pub receiver: Option<TypedReceiver>,
     pub params: Params,
+    /// Values for the parameters a call may leave out, walked where the function is declared.
+    pub defaults: IndexMap<Ident, TypedExpr>,
     pub return_type: Types,
     pub kind: FuncKind,
+    /// Declared with `export`: the symbol is the unqualified source name.
+    pub export: bool,
+    pub attributes: Attributes,
+    /// The source name of an overloaded function, which is registered under that name mangled
+    /// with its parameter types.
+    pub overload_of: Option<Name>,
 }
 
 impl FunctionSignature {
@@ -96,9 +165,13 @@ impl FunctionSignature {
     ) -> Self {
         Self {
             params: Params::Fixed(params),
+            defaults: IndexMap::new(),
             return_type,
             receiver,
             kind: FuncKind::Normal,
+            export: false,
+            attributes: Attributes::default(),
+            overload_of: None,
         }
     }
 
@@ -106,6 +179,7 @@ impl FunctionSignature {
         params: IndexMap<ParamKind, Types>,
         return_type: Types,
         variadic: bool,
+        widths: CWidths,
     ) -> Self {
         Self {
             params: if variadic {
@@ -113,9 +187,13 @@ impl FunctionSignature {
             } else {
                 Params::Fixed(params)
             },
+            defaults: IndexMap::new(),
             return_type,
             receiver: None,
-            kind: FuncKind::Extern { variadic },
+            kind: FuncKind::Extern { variadic, widths },
+            export: false,
+            attributes: Attributes::default(),
+            overload_of: None,
         }
     }
 
@@ -126,23 +204,61 @@ impl FunctionSignature {
     ) -> Self {
         Self {
             params: Params::Fixed(params),
+            defaults: IndexMap::new(),
             return_type,
             receiver: None,
             kind: FuncKind::Intrinsic(llvm_name),
+            export: false,
+            attributes: Attributes::default(),
+            overload_of: None,
         }
     }
 
+    /// Functions can share a name if they are plain Hades functions. Exported ones keep their
+    /// source name as the symbol, so they can't.
+    pub fn can_overload(&self) -> bool {
+        self.kind == FuncKind::Normal && !self.export && self.receiver.is_none()
+    }
+
+    /// Registers this signature as one of the overloads named `name`, returning the name it is
+    /// registered under.
+    pub fn overload(&mut self, name: Name) -> Name {
+        let overload = name.overload(&self.params.mangled());
+        self.overload_of = Some(name);
+        overload
+    }
+
     pub fn param_count(&self) -> usize {
         self.params.named_count()
     }
 
     pub fn check_arg_count(&self, provided: usize) -> bool {
-        match &self.kind {
-            FuncKind::Extern { variadic: true } => provided >= self.params.named_count(),
+        match (&self.kind, &self.params) {
+            (FuncKind::Extern { variadic: true, .. }, _) | (_, Params::Sequence(_)) => {
+                provided >= self.params.named_count()
+            }
             _ => provided == self.params.named_count(),
         }
     }
 
+    /// The C type of parameter `index` when a function imported from a C header takes a
+    /// narrower one than Hades passes.
+    pub fn c_param(&self, index: usize) -> Option<CScalar> {
+        match &self.kind {
+            FuncKind::Extern { widths, .. } => widths.param(index),
+            _ => None,
+        }
+    }
+
+    /// The C return type when a function imported from a C header returns a narrower one than
+    /// Hades sees.
+    pub fn c_return(&self) -> Option<CScalar> {
+        match &self.kind {
+            FuncKind::Extern { widths, .. } => widths.ret,
+            _ => None,
+        }
+    }
+
     pub fn params(&self) -> Params {
         self.params.clone()
     }
@@ -151,6 +267,22 @@ impl FunctionSignature {
         self.params.map().clone()
     }
 
+    /// The LLVM symbol for the function registered under `name`.
+    pub fn symbol_name<'a>(&self, name: &'a Name) -> &'a str {
+        if self.export || matches!(self.kind, FuncKind::Extern { .. }) {
+            name.link_name()
+        } else {
+            name.inner()
+        }
+    }
+
+    /// The warning to report when this function is called as `name` while `@deprecated`.
+    pub fn deprecation_warning(&self, name: &str, span: Span) -> Option<SemanticError> {
+        self.attributes
+            .deprecation()
+            .map(|note| SemanticError::deprecated_use(format!("function {name}"), note, span))
+    }
+
     pub fn return_type(&self) -> &Types {
         &self.return_type
     }
@@ -188,8 +320,9 @@ impl Functions {
             if matches!(sig.kind, FuncKind::Extern { .. } | FuncKind::Intrinsic(_)) {
                 return Ok(());
             }
+            let source = sig.overload_of.as_ref().unwrap_or(&name);
             return Err(SemanticError::redefined_function(
-                name.inner().to_string(),
+                source.inner().to_string(),
                 name.span().clone(),
             ));
         }
@@ -197,12 +330,28 @@ impl Functions {
         Ok(())
     }
 
+    /// The function registered as `name`, or its overloads.
+    pub fn overloads(&self, name: &Name) -> Vec<(&Name, &FunctionSignature)> {
+        self.inner
+            .iter()
+            .filter(|(key, sig)| *key == name || sig.overload_of.as_ref() == Some(name))
+            .collect()
+    }
+
+    pub fn import(&mut self, name: Name, sig: FunctionSignature) {
+        self.inner.entry(name).or_insert(sig);
+    }
+
     pub fn get(&self, name: &Name) -> Result<&FunctionSignature, SemanticError> {
         self.inner.get(name).ok_or_else(|| {
             SemanticError::undefined_function(name.inner().to_string(), name.span().clone())
         })
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&Name, &FunctionSignature)> {
+        self.inner.iter()
+    }
+
     pub fn into_user_defined(self) -> IndexMap<Name, FunctionSignature> {
         let builtin_names: std::collections::HashSet<String> = BUILTIN_FUNCTIONS
             .keys()
@@ -3,9 +3,17 @@ use crate::scope::Scope;
 
 use hades_tokens::Ident;
 
+/// What a local name is bound to: its type and, for a variadic parameter, the hidden local
+/// holding how many arguments it gathered.
+#[derive(Debug, Clone, PartialEq)]
+struct Binding {
+    typ: Types,
+    length: Option<Ident>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IdentMap {
-    inner: Scope<Types>,
+    inner: Scope<Binding>,
 }
 
 impl IdentMap {
@@ -23,12 +31,32 @@ impl IdentMap {
     }
 
     pub fn insert(&mut self, name: Ident, typ: Types) {
+        self.bind(name, Binding { typ, length: None });
+    }
+
+    /// Binds a variadic parameter, passed as a pointer whose length is the local `length`.
+    pub fn insert_sequence(&mut self, name: Ident, typ: Types, length: Ident) {
+        self.bind(
+            name,
+            Binding {
+                typ,
+                length: Some(length),
+            },
+        );
+    }
+
+    fn bind(&mut self, name: Ident, binding: Binding) {
         self.inner.on_scope_mut(|node| {
-            node.insert(name, typ);
+            node.insert(name, binding);
         });
     }
 
     pub fn lookup<'a>(&'a self, name: &'a Ident) -> Option<&'a Types> {
-        self.inner.lookup_scope(name)
+        self.inner.lookup_scope(name).map(|binding| &binding.typ)
+    }
+
+    /// The length of the variadic parameter `name` is bound to, if it is bound to one.
+    pub fn sequence_length(&self, name: &Ident) -> Option<&Ident> {
+        self.inner.lookup_scope(name)?.length.as_ref()
     }
 }
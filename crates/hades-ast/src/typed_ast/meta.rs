@@ -1,28 +1,51 @@
 use crate::typed_ast::{
     TypedFieldKind,
-    function::{FunctionSignature, Functions},
+    function::{FunctionSignature, Functions, Params},
     ident::IdentMap,
     struc::{Field, Structs},
 };
 use hades_error::SemanticError;
 
-use crate::ast::Types;
+use crate::ast::{ArrayType, Types};
 use hades_error::Span;
 use hades_tokens::{Ident, Name, Op};
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
+use std::borrow::Cow;
 
 /// A simple module path — either from the standard library or a local module.
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum ModulePath {
     Std(String),
-    Local(String),
+    /// `import self::net::http`: the directories below the project and the file stem.
+    Local(Vec<String>),
+    /// A C header brought in with `import c "foo.h"`, named after its file stem.
+    C(String),
+    /// A module of a dependency package: `import geometry::shapes` or, inside the package,
+    /// `import self::shapes`.
+    Package(String, Vec<String>),
 }
 
 impl ModulePath {
-    pub fn name(&self) -> &str {
+    /// The qualifier of the module's item names. Nested local modules join their segments
+    /// with `.`, as in `net.http`, and dependency modules start with their package's name and
+    /// `$`, as in `geometry$shapes`. Neither can appear in an identifier, so no file name or
+    /// package collides with another module.
+    pub fn name(&self) -> Cow<'_, str> {
         match self {
-            ModulePath::Std(name) => name,
-            ModulePath::Local(name) => name,
+            ModulePath::Std(name) => Cow::Borrowed(name),
+            ModulePath::Local(segments) => match segments.as_slice() {
+                [name] => Cow::Borrowed(name),
+                _ => Cow::Owned(segments.join(".")),
+            },
+            ModulePath::Package(package, segments) => {
+                Cow::Owned(format!("{}${}", package, segments.join(".")))
+            }
+            ModulePath::C(header) => Cow::Borrowed(
+                std::path::Path::new(header)
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or(header),
+            ),
         }
     }
 }
@@ -31,18 +54,59 @@ impl std::fmt::Display for ModulePath {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ModulePath::Std(name) => write!(f, "std::{}", name),
-            ModulePath::Local(name) => write!(f, "self::{}", name),
+            ModulePath::Local(segments) => write!(f, "self::{}", segments.join("::")),
+            ModulePath::C(header) => write!(f, "c \"{}\"", header),
+            ModulePath::Package(package, segments) => {
+                write!(f, "{}::{}", package, segments.join("::"))
+            }
         }
     }
 }
 
+/// Break bookkeeping for one enclosing loop while its body is walked.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LoopFrame {
+    /// Only `loop` may yield a value through `break`; `while` and `for` may not.
+    pub yields_value: bool,
+    pub breaks: bool,
+    pub value: Option<Types>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct CompilerContext {
     idents: IdentMap,
     functions: Functions,
     structs: Structs,
     current_function: Option<(Name, Types)>,
+    loops: Vec<LoopFrame>,
     module_path: Option<ModulePath>,
+    /// `module_path.name()`, kept so item names can borrow it.
+    module_name: Option<String>,
+    /// Libraries named by `@link` markers, passed to the linker as `-l<name>`.
+    links: IndexSet<String>,
+    /// Non-fatal diagnostics, such as uses of `@deprecated` items.
+    warnings: Vec<SemanticError>,
+    /// Functions, methods and structs declared without `pub`, with the spans of their names. A
+    /// private overload also marks the name it shares. Those of imported modules are only known
+    /// so their uses can be reported.
+    private: IndexMap<Name, Span>,
+    /// `import m as alias`, or the last segment of `import a::m`: each alias, the module it
+    /// stands for and where it was introduced.
+    module_aliases: IndexMap<String, (String, Span)>,
+    /// The modules this one imports. Their signatures bring in those of the modules they
+    /// import, which can't be named from here unless imported too.
+    imported_modules: IndexSet<String>,
+    /// Items brought in by `import m::{name}` or `import m::*`, by the name they are used under.
+    imported: IndexMap<String, Name>,
+    /// The names of `imported` brought in by `import m::{name}`, spanned where they are named.
+    named_imports: IndexMap<String, Span>,
+    /// Items a module makes reachable with `pub import`, by their name under that module
+    /// (`shapes::area`), and the items they stand for (`shapes::circle::area`).
+    reexports: IndexMap<Name, Name>,
+    /// How many compiler-introduced locals have been named, so each name is unique.
+    hidden_idents: usize,
+    /// Names this module declares several functions under, which each register as an overload.
+    overloaded: IndexSet<Name>,
 }
 
 impl CompilerContext {
@@ -52,11 +116,24 @@ impl CompilerContext {
             functions: Functions::new(),
             structs: Structs::new(),
             current_function: None,
+            loops: Vec::new(),
             module_path: None,
+            module_name: None,
+            links: IndexSet::new(),
+            warnings: Vec::new(),
+            private: IndexMap::new(),
+            module_aliases: IndexMap::new(),
+            imported_modules: IndexSet::new(),
+            imported: IndexMap::new(),
+            named_imports: IndexMap::new(),
+            reexports: IndexMap::new(),
+            hidden_idents: 0,
+            overloaded: IndexSet::new(),
         }
     }
 
     pub fn set_module_path(&mut self, path: ModulePath) {
+        self.module_name = Some(path.name().into_owned());
         self.module_path = Some(path);
     }
 
@@ -65,17 +142,295 @@ impl CompilerContext {
     }
 
     pub fn module_name(&self) -> Option<&str> {
-        self.module_path.as_ref().map(|p| p.name())
+        self.module_name.as_deref()
+    }
+
+    /// The dependency package this module belongs to, if it is not in the root package.
+    pub fn package(&self) -> Option<&str> {
+        match &self.module_path {
+            Some(ModulePath::Package(package, _)) => Some(package),
+            _ => None,
+        }
     }
 
     pub fn is_stdlib(&self) -> bool {
         matches!(self.module_path, Some(ModulePath::Std(_)))
     }
 
+    pub fn is_c_header(&self) -> bool {
+        matches!(self.module_path, Some(ModulePath::C(_)))
+    }
+
+    pub fn add_link(&mut self, library: String) {
+        self.links.insert(library);
+    }
+
+    pub fn links(&self) -> impl Iterator<Item = &str> {
+        self.links.iter().map(String::as_str)
+    }
+
+    pub fn warn(&mut self, warning: SemanticError) {
+        self.warnings.push(warning);
+    }
+
+    pub fn warnings(&self) -> &[SemanticError] {
+        &self.warnings
+    }
+
+    /// Warns if the struct, or the given field of it, is `@deprecated`.
+    pub fn check_deprecated_struct(&mut self, name: &Name, field: Option<&Ident>, span: Span) {
+        if let Some(note) = self.structs.deprecation(name, field) {
+            let item = match field {
+                Some(field) => format!("field {}.{}", name.link_name(), field.inner()),
+                None => format!("struct {}", name.link_name()),
+            };
+            self.warnings
+                .push(SemanticError::deprecated_use(item, note, span));
+        }
+    }
+
+    pub fn mark_private(&mut self, name: Name, declared: Span) {
+        self.private.entry(name).or_insert(declared);
+    }
+
+    pub fn private_items(&self) -> &IndexMap<Name, Span> {
+        &self.private
+    }
+
+    pub fn hide_field(&mut self, name: Name, field: Ident) {
+        self.structs.hide_field(name, field);
+    }
+
+    /// Fields are private to the module that declares their struct.
+    pub fn check_field_visible(
+        &self,
+        name: &Name,
+        field: &Ident,
+        span: Span,
+    ) -> Result<(), SemanticError> {
+        match self.structs.private_field(name, field) {
+            Some(declared) if name.module() != self.module_name() => {
+                Err(SemanticError::private_item(
+                    format!("field {}.{}", source_path(name), field.inner()),
+                    declared.span().clone(),
+                    span,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The error for naming an item another module did not declare `pub`, if it is one.
+    fn private_use(&self, kind: &str, name: &Name, span: Span) -> Option<SemanticError> {
+        self.private.get(name).map(|declared| {
+            SemanticError::private_item(
+                format!("{} {}", kind, source_path(name)),
+                declared.clone(),
+                span,
+            )
+        })
+    }
+
+    /// One alias can't stand for two modules.
+    pub fn alias_module(&mut self, alias: &Ident, module: &str) -> Result<(), SemanticError> {
+        if let Some((earlier, first)) = self.module_aliases.get(alias.inner())
+            && earlier != module
+        {
+            return Err(SemanticError::conflicting_alias(
+                alias.inner().to_string(),
+                (source_module(earlier), source_module(module)),
+                first.clone(),
+                alias.span().clone(),
+            ));
+        }
+        self.module_aliases.insert(
+            alias.inner().to_string(),
+            (module.to_string(), alias.span().clone()),
+        );
+        Ok(())
+    }
+
+    pub fn import_module(&mut self, module: &str) {
+        self.imported_modules.insert(module.to_string());
+    }
+
+    /// The module a `qualifier::` refers to, looking through `import m as alias`. Only this
+    /// module and those it imports can be named.
+    pub fn resolve_module<'a>(&'a self, qualifier: &'a Ident) -> Result<&'a str, SemanticError> {
+        let module = self
+            .module_aliases
+            .get(qualifier.inner())
+            .map_or(qualifier.inner(), |(module, _)| module.as_str());
+        if self.module_name() == Some(module) || self.imported_modules.contains(module) {
+            Ok(module)
+        } else {
+            Err(SemanticError::module_not_imported(
+                source_module(module),
+                qualifier.span().clone(),
+            ))
+        }
+    }
+
+    /// Explicitly named imports replace glob ones; glob imports never replace anything. Two
+    /// explicit imports can't bring different items in under the same name.
+    pub fn import_item(
+        &mut self,
+        local: &Ident,
+        item: Name,
+        glob: bool,
+    ) -> Result<(), SemanticError> {
+        let name = local.inner();
+        if let Some(earlier) = self.imported.get(name) {
+            if glob {
+                return Ok(());
+            }
+            if let Some(first) = self.named_imports.get(name)
+                && earlier != &item
+            {
+                return Err(SemanticError::conflicting_import(
+                    name.to_string(),
+                    (source_path(earlier), source_path(&item)),
+                    first.clone(),
+                    local.span().clone(),
+                ));
+            }
+        }
+        if !glob {
+            self.named_imports
+                .entry(name.to_string())
+                .or_insert_with(|| local.span().clone());
+        }
+        self.imported.insert(name.to_string(), item);
+        Ok(())
+    }
+
+    /// Makes `item` reachable as `local` through this module. Two different items can't be
+    /// re-exported under the same name.
+    pub fn reexport(&mut self, local: &Ident, item: Name) -> Result<(), SemanticError> {
+        let key = Name::new(local.inner().to_string(), local.span().clone())
+            .full_name_optional(self.module_name());
+        if let Some((first, earlier)) = self.reexports.get_key_value(&key)
+            && earlier != &item
+        {
+            return Err(SemanticError::ambiguous_reexport(
+                local.inner().to_string(),
+                (source_path(earlier), source_path(&item)),
+                first.span().clone(),
+                local.span().clone(),
+            ));
+        }
+        self.reexports.insert(key, item);
+        Ok(())
+    }
+
+    /// Records a re-export of an imported module.
+    pub fn import_reexport(&mut self, key: Name, item: Name) {
+        self.reexports.insert(key, item);
+    }
+
+    /// The re-exports this module declared with `pub import`.
+    pub fn own_reexports(&self) -> impl Iterator<Item = (&Name, &Name)> {
+        self.reexports
+            .iter()
+            .filter(|(key, _)| key.module() == self.module_name())
+    }
+
+    /// The item `name` stands for if a module re-exported it, spanned at the use.
+    pub fn through_reexports(&self, name: Name) -> Name {
+        match self.reexports.get(&name) {
+            Some(item) => Name::new(item.link_name().to_string(), name.span().clone())
+                .full_name_optional(item.module()),
+            None => name,
+        }
+    }
+
+    /// Errors unless `item` is a public function or struct that `module` provides.
+    pub fn check_importable(
+        &self,
+        item: &Name,
+        module: &str,
+        span: Span,
+    ) -> Result<(), SemanticError> {
+        if !self.functions.overloads(item).is_empty() || self.structs.declares(item) {
+            return Ok(());
+        }
+        Err(self
+            .private_use("item", item, span.clone())
+            .unwrap_or_else(|| {
+                SemanticError::invalid_import(
+                    format!("no public item '{}' in module {}", item.link_name(), module),
+                    span,
+                )
+            }))
+    }
+
+    /// The item an imported name stands for, spanned at the use.
+    pub fn imported_item(&self, local: &Name) -> Option<Name> {
+        self.imported.get(local.inner()).map(|item| {
+            Name::new(item.link_name().to_string(), local.span().clone())
+                .full_name_optional(item.module())
+        })
+    }
+
+    /// The public functions and structs `import module::*` brings in: those `module` declares
+    /// and those it re-exports with `pub import`, but not what it imports for itself. Methods
+    /// stay reachable through their struct, and overloads through the name they share.
+    pub fn module_items(&self, module: &str) -> Vec<Name> {
+        let functions = self
+            .functions
+            .iter()
+            .filter(|(name, sig)| name.module() == Some(module) && sig.receiver.is_none())
+            .map(|(name, sig)| sig.overload_of.as_ref().unwrap_or(name).clone())
+            .collect::<IndexSet<_>>()
+            .into_iter();
+        let structs = self
+            .structs
+            .iter()
+            .map(|(name, _)| name)
+            .chain(self.structs.opaque_types())
+            .filter(|name| name.module() == Some(module))
+            .cloned();
+        let reexports = self
+            .reexports
+            .keys()
+            .filter(|key| key.module() == Some(module))
+            .cloned();
+        functions.chain(structs).chain(reexports).collect()
+    }
+
+    /// A struct named without a module: one of this module's, or an imported one.
+    pub fn struct_name(&self, name: &Name) -> Name {
+        let own = name.full_name_optional(self.module_name());
+        if self.structs.fields(&own).is_some() {
+            return own;
+        }
+        match self.imported.get(name.inner()) {
+            Some(item) if self.structs.declares(item) => item.clone(),
+            _ => own,
+        }
+    }
+
+    /// Gives the struct names in a type written in this module their full names.
+    pub fn qualify(&self, typ: &Types) -> Types {
+        match typ {
+            Types::Struct(name) => Types::Struct(self.struct_name(name)),
+            Types::Array(ArrayType::StructArray(size, name)) => {
+                Types::Array(ArrayType::StructArray(*size, self.struct_name(name)))
+            }
+            Types::Pointer(inner) => Types::Pointer(Box::new(self.qualify(inner))),
+            other => other.clone(),
+        }
+    }
+
     pub fn structs(&self) -> &Structs {
         &self.structs
     }
 
+    /// Imported signatures can reach a module along several paths; the first one is kept.
+    pub fn import_function(&mut self, name: Name, sig: FunctionSignature) {
+        self.functions.import(name, sig);
+    }
+
     pub fn register_function(
         &mut self,
         name: Name,
@@ -84,6 +439,20 @@ impl CompilerContext {
         self.functions.insert(name, sig)
     }
 
+    /// Every function declared under one of `names` is registered as an overload, so none of
+    /// them depends on which is declared first.
+    pub fn set_overloaded(&mut self, names: IndexSet<Name>) {
+        self.overloaded = names;
+    }
+
+    pub fn is_overloaded(&self, name: &Name) -> bool {
+        self.overloaded.contains(name)
+    }
+
+    pub fn overloads(&self, name: &Name) -> Vec<(&Name, &FunctionSignature)> {
+        self.functions.overloads(name)
+    }
+
     pub fn set_current_function(&mut self, name: Name, return_type: Types) {
         self.current_function = Some((name, return_type));
     }
@@ -100,6 +469,51 @@ impl CompilerContext {
         self.current_function = None;
     }
 
+    pub(crate) fn enter_loop(&mut self, yields_value: bool) {
+        self.loops.push(LoopFrame {
+            yields_value,
+            breaks: false,
+            value: None,
+        });
+    }
+
+    pub(crate) fn exit_loop(&mut self) -> LoopFrame {
+        self.loops.pop().expect("exit_loop without enter_loop")
+    }
+
+    pub(crate) fn record_break(
+        &mut self,
+        value: Option<Types>,
+        span: Span,
+    ) -> Result<(), SemanticError> {
+        let frame = self
+            .loops
+            .last_mut()
+            .ok_or_else(|| SemanticError::break_outside_loop(span.clone()))?;
+
+        if value.is_some() && !frame.yields_value {
+            return Err(SemanticError::break_value_outside_loop(span));
+        }
+
+        frame.breaks = true;
+        if !frame.yields_value {
+            return Ok(());
+        }
+
+        let value = value.unwrap_or(Types::Void);
+        match &frame.value {
+            Some(expected) if *expected != value => Err(SemanticError::type_mismatch(
+                expected.to_string(),
+                value.to_string(),
+                span,
+            )),
+            _ => {
+                frame.value = Some(value);
+                Ok(())
+            }
+        }
+    }
+
     pub fn insert_variable(&mut self, name: Ident, typ: Types) {
         self.idents.insert(name, typ);
     }
@@ -111,13 +525,113 @@ impl CompilerContext {
             .cloned()
     }
 
+    /// A fresh name for a local the compiler introduces, which user code cannot spell.
+    pub fn hidden_ident(&mut self, role: &str, span: Span) -> Ident {
+        self.hidden_idents += 1;
+        Ident::new(format!("{role}.{}", self.hidden_idents), span)
+    }
+
+    /// Binds the variadic parameter `name`, whose arguments of type `element` are passed as a
+    /// pointer and a hidden length.
+    pub fn insert_sequence(&mut self, name: Ident, element: Types) {
+        let length = Params::length_of(&name);
+        self.idents.insert(length.clone(), Types::Int);
+        self.idents
+            .insert_sequence(name, Types::Pointer(Box::new(element)), length);
+    }
+
+    /// The hidden length of the variadic parameter `name` is bound to. A local shadowing the
+    /// parameter has none.
+    pub fn sequence_length(&self, name: &Ident) -> Option<Ident> {
+        self.idents.sequence_length(name).cloned()
+    }
+
     pub fn insert_struct(&mut self, name: Name, fields: IndexMap<Ident, TypedFieldKind>) {
         self.structs.insert(name.clone(), fields);
     }
 
+    pub fn export_struct(&mut self, name: Name) {
+        self.structs.export(name);
+    }
+
+    pub fn pack_struct(&mut self, name: Name) {
+        self.structs.pack(name);
+    }
+
+    pub fn deprecate_struct(&mut self, name: Name, field: Option<Ident>, note: Option<String>) {
+        self.structs.deprecate(name, field, note);
+    }
+
+    /// Opaque types seen through an import may already be known via another dependency.
+    pub fn import_opaque(&mut self, name: Name) {
+        self.structs.insert_opaque(name);
+    }
+
+    pub fn insert_opaque(&mut self, name: Name, span: Span) -> Result<(), SemanticError> {
+        if self.structs.insert_opaque(name.clone()) {
+            Ok(())
+        } else {
+            Err(SemanticError::redefined_struct(
+                name.inner().to_string(),
+                span,
+            ))
+        }
+    }
+
+    /// Whether values of `typ` can be stored, copied or pointed into. `void` and opaque
+    /// extern types can only be used behind a pointer.
+    pub fn is_sized(&self, typ: &Types) -> bool {
+        match typ {
+            Types::Void => false,
+            Types::Struct(name) | Types::Array(ArrayType::StructArray(_, name)) => {
+                !self.structs.is_opaque(name)
+            }
+            _ => true,
+        }
+    }
+
+    /// Errors if `typ` names a struct private to this module, as part of the public `item`.
+    pub fn check_public_type(
+        &self,
+        item: &str,
+        typ: &Types,
+        span: Span,
+    ) -> Result<(), SemanticError> {
+        let name = match typ {
+            Types::Struct(name) | Types::Array(ArrayType::StructArray(_, name)) => name,
+            Types::Pointer(inner) | Types::Array(ArrayType::PointerArray(_, inner)) => {
+                return self.check_public_type(item, inner, span);
+            }
+            _ => return Ok(()),
+        };
+        match self.private.get(name) {
+            Some(declared) => Err(SemanticError::private_in_public(
+                item.to_string(),
+                typ.source_name(),
+                declared.clone(),
+                span,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_private(&self, name: &Name) -> bool {
+        self.private.contains_key(name)
+    }
+
+    pub fn check_sized(&self, typ: &Types, span: Span) -> Result<(), SemanticError> {
+        if self.is_sized(typ) {
+            Ok(())
+        } else {
+            Err(SemanticError::unsized_type(typ.source_name(), span))
+        }
+    }
+
     pub fn get_struct_type(&self, name: &Name, span: Span) -> Result<Field, SemanticError> {
         if let Some(fields) = self.structs.fields(name) {
             Ok(fields.clone())
+        } else if let Some(err) = self.private_use("struct", name, span.clone()) {
+            Err(err)
         } else {
             Err(SemanticError::undefined_struct(
                 name.inner().to_string(),
@@ -127,12 +641,25 @@ impl CompilerContext {
     }
 
     pub fn get_function_signature(&self, name: &Name) -> Result<&FunctionSignature, SemanticError> {
-        self.functions.get(name)
+        self.get_signature("function", name)
+    }
+
+    /// The signature registered under `name`, with a private one reported as a `kind`, i.e.
+    /// "function" or "method".
+    pub fn get_signature(
+        &self,
+        kind: &str,
+        name: &Name,
+    ) -> Result<&FunctionSignature, SemanticError> {
+        self.functions.get(name).map_err(|err| {
+            self.private_use(kind, name, name.span().clone())
+                .unwrap_or(err)
+        })
     }
 
     pub fn check_return_type(&self, return_type: Types, span: Span) -> Result<(), SemanticError> {
         if let Some((_, expected_return_type)) = &self.current_function
-            && *expected_return_type != return_type
+            && !return_type.coerces_to(expected_return_type)
         {
             return Err(SemanticError::return_type_mismatch(
                 expected_return_type.clone().to_string(),
@@ -165,9 +692,51 @@ impl CompilerContext {
                 (Types::Int, Types::Int) => Ok(Types::Int),
                 (Types::Float, Types::Float) => Ok(Types::Float),
                 (Types::Int, Types::Float) | (Types::Float, Types::Int) => Ok(Types::Float),
+                (Types::Vector(..), Types::Vector(..))
+                    if left == right
+                        && matches!(
+                            op,
+                            Op::Add
+                                | Op::Plus
+                                | Op::PlusEqual
+                                | Op::Sub
+                                | Op::Minus
+                                | Op::MinusEqual
+                                | Op::Mul
+                                | Op::Multiply
+                                | Op::Div
+                                | Op::Divide
+                        ) =>
+                {
+                    Ok(left.clone())
+                }
                 (Types::String, Types::String) if matches!(op, Op::Add | Op::Plus) => {
                     Ok(Types::String)
                 }
+                (Types::Pointer(pointee), Types::Int)
+                    if self.is_sized(pointee)
+                        && matches!(
+                            op,
+                            Op::Add
+                                | Op::Plus
+                                | Op::PlusEqual
+                                | Op::Sub
+                                | Op::Minus
+                                | Op::MinusEqual
+                        ) =>
+                {
+                    Ok(left.clone())
+                }
+                (Types::Int, Types::Pointer(pointee))
+                    if self.is_sized(pointee) && matches!(op, Op::Add | Op::Plus) =>
+                {
+                    Ok(right.clone())
+                }
+                (Types::Pointer(l), Types::Pointer(r))
+                    if l == r && self.is_sized(l) && matches!(op, Op::Sub | Op::Minus) =>
+                {
+                    Ok(Types::Int)
+                }
                 _ => Err(SemanticError::invalid_binary_operation(
                     left.to_string().to_string(),
                     format!("{op:?}"),
@@ -192,8 +761,12 @@ impl CompilerContext {
                 | (Types::String, Types::String)
                 | (Types::Bool, Types::Bool) => Ok(Types::Bool),
                 (Types::Int, Types::Float) | (Types::Float, Types::Int) => Ok(Types::Bool),
-                (Types::Pointer(_), Types::Pointer(_))
-                    if matches!(op, Op::Eq | Op::Ne | Op::EqualEqual | Op::BangEqual) =>
+                (Types::Pointer(l), Types::Pointer(r)) if l == r => Ok(Types::Bool),
+                // Any pointer converts to `&void`, so the two may be the same address, but
+                // their order means nothing.
+                (Types::Pointer(l), Types::Pointer(r))
+                    if (**l == Types::Void || **r == Types::Void)
+                        && matches!(op, Op::Eq | Op::Ne | Op::EqualEqual | Op::BangEqual) =>
                 {
                     Ok(Types::Bool)
                 }
@@ -205,7 +778,7 @@ impl CompilerContext {
                 )),
             },
             Op::Assign => {
-                if left == right {
+                if right.coerces_to(left) {
                     Ok(left.clone())
                 } else {
                     Err(SemanticError::invalid_binary_operation(
@@ -253,6 +826,7 @@ impl CompilerContext {
             Op::Sub | Op::Minus => match operand {
                 Types::Int => Ok(Types::Int),
                 Types::Float => Ok(Types::Float),
+                Types::Vector(..) => Ok(operand.clone()),
                 _ => Err(SemanticError::invalid_unary_operation(
                     format!("{op:?}"),
                     operand.to_string(),
@@ -277,7 +851,11 @@ impl CompilerContext {
             },
             Op::Ref => Ok(Types::Pointer(Box::new(operand.clone()))),
             Op::Deref => match operand {
-                Types::Pointer(inner) => Ok(*inner.clone()),
+                Types::Pointer(inner) if self.is_sized(inner) => Ok(*inner.clone()),
+                Types::Pointer(_) => Err(SemanticError::invalid_dereference(
+                    operand.to_string(),
+                    span,
+                )),
                 _ => Err(SemanticError::invalid_unary_operation(
                     format!("{op:?}"),
                     operand.to_string(),
@@ -301,6 +879,23 @@ impl CompilerContext {
     }
 }
 
+/// The path of an item as written in source. Methods are mangled as `method__Struct`.
+fn source_path(name: &Name) -> String {
+    let item = match name.link_name().split_once("__") {
+        Some((method, struct_name)) => format!("{struct_name}::{method}"),
+        None => name.link_name().to_string(),
+    };
+    match name.module() {
+        Some(module) => format!("{}::{item}", source_module(module)),
+        None => item,
+    }
+}
+
+/// A module qualifier as written in source: `net::http` for `net.http`.
+fn source_module(module: &str) -> String {
+    module.replace(['.', '$'], "::")
+}
+
 impl Default for CompilerContext {
     fn default() -> Self {
         Self::new()
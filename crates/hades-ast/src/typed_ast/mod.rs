@@ -9,8 +9,8 @@ mod value;
 
 pub use ast::*;
 pub use expr::{
-    TypedArrayIndex, TypedAsExpression, TypedAssignExpr, TypedAssignTarget, TypedBinaryExpr,
-    TypedExpr, TypedFieldAccess,
+    TypedArrayIndex, TypedAsExpression, TypedAsm, TypedAsmOutput, TypedAssignExpr,
+    TypedAssignTarget, TypedBinaryExpr, TypedExpr, TypedFieldAccess,
 };
 pub use function::{FuncKind, FunctionSignature, Functions, Params, TypedReceiver};
 pub use meta::{CompilerContext, ModulePath};
@@ -1,13 +1,23 @@
 use crate::typed_ast::TypedFieldKind;
 use hades_common::consts::GOOLAG_MESSAGE;
 use hades_tokens::{Ident, Name};
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 
 pub type Field = IndexMap<Ident, TypedFieldKind>;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Structs {
     inner: IndexMap<Name, Field>,
+    /// `extern type` declarations: named, but with no layout known to Hades.
+    opaque: IndexSet<Name>,
+    /// Structs declared with `export`, in declaration order.
+    exported: IndexSet<Name>,
+    /// `@deprecated` structs (no field) and fields, with their notes.
+    deprecated: IndexMap<(Name, Option<Ident>), Option<String>>,
+    /// Data fields declared without `pub`; the ident is the one from the declaration.
+    private: IndexSet<(Name, Ident)>,
+    /// `@packed` structs, which importers must lay out without padding too.
+    packed: IndexSet<Name>,
 }
 
 impl Default for Structs {
@@ -20,6 +30,11 @@ impl Structs {
     pub fn new() -> Self {
         Self {
             inner: IndexMap::new(),
+            opaque: IndexSet::new(),
+            exported: IndexSet::new(),
+            deprecated: IndexMap::new(),
+            private: IndexSet::new(),
+            packed: IndexSet::new(),
         }
     }
 
@@ -27,6 +42,85 @@ impl Structs {
         self.inner.insert(name, fields).is_none()
     }
 
+    pub fn insert_opaque(&mut self, name: Name) -> bool {
+        !self.inner.contains_key(&name) && self.opaque.insert(name)
+    }
+
+    pub fn is_opaque(&self, name: &Name) -> bool {
+        self.opaque.contains(name)
+    }
+
+    /// Whether `name` is a struct or an `extern type`.
+    pub fn declares(&self, name: &Name) -> bool {
+        self.inner.contains_key(name) || self.opaque.contains(name)
+    }
+
+    pub fn opaque_types(&self) -> impl Iterator<Item = &Name> {
+        self.opaque.iter()
+    }
+
+    pub fn export(&mut self, name: Name) {
+        self.exported.insert(name);
+    }
+
+    pub fn exported(&self) -> impl Iterator<Item = &Name> {
+        self.exported.iter()
+    }
+
+    pub fn pack(&mut self, name: Name) {
+        self.packed.insert(name);
+    }
+
+    pub fn is_packed(&self, name: &Name) -> bool {
+        self.packed.contains(name)
+    }
+
+    pub fn packed(&self) -> impl Iterator<Item = &Name> {
+        self.packed.iter()
+    }
+
+    pub fn deprecate(&mut self, name: Name, field: Option<Ident>, note: Option<String>) {
+        self.deprecated.insert((name, field), note);
+    }
+
+    /// `Some` if the struct, or the given field of it, is `@deprecated`.
+    pub fn deprecation(&self, name: &Name, field: Option<&Ident>) -> Option<Option<&str>> {
+        self.deprecated
+            .get(&(name.clone(), field.cloned()))
+            .map(Option::as_deref)
+    }
+
+    pub fn deprecations(&self) -> impl Iterator<Item = (&Name, Option<&Ident>, Option<&String>)> {
+        self.deprecated
+            .iter()
+            .map(|((name, field), note)| (name, field.as_ref(), note.as_ref()))
+    }
+
+    pub fn hide_field(&mut self, name: Name, field: Ident) {
+        self.private.insert((name, field));
+    }
+
+    /// The declaration of the field if it is private to the struct's module.
+    pub fn private_field(&self, name: &Name, field: &Ident) -> Option<&Ident> {
+        self.private
+            .get(&(name.clone(), field.clone()))
+            .map(|(_, declared)| declared)
+    }
+
+    pub fn private_fields(&self) -> impl Iterator<Item = (&Name, &Ident)> {
+        self.private.iter().map(|(name, field)| (name, field))
+    }
+
+    /// Keeps only the structs for which `keep` holds, along with everything recorded about them.
+    pub fn retain(&mut self, keep: impl Fn(&Name) -> bool) {
+        self.inner.retain(|name, _| keep(name));
+        self.opaque.retain(|name| keep(name));
+        self.exported.retain(|name| keep(name));
+        self.deprecated.retain(|(name, _), _| keep(name));
+        self.private.retain(|(name, _)| keep(name));
+        self.packed.retain(|name| keep(name));
+    }
+
     pub fn fields(&self, name: &Name) -> Option<&Field> {
         self.inner.get(name)
     }
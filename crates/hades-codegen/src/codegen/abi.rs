@@ -0,0 +1,291 @@
+use crate::codegen::context::LLVMContext;
+use crate::codegen::error::{CodegenError, CodegenResult, CodegenValue};
+use crate::codegen::types::{AbiClass, CAbiSignature};
+use hades_ast::{CScalar, FunctionSignature};
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::module::Linkage;
+use inkwell::types::{AnyType, BasicType, BasicTypeEnum};
+use inkwell::values::{
+    BasicMetadataValueEnum, BasicValueEnum, FunctionValue, PointerValue, StructValue,
+};
+
+impl<'ctx> LLVMContext<'ctx> {
+    /// Struct lowering follows the System V x86-64 ABI; other targets keep LLVM's
+    /// default handling of aggregates.
+    fn uses_sysv_abi(&self) -> bool {
+        let triple = self.module().get_triple();
+        let triple = triple.as_str().to_string_lossy();
+        triple.starts_with("x86_64") && !triple.contains("windows")
+    }
+
+    pub(crate) fn extern_signature(
+        &self,
+        sig: &FunctionSignature,
+        variadic: bool,
+    ) -> CodegenResult<CAbiSignature<'ctx>> {
+        if self.uses_sysv_abi() {
+            return self.type_converter().c_abi_signature(
+                sig,
+                self.module(),
+                self.target_data(),
+                variadic,
+            );
+        }
+
+        let param_types = self
+            .type_converter()
+            .extern_params_to_llvm_types(sig, self.module())?;
+        let fn_type = match self
+            .type_converter()
+            .extern_return_llvm_type(sig, self.module())?
+        {
+            Some(ret) => ret.fn_type(&param_types, variadic),
+            None => self
+                .type_converter()
+                .void_type()
+                .fn_type(&param_types, variadic),
+        };
+        Ok(CAbiSignature {
+            fn_type,
+            ret: AbiClass::Direct,
+            params: param_types.iter().map(|_| AbiClass::Direct).collect(),
+        })
+    }
+
+    pub(crate) fn declare_extern_fn(
+        &self,
+        link_name: &str,
+        sig: &FunctionSignature,
+        variadic: bool,
+    ) -> CodegenResult<FunctionValue<'ctx>> {
+        if let Some(function) = self.module().get_function(link_name) {
+            return Ok(function);
+        }
+
+        let abi = self.extern_signature(sig, variadic)?;
+        let function = self
+            .module()
+            .add_function(link_name, abi.fn_type, Some(Linkage::External));
+        for (loc, attr) in self.abi_attributes(&abi, sig) {
+            function.add_attribute(loc, attr);
+        }
+        Ok(function)
+    }
+
+    /// Calls an extern function, splitting struct arguments into their register pieces or
+    /// passing them through memory, and reassembling a struct result the same way. An `int`
+    /// or `float` is truncated to a narrower C parameter and a narrower result extended back.
+    pub(crate) fn build_extern_call(
+        &mut self,
+        function: FunctionValue<'ctx>,
+        sig: &FunctionSignature,
+        variadic: bool,
+        args: &[BasicMetadataValueEnum<'ctx>],
+    ) -> CodegenResult<CodegenValue<'ctx>> {
+        let abi = self.extern_signature(sig, variadic)?;
+        let mut call_args: Vec<BasicMetadataValueEnum<'ctx>> = Vec::with_capacity(args.len() + 1);
+
+        let sret = match &abi.ret {
+            AbiClass::Memory(ty) => {
+                let slot = self.create_alloca("sret", *ty)?;
+                call_args.push(slot.into());
+                Some((slot, *ty))
+            }
+            _ => None,
+        };
+
+        for (i, arg) in args.iter().enumerate() {
+            match (abi.params.get(i), arg) {
+                (Some(AbiClass::Coerce(pieces)), BasicMetadataValueEnum::StructValue(value)) => {
+                    let slot = self.spill_struct(*value)?;
+                    for (k, piece) in pieces.iter().enumerate() {
+                        let piece_ptr = self.eightbyte_ptr(slot, k)?;
+                        call_args.push(self.load(piece_ptr, *piece, "abi_arg_piece")?.into());
+                    }
+                }
+                (Some(AbiClass::Memory(_)), BasicMetadataValueEnum::StructValue(value)) => {
+                    call_args.push(self.spill_struct(*value)?.into());
+                }
+                _ => call_args.push(self.narrow_arg(*arg, sig.c_param(i))?),
+            }
+        }
+
+        let call_site = self
+            .builder()
+            .build_call(function, &call_args, "call")
+            .map_err(|_| CodegenError::LLVMBuild {
+                message: format!(
+                    "Failed to generate function call to {}",
+                    function.get_name().to_string_lossy()
+                ),
+            })?;
+        for (loc, attr) in self.abi_attributes(&abi, sig) {
+            call_site.add_attribute(loc, attr);
+        }
+
+        let return_type = sig.return_type().clone();
+        if let Some((slot, ty)) = sret {
+            let value = self.load(slot, ty, "sret_val")?;
+            return Ok(CodegenValue::new(value, return_type));
+        }
+
+        let Some(result) = call_site.try_as_basic_value().basic() else {
+            return Ok(CodegenValue::Void);
+        };
+        let AbiClass::Coerce(pieces) = &abi.ret else {
+            let result = self.widen_result(result, sig.c_return())?;
+            return Ok(CodegenValue::new(result, return_type));
+        };
+
+        let struct_type = self
+            .type_converter()
+            .to_llvm_type(&return_type, self.module())?;
+        let slot = self.create_alloca("abi_ret", struct_type)?;
+        if pieces.len() == 1 {
+            self.builder().build_store(slot, result)?;
+        } else {
+            for k in 0..pieces.len() {
+                let piece = self.builder().build_extract_value(
+                    result.into_struct_value(),
+                    k as u32,
+                    "abi_ret_piece",
+                )?;
+                let piece_ptr = self.eightbyte_ptr(slot, k)?;
+                self.builder().build_store(piece_ptr, piece)?;
+            }
+        }
+        let value = self.load(slot, struct_type, "abi_ret_val")?;
+        Ok(CodegenValue::new(value, return_type))
+    }
+
+    fn narrow_arg(
+        &self,
+        arg: BasicMetadataValueEnum<'ctx>,
+        scalar: Option<CScalar>,
+    ) -> CodegenResult<BasicMetadataValueEnum<'ctx>> {
+        Ok(match (scalar, arg) {
+            (Some(CScalar::Int { bits, .. }), BasicMetadataValueEnum::IntValue(value)) => {
+                let typ = self.type_converter().get_int_type(bits as u32);
+                self.builder()
+                    .build_int_truncate(value, typ, "c_arg")?
+                    .into()
+            }
+            (Some(CScalar::Float), BasicMetadataValueEnum::FloatValue(value)) => self
+                .builder()
+                .build_float_trunc(value, self.context().f32_type(), "c_arg")?
+                .into(),
+            _ => arg,
+        })
+    }
+
+    fn widen_result(
+        &self,
+        result: BasicValueEnum<'ctx>,
+        scalar: Option<CScalar>,
+    ) -> CodegenResult<BasicValueEnum<'ctx>> {
+        let int_type = self.context().i64_type();
+        Ok(match (scalar, result) {
+            (Some(CScalar::Int { signed: true, .. }), BasicValueEnum::IntValue(value)) => self
+                .builder()
+                .build_int_s_extend(value, int_type, "c_ret")?
+                .into(),
+            (Some(CScalar::Int { signed: false, .. }), BasicValueEnum::IntValue(value)) => self
+                .builder()
+                .build_int_z_extend(value, int_type, "c_ret")?
+                .into(),
+            (Some(CScalar::Float), BasicValueEnum::FloatValue(value)) => self
+                .builder()
+                .build_float_ext(value, self.context().f64_type(), "c_ret")?
+                .into(),
+            _ => result,
+        })
+    }
+
+    /// `sret` and `byval` carry the struct type; the declaration and every call site need them.
+    /// A C integer narrower than 32 bits is `signext` or `zeroext`, as C callers widen it.
+    fn abi_attributes(
+        &self,
+        abi: &CAbiSignature<'ctx>,
+        sig: &FunctionSignature,
+    ) -> Vec<(AttributeLoc, Attribute)> {
+        let mut attrs = Vec::new();
+        let mut index = 0;
+
+        if let AbiClass::Memory(ty) = &abi.ret {
+            attrs.push((AttributeLoc::Param(0), self.type_attribute("sret", *ty)));
+            index = 1;
+        }
+        if let Some(attr) = self.extension_attribute(sig.c_return()) {
+            attrs.push((AttributeLoc::Return, attr));
+        }
+        for (i, class) in abi.params.iter().enumerate() {
+            match class {
+                AbiClass::Direct => {
+                    if let Some(attr) = self.extension_attribute(sig.c_param(i)) {
+                        attrs.push((AttributeLoc::Param(index), attr));
+                    }
+                    index += 1;
+                }
+                AbiClass::Coerce(pieces) => index += pieces.len() as u32,
+                AbiClass::Memory(ty) => {
+                    let align = self.target_data().get_abi_alignment(ty).max(8);
+                    let align_kind = Attribute::get_named_enum_kind_id("align");
+                    attrs.push((
+                        AttributeLoc::Param(index),
+                        self.type_attribute("byval", *ty),
+                    ));
+                    attrs.push((
+                        AttributeLoc::Param(index),
+                        self.context()
+                            .create_enum_attribute(align_kind, align as u64),
+                    ));
+                    index += 1;
+                }
+            }
+        }
+        attrs
+    }
+
+    fn extension_attribute(&self, scalar: Option<CScalar>) -> Option<Attribute> {
+        let Some(CScalar::Int { bits, signed }) = scalar else {
+            return None;
+        };
+        let name = if signed { "signext" } else { "zeroext" };
+        (bits < 32).then(|| {
+            self.context()
+                .create_enum_attribute(Attribute::get_named_enum_kind_id(name), 0)
+        })
+    }
+
+    fn type_attribute(&self, name: &str, ty: BasicTypeEnum<'ctx>) -> Attribute {
+        let kind = Attribute::get_named_enum_kind_id(name);
+        self.context()
+            .create_type_attribute(kind, ty.as_any_type_enum())
+    }
+
+    fn spill_struct(&mut self, value: StructValue<'ctx>) -> CodegenResult<PointerValue<'ctx>> {
+        let slot = self.create_alloca("abi_arg", value.get_type().as_basic_type_enum())?;
+        self.builder().build_store(slot, value)?;
+        Ok(slot)
+    }
+
+    fn eightbyte_ptr(
+        &self,
+        base: PointerValue<'ctx>,
+        index: usize,
+    ) -> CodegenResult<PointerValue<'ctx>> {
+        if index == 0 {
+            return Ok(base);
+        }
+        let offset = self.context().i64_type().const_int(8 * index as u64, false);
+        let ptr = unsafe {
+            self.builder().build_in_bounds_gep(
+                self.context().i8_type(),
+                base,
+                &[offset],
+                "abi_piece_ptr",
+            )
+        }?;
+        Ok(ptr)
+    }
+}
@@ -0,0 +1,76 @@
+use super::{CodegenValue, CompileTimeBuiltin, type_arg};
+use crate::codegen::context::LLVMContext;
+use crate::codegen::error::{CodegenError, CodegenResult};
+use hades_ast::{TypedExpr, TypedValue, Types};
+use hades_tokens::Ident;
+use inkwell::types::BasicTypeEnum;
+
+pub struct SizeOf;
+
+pub struct AlignOf;
+
+pub struct OffsetOf;
+
+impl CompileTimeBuiltin for SizeOf {
+    fn call<'ctx>(
+        args: &[TypedExpr],
+        context: &mut LLVMContext<'ctx>,
+    ) -> CodegenResult<CodegenValue<'ctx>> {
+        let llvm_type = layout_type(type_arg(args, "sizeof")?, context)?;
+        let size = context.target_data().get_abi_size(&llvm_type);
+        Ok(const_int(size, context))
+    }
+}
+
+impl CompileTimeBuiltin for AlignOf {
+    fn call<'ctx>(
+        args: &[TypedExpr],
+        context: &mut LLVMContext<'ctx>,
+    ) -> CodegenResult<CodegenValue<'ctx>> {
+        let llvm_type = layout_type(type_arg(args, "alignof")?, context)?;
+        let align = context.target_data().get_abi_alignment(&llvm_type);
+        Ok(const_int(align as u64, context))
+    }
+}
+
+impl CompileTimeBuiltin for OffsetOf {
+    fn call<'ctx>(
+        args: &[TypedExpr],
+        context: &mut LLVMContext<'ctx>,
+    ) -> CodegenResult<CodegenValue<'ctx>> {
+        let typ = type_arg(args, "offsetof")?;
+        let (Types::Struct(struct_name), Some(TypedExpr::Value(TypedValue::String(field)))) =
+            (typ, args.get(1))
+        else {
+            return Err(CodegenError::LLVMBuild {
+                message: "offsetof requires a struct type and a field name".to_string(),
+            });
+        };
+
+        let index = context
+            .symbols()
+            .structs()
+            .field_index(struct_name, &Ident::new(field.clone(), Default::default()));
+        let struct_type = layout_type(typ, context)?.into_struct_type();
+        let offset = context
+            .target_data()
+            .offset_of_element(&struct_type, index as u32)
+            .ok_or_else(|| CodegenError::InvalidField {
+                field: field.clone(),
+                struct_name: struct_name.to_string(),
+            })?;
+        Ok(const_int(offset, context))
+    }
+}
+
+fn layout_type<'ctx>(
+    typ: &Types,
+    context: &LLVMContext<'ctx>,
+) -> CodegenResult<BasicTypeEnum<'ctx>> {
+    context.type_converter().to_llvm_type(typ, context.module())
+}
+
+fn const_int<'ctx>(value: u64, context: &LLVMContext<'ctx>) -> CodegenValue<'ctx> {
+    let val = context.context().i64_type().const_int(value, false).into();
+    CodegenValue::new(val, Types::Int)
+}
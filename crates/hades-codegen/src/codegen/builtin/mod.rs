@@ -1,11 +1,17 @@
+mod layout;
 mod len;
+mod type_name;
+mod vector;
 
 use super::context::LLVMContext;
-use super::error::{CodegenResult, CodegenValue};
-use hades_ast::TypedExpr;
+use super::error::{CodegenError, CodegenResult, CodegenValue};
+use hades_ast::{TypedExpr, Types};
 use indexmap::{IndexMap, indexmap};
+pub use layout::{AlignOf, OffsetOf, SizeOf};
 pub use len::Len;
 use once_cell::sync::Lazy;
+pub use type_name::TypeName;
+pub use vector::{Shuffle, Splat};
 
 pub trait CompileTimeBuiltin {
     fn call<'ctx>(
@@ -20,9 +26,26 @@ pub type CompileTimeHandler =
 pub static COMPILE_TIME_HANDLERS: Lazy<IndexMap<String, CompileTimeHandler>> = Lazy::new(|| {
     indexmap! {
         String::from("len") => Len::call as CompileTimeHandler,
+        String::from("sizeof") => SizeOf::call as CompileTimeHandler,
+        String::from("alignof") => AlignOf::call as CompileTimeHandler,
+        String::from("offsetof") => OffsetOf::call as CompileTimeHandler,
+        String::from("type_name") => TypeName::call as CompileTimeHandler,
+        String::from("splat") => Splat::call as CompileTimeHandler,
+        String::from("shuffle") => Shuffle::call as CompileTimeHandler,
     }
 });
 
+/// The type operand the type checker places first for `sizeof`, `alignof`, `offsetof`,
+/// `type_name` and `splat`.
+fn type_arg<'a>(args: &'a [TypedExpr], builtin: &str) -> CodegenResult<&'a Types> {
+    match args.first() {
+        Some(TypedExpr::Type(typ)) => Ok(typ),
+        _ => Err(CodegenError::LLVMBuild {
+            message: format!("{builtin} requires a type argument"),
+        }),
+    }
+}
+
 pub struct BuiltinRegistar;
 
 impl BuiltinRegistar {
@@ -0,0 +1,17 @@
+use super::{CodegenValue, CompileTimeBuiltin, type_arg};
+use crate::codegen::context::LLVMContext;
+use crate::codegen::error::CodegenResult;
+use crate::codegen::traits::Visit;
+use hades_ast::{TypedExpr, TypedValue};
+
+pub struct TypeName;
+
+impl CompileTimeBuiltin for TypeName {
+    fn call<'ctx>(
+        args: &[TypedExpr],
+        context: &mut LLVMContext<'ctx>,
+    ) -> CodegenResult<CodegenValue<'ctx>> {
+        let name = type_arg(args, "type_name")?.source_name();
+        TypedValue::String(name).visit(context)
+    }
+}
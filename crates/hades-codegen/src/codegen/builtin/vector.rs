@@ -0,0 +1,95 @@
+use super::{CodegenValue, CompileTimeBuiltin, type_arg};
+use crate::codegen::context::LLVMContext;
+use crate::codegen::error::{CodegenError, CodegenResult};
+use crate::codegen::traits::Visit;
+use hades_ast::{TypedExpr, TypedValue, Types};
+use inkwell::types::VectorType;
+
+pub struct Splat;
+
+impl CompileTimeBuiltin for Splat {
+    fn call<'ctx>(
+        args: &[TypedExpr],
+        context: &mut LLVMContext<'ctx>,
+    ) -> CodegenResult<CodegenValue<'ctx>> {
+        let typ = type_arg(args, "splat")?.clone();
+        let value = args
+            .get(1)
+            .ok_or(CodegenError::LLVMBuild {
+                message: "splat requires a lane value".to_string(),
+            })?
+            .visit(context)?
+            .value()?;
+
+        let vector_type = context
+            .type_converter()
+            .to_llvm_type(&typ, context.module())?
+            .into_vector_type();
+        let zero = context.context().i32_type().const_zero();
+        let single = context.builder().build_insert_element(
+            vector_type.get_undef(),
+            value,
+            zero,
+            "splat_insert",
+        )?;
+        // An all-zero mask copies lane 0 into every lane.
+        let mask = context
+            .context()
+            .i32_type()
+            .vec_type(vector_type.get_size())
+            .const_zero();
+        let splat = context.builder().build_shuffle_vector(
+            single,
+            vector_type.get_undef(),
+            mask,
+            "splat",
+        )?;
+
+        Ok(CodegenValue::new(splat.into(), typ))
+    }
+}
+
+pub struct Shuffle;
+
+impl CompileTimeBuiltin for Shuffle {
+    fn call<'ctx>(
+        args: &[TypedExpr],
+        context: &mut LLVMContext<'ctx>,
+    ) -> CodegenResult<CodegenValue<'ctx>> {
+        let [left, right, indices @ ..] = args else {
+            return Err(CodegenError::LLVMBuild {
+                message: "shuffle requires two vectors and a lane mask".to_string(),
+            });
+        };
+        let Types::Vector(elem, _) = left.get_type() else {
+            return Err(CodegenError::TypeMismatch {
+                expected: "vector type".to_string(),
+                actual: left.get_type().to_string(),
+            });
+        };
+
+        let left = left.visit(context)?.value()?.into_vector_value();
+        let right = right.visit(context)?.value()?.into_vector_value();
+        let i32_type = context.context().i32_type();
+        let mask = indices
+            .iter()
+            .map(|index| match index {
+                TypedExpr::Value(TypedValue::Number(n)) => Ok(i32_type.const_int(*n as u64, false)),
+                other => Err(CodegenError::LLVMBuild {
+                    message: format!("shuffle index must be a constant, found {other:?}"),
+                }),
+            })
+            .collect::<CodegenResult<Vec<_>>>()?;
+        let shuffled = context.builder().build_shuffle_vector(
+            left,
+            right,
+            VectorType::const_vector(&mask),
+            "shuffle",
+        )?;
+
+        Ok(CodegenValue::new(
+            shuffled.into(),
+            Types::Vector(elem, indices.len()),
+        ))
+    }
+}
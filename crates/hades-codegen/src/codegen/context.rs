@@ -2,19 +2,22 @@ use crate::codegen::error::{CodegenError, CodegenResult};
 use crate::codegen::symbols::{CodegenSymbols, LLVMVariable};
 use crate::codegen::types::TypeConverter;
 use hades_ast::Types;
-use hades_ast::{CompilerContext, FuncKind, FunctionSignature, TypedDefer};
+use hades_ast::{CompilerContext, FuncKind, FunctionSignature, TypedDefer, TypedFieldKind};
 use hades_semantic::ModuleSignatures;
 use hades_tokens::{Ident, Name};
 use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::module::{Linkage, Module};
+use inkwell::targets::TargetData;
 use inkwell::types::{BasicType, BasicTypeEnum, FunctionType};
 use inkwell::values::{BasicValueEnum, FunctionValue, PointerValue};
 
 pub struct LoopContext<'ctx> {
     pub continue_block: BasicBlock<'ctx>,
     pub break_block: BasicBlock<'ctx>,
+    pub result: Option<PointerValue<'ctx>>,
+    pub defer_depth: usize,
 }
 
 pub struct LLVMContext<'ctx> {
@@ -24,12 +27,13 @@ pub struct LLVMContext<'ctx> {
     symbols: &'ctx CompilerContext,
     codegen_symbols: CodegenSymbols<'ctx>,
     type_converter: TypeConverter<'ctx>,
+    target_data: TargetData,
     current_function: Option<FunctionContext<'ctx>>,
 }
 
 pub struct FunctionContext<'ctx> {
     pub function: FunctionValue<'ctx>,
-    pub defer: Vec<TypedDefer>,
+    pub defer_scopes: Vec<Vec<TypedDefer>>,
     pub loop_stack: Vec<LoopContext<'ctx>>,
 }
 
@@ -38,6 +42,7 @@ impl<'ctx> LLVMContext<'ctx> {
         compiler_context: &'ctx CompilerContext,
         context: &'ctx Context,
         module: Module<'ctx>,
+        target_data: TargetData,
     ) -> Self {
         let builder = context.create_builder();
         let type_converter = TypeConverter::new(context);
@@ -50,6 +55,7 @@ impl<'ctx> LLVMContext<'ctx> {
             module,
             codegen_symbols,
             type_converter,
+            target_data,
             current_function: None,
         }
     }
@@ -74,6 +80,11 @@ impl<'ctx> LLVMContext<'ctx> {
         &self.type_converter
     }
 
+    /// Data layout of the target machine the module is compiled for.
+    pub fn target_data(&self) -> &TargetData {
+        &self.target_data
+    }
+
     pub fn declare_variable(
         &mut self,
         name: Ident,
@@ -93,16 +104,9 @@ impl<'ctx> LLVMContext<'ctx> {
         sig: &FunctionSignature,
     ) -> CodegenResult<FunctionValue<'ctx>> {
         match &sig.kind {
-            FuncKind::Normal => {
-                self.module()
-                    .get_function(name)
-                    .ok_or(CodegenError::FunctionNotFound {
-                        name: name.to_string(),
-                    })
-            }
-            FuncKind::Extern { .. } => {
-                let link_name = Name::from_key(name, Default::default());
-                self.module().get_function(link_name.link_name()).ok_or(
+            FuncKind::Normal | FuncKind::Extern { .. } => {
+                let key = Name::from_key(name, Default::default());
+                self.module().get_function(sig.symbol_name(&key)).ok_or(
                     CodegenError::FunctionNotFound {
                         name: name.to_string(),
                     },
@@ -139,7 +143,7 @@ impl<'ctx> LLVMContext<'ctx> {
         let func_ctx = FunctionContext {
             function: func,
             loop_stack: Vec::new(),
-            defer: Vec::new(),
+            defer_scopes: Vec::new(),
         };
 
         self.current_function = Some(func_ctx);
@@ -161,15 +165,23 @@ impl<'ctx> LLVMContext<'ctx> {
         self.current_function = None;
     }
 
-    pub fn push_loop(&mut self, continue_block: BasicBlock<'ctx>, break_block: BasicBlock<'ctx>) {
+    pub fn push_loop(
+        &mut self,
+        continue_block: BasicBlock<'ctx>,
+        break_block: BasicBlock<'ctx>,
+        result: Option<PointerValue<'ctx>>,
+    ) {
         let current_func = self
             .current_function
             .as_mut()
             .expect("LOOP OUTSIDE FUNCTION");
 
+        let defer_depth = current_func.defer_scopes.len();
         current_func.loop_stack.push(LoopContext {
             continue_block,
             break_block,
+            result,
+            defer_depth,
         });
     }
 
@@ -305,6 +317,15 @@ impl<'ctx> LLVMContext<'ctx> {
         Ok(())
     }
 
+    pub fn build_unreachable(&self) -> CodegenResult<()> {
+        self.builder
+            .build_unreachable()
+            .map_err(|_| CodegenError::LLVMBuild {
+                message: "Failed to build unreachable".to_string(),
+            })?;
+        Ok(())
+    }
+
     pub fn get_current_block(&self) -> Option<BasicBlock<'ctx>> {
         self.builder.get_insert_block()
     }
@@ -343,7 +364,35 @@ impl<'ctx> LLVMContext<'ctx> {
         }
     }
 
+    /// Struct types are created by the module defining them, but modules that import each
+    /// other can be compiled in either order, so missing ones are laid out from the imports.
+    fn declare_imported_structs(&self, sigs: &[&ModuleSignatures]) -> CodegenResult<()> {
+        let mut created = Vec::new();
+        for sig in sigs {
+            for (name, fields) in sig.structs.iter() {
+                if self.module().get_struct_type(name.inner()).is_none() {
+                    let struct_type = self.context().opaque_struct_type(name.inner());
+                    created.push((struct_type, sig.structs.is_packed(name), fields));
+                }
+            }
+        }
+        for (struct_type, packed, fields) in created {
+            let body = fields
+                .values()
+                .filter_map(|field| match field {
+                    TypedFieldKind::Var(typ) => {
+                        Some(self.type_converter().to_llvm_type(typ, self.module()))
+                    }
+                    TypedFieldKind::Func(_) => None,
+                })
+                .collect::<CodegenResult<Vec<_>>>()?;
+            struct_type.set_body(&body, packed);
+        }
+        Ok(())
+    }
+
     pub fn declare_imports(&mut self, sigs: &[&ModuleSignatures]) -> CodegenResult<()> {
+        self.declare_imported_structs(sigs)?;
         for sig in sigs {
             for (name, fn_sig) in &sig.functions {
                 match &fn_sig.kind {
@@ -369,26 +418,12 @@ impl<'ctx> LLVMContext<'ctx> {
                                 message: format!("Failed to get declaration for '{}'", llvm_name),
                             })?;
                     }
-                    FuncKind::Extern { variadic } => {
-                        let link_name = name.link_name().to_string();
-                        if self.module().get_function(&link_name).is_some() {
-                            continue;
-                        }
-                        let variadic = *variadic;
-                        let _symbols = self.symbols();
-                        let param_types = self
-                            .type_converter()
-                            .params_to_llvm_types(fn_sig, &self.module)?;
-                        let fn_type = self.build_fn_type(
-                            &fn_sig.return_type.clone(),
-                            &param_types,
-                            variadic,
-                        )?;
-                        self.module()
-                            .add_function(&link_name, fn_type, Some(Linkage::External));
+                    FuncKind::Extern { variadic, .. } => {
+                        self.declare_extern_fn(name.link_name(), fn_sig, *variadic)?;
                     }
                     FuncKind::Normal => {
-                        if self.module().get_function(name.inner()).is_some() {
+                        let symbol = fn_sig.symbol_name(name);
+                        if self.module().get_function(symbol).is_some() {
                             continue;
                         }
                         let _symbols = self.symbols();
@@ -398,7 +433,7 @@ impl<'ctx> LLVMContext<'ctx> {
                         let fn_type =
                             self.build_fn_type(&fn_sig.return_type.clone(), &param_types, false)?;
                         self.module()
-                            .add_function(name.inner(), fn_type, Some(Linkage::External));
+                            .add_function(symbol, fn_type, Some(Linkage::External));
                     }
                 }
             }
@@ -408,15 +443,36 @@ impl<'ctx> LLVMContext<'ctx> {
 }
 
 impl FunctionContext<'_> {
+    pub fn enter_defer_scope(&mut self) {
+        self.defer_scopes.push(Vec::new());
+    }
+
+    pub fn exit_defer_scope(&mut self) -> Vec<TypedDefer> {
+        let mut scope = self.defer_scopes.pop().unwrap_or_default();
+        scope.reverse();
+        scope
+    }
+
     pub fn push_defer(&mut self, defer: TypedDefer) {
-        self.defer.push(defer);
+        self.defer_scopes
+            .last_mut()
+            .expect("defer outside of a block")
+            .push(defer);
     }
 
     pub fn pop_defer(&mut self) -> Option<TypedDefer> {
-        self.defer.pop()
+        self.defer_scopes.last_mut()?.pop()
     }
 
     pub fn defer_iter(&self) -> impl Iterator<Item = &TypedDefer> {
-        self.defer.iter().rev()
+        self.defer_iter_since(0)
+    }
+
+    /// Defers of every scope opened at or above `depth`, innermost first.
+    pub fn defer_iter_since(&self, depth: usize) -> impl Iterator<Item = &TypedDefer> {
+        self.defer_scopes[depth..]
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
     }
 }
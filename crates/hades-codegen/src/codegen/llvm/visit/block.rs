@@ -1,18 +1,30 @@
 use crate::codegen::context::LLVMContext;
-use crate::codegen::error::CodegenResult;
+use crate::codegen::error::{CodegenResult, CodegenValue};
 use crate::codegen::traits::Visit;
 use hades_ast::TypedBlock;
 
 impl Visit for TypedBlock {
-    type Output<'ctx> = ();
+    type Output<'ctx> = CodegenValue<'ctx>;
 
     fn visit<'ctx>(&self, context: &mut LLVMContext<'ctx>) -> CodegenResult<Self::Output<'ctx>> {
+        context.current_fn_mut_unchecked().enter_defer_scope();
         for stmt in &self.stmts.0 {
             stmt.visit(context)?;
             if context.is_block_terminated() {
-                break;
+                context.current_fn_mut_unchecked().exit_defer_scope();
+                return Ok(CodegenValue::Void);
             }
         }
-        Ok(())
+
+        let value = match &self.tail {
+            Some(tail) => tail.expr().visit(context)?,
+            None => CodegenValue::Void,
+        };
+
+        let deferred = context.current_fn_mut_unchecked().exit_defer_scope();
+        for defer in deferred {
+            defer.stmt.visit(context)?;
+        }
+        Ok(value)
     }
 }
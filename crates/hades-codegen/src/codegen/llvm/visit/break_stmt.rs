@@ -14,6 +14,15 @@ impl Visit for TypedBreak {
             })?;
 
         let break_block = loop_ctx.break_block;
+        let result = loop_ctx.result;
+        let defer_depth = loop_ctx.defer_depth;
+
+        if let Some(value) = &self.value {
+            let val = value.expr().visit(context)?;
+            context.store_result(result, &val, &value.expr().get_type())?;
+        }
+
+        context.emit_deferred_since(defer_depth)?;
         context.build_unconditional_branch(break_block)?;
         Ok(())
     }
@@ -14,6 +14,9 @@ impl Visit for TypedContinue {
             })?;
 
         let continue_block = loop_ctx.continue_block;
+        let defer_depth = loop_ctx.defer_depth;
+
+        context.emit_deferred_since(defer_depth)?;
         context.build_unconditional_branch(continue_block)?;
         Ok(())
     }
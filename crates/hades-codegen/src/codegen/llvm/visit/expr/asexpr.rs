@@ -25,6 +25,10 @@ impl Visit for TypedAsExpression {
             (Types::Char, Types::Int) => cast_char_to_int(context, value, target_type)?,
             (Types::Char, Types::Float) => cast_char_to_float(context, value, target_type)?,
             (Types::Float, Types::Int) => cast_float_to_int(context, value, target_type)?,
+            (Types::Pointer(_), Types::Int) => cast_ptr_to_int(context, value, target_type)?,
+            (Types::Int, Types::Pointer(_)) => cast_int_to_ptr(context, value, target_type)?,
+            // Pointers are opaque in LLVM, so only the pointee type changes.
+            (Types::Pointer(_), Types::Pointer(_)) => value.value()?,
             (_, _) => {
                 unreachable!(
                     "This should have been caught by the type checker. Invalid cast from {:?} to {:?}",
@@ -92,3 +96,31 @@ fn cast_char_to_float<'ctx>(
 
     Ok(casted_value.into())
 }
+
+fn cast_ptr_to_int<'ctx>(
+    context: &mut LLVMContext<'ctx>,
+    value: CodegenValue<'ctx>,
+    target_type: BasicTypeEnum<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let casted_value = context.builder().build_ptr_to_int(
+        value.value()?.into_pointer_value(),
+        target_type.into_int_type(),
+        "ptr_to_int_cast",
+    )?;
+
+    Ok(casted_value.into())
+}
+
+fn cast_int_to_ptr<'ctx>(
+    context: &mut LLVMContext<'ctx>,
+    value: CodegenValue<'ctx>,
+    target_type: BasicTypeEnum<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let casted_value = context.builder().build_int_to_ptr(
+        value.value()?.into_int_value(),
+        target_type.into_pointer_type(),
+        "int_to_ptr_cast",
+    )?;
+
+    Ok(casted_value.into())
+}
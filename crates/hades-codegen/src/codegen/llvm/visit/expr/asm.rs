@@ -0,0 +1,89 @@
+use inkwell::types::{BasicMetadataTypeEnum, BasicType};
+use inkwell::values::BasicMetadataValueEnum;
+
+use crate::codegen::context::LLVMContext;
+use crate::codegen::error::{CodegenError, CodegenResult, CodegenValue};
+use crate::codegen::traits::Visit;
+use hades_ast::TypedAsm;
+
+impl Visit for TypedAsm {
+    type Output<'ctx> = CodegenValue<'ctx>;
+
+    fn visit<'ctx>(&self, context: &mut LLVMContext<'ctx>) -> CodegenResult<Self::Output<'ctx>> {
+        let mut args: Vec<BasicMetadataValueEnum<'ctx>> = Vec::with_capacity(self.inputs.len());
+        let mut param_types: Vec<BasicMetadataTypeEnum<'ctx>> =
+            Vec::with_capacity(self.inputs.len());
+        for (_, input) in &self.inputs {
+            let value = input.visit(context)?.value()?;
+            param_types.push(value.get_type().into());
+            args.push(value.into());
+        }
+
+        let output_types = self
+            .outputs
+            .iter()
+            .map(|output| {
+                context
+                    .type_converter()
+                    .to_llvm_type(&output.typ, context.module())
+            })
+            .collect::<CodegenResult<Vec<_>>>()?;
+        // Several outputs come back from the asm call as one struct.
+        let fn_type = match output_types.as_slice() {
+            [] => context.context().void_type().fn_type(&param_types, false),
+            [single] => single.fn_type(&param_types, false),
+            many => context
+                .context()
+                .struct_type(many, false)
+                .fn_type(&param_types, false),
+        };
+
+        // LLVM numbers operands outputs first, then inputs; clobbers follow as `~{reg}`.
+        let constraints = self
+            .outputs
+            .iter()
+            .map(|output| output.constraint.clone())
+            .chain(self.inputs.iter().map(|(constraint, _)| constraint.clone()))
+            .chain(
+                self.clobbers
+                    .iter()
+                    .map(|clobber| format!("~{{{clobber}}}")),
+            )
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let asm = context.context().create_inline_asm(
+            fn_type,
+            self.template.clone(),
+            constraints,
+            true,
+            false,
+            None,
+            false,
+        );
+        let call_site = context
+            .builder()
+            .build_indirect_call(fn_type, asm, &args, "asm")
+            .map_err(|e| CodegenError::LLVMBuild {
+                message: format!("Failed to build inline assembly: {e:?}"),
+            })?;
+
+        let Some(result) = call_site.try_as_basic_value().basic() else {
+            return Ok(CodegenValue::Void);
+        };
+        for (i, output) in self.outputs.iter().enumerate() {
+            let value = if self.outputs.len() == 1 {
+                result
+            } else {
+                context.builder().build_extract_value(
+                    result.into_struct_value(),
+                    i as u32,
+                    "asm_out",
+                )?
+            };
+            let place = context.assign_target_ptr(&output.target)?;
+            context.builder().build_store(place.value(), value)?;
+        }
+        Ok(CodegenValue::Void)
+    }
+}
@@ -5,7 +5,7 @@ use crate::codegen::traits::Visit;
 use crate::codegen::{
     context::LLVMContext, llvm::visit::expr::variable::VariableAccess, symbols::LLVMVariable,
 };
-use hades_ast::{TypedAssignTarget, TypedExpr};
+use hades_ast::{TypedAssignTarget, TypedExpr, Types};
 use hades_common::VisitOptions;
 use hades_tokens::Op;
 
@@ -19,36 +19,42 @@ impl<'a> Assignment<'a> {
     pub fn new(target: &'a TypedAssignTarget, op: &'a Op, value: &'a TypedExpr) -> Self {
         Self { target, op, value }
     }
+}
 
-    fn get_target_ptr<'b>(&self, ctx: &mut LLVMContext<'b>) -> CodegenResult<LLVMVariable<'b>> {
-        match self.target {
-            TypedAssignTarget::Ident(ident) => ctx.get_variable(ident),
+impl<'ctx> LLVMContext<'ctx> {
+    /// Address of the place an assignment or inline assembly output writes to.
+    pub(crate) fn assign_target_ptr(
+        &mut self,
+        target: &TypedAssignTarget,
+    ) -> CodegenResult<LLVMVariable<'ctx>> {
+        match target {
+            TypedAssignTarget::Ident(ident) => self.get_variable(ident),
             TypedAssignTarget::FieldAccess(field) => {
-                let symbols = ctx.symbols();
+                let symbols = self.symbols();
 
                 let raw_ptr = match field.expr.as_ref() {
-                    hades_ast::TypedExpr::Ident { ident, .. } => ctx.get_variable(ident)?.value(),
-                    other => ctx.get_ptr(other)?,
+                    hades_ast::TypedExpr::Ident { ident, .. } => self.get_variable(ident)?.value(),
+                    other => self.get_ptr(other)?,
                 };
 
-                let struct_ptr = ctx.deref_if_pointer(raw_ptr, &field.expr.get_type())?;
+                let struct_ptr = self.deref_if_pointer(raw_ptr, &field.expr.get_type())?;
 
-                let struct_type = ctx
+                let struct_type = self
                     .type_converter()
-                    .to_llvm_type(&field.struct_type, ctx.module())?;
+                    .to_llvm_type(&field.struct_type, self.module())?;
 
                 let struct_name = field.struct_type.unwrap_struct_name();
                 let strct = symbols.structs();
                 let field_index = strct.field_index(struct_name, &field.field);
 
-                let zero = ctx.context().i32_type().const_zero();
-                let field_index_val = ctx
+                let zero = self.context().i32_type().const_zero();
+                let field_index_val = self
                     .context()
                     .i32_type()
                     .const_int(field_index as u64, false);
 
                 let field_ptr = unsafe {
-                    ctx.builder().build_in_bounds_gep(
+                    self.builder().build_in_bounds_gep(
                         struct_type,
                         struct_ptr,
                         &[zero, field_index_val],
@@ -62,32 +68,15 @@ impl<'a> Assignment<'a> {
                 Ok(LLVMVariable::new(field_ptr, field.field_type.clone()))
             }
             TypedAssignTarget::ArrayIndex(index) => {
-                let array_ptr = ctx.get_ptr(&index.expr)?;
-                let index_val = index.index.visit(ctx)?;
-                let elem_type = index.typ.get_array_elem_type();
-                let _symbols = ctx.symbols();
-                let array_type = ctx
-                    .type_converter()
-                    .to_llvm_type(&index.typ, ctx.module())?;
-                let zero = ctx.context().i32_type().const_zero();
-                let elem_ptr = unsafe {
-                    ctx.builder().build_in_bounds_gep(
-                        array_type,
-                        array_ptr,
-                        &[zero, index_val.value()?.into_int_value()],
-                        "array_assign_ptr",
-                    )
-                }
-                .map_err(|_| CodegenError::LLVMBuild {
-                    message: "Failed to create array element pointer for assignment".to_string(),
-                })?;
+                let elem_ptr = self.elem_ptr(index, "array_assign_ptr")?;
+                let elem_type = index.elem_type();
                 Ok(LLVMVariable::new(elem_ptr, elem_type))
             }
             TypedAssignTarget::Deref(inner) => {
                 // Evaluate the pointer expression, then load the pointer value stored in it.
                 // The result is the address we write through.
-                let ptr_holder = ctx.get_ptr(inner)?;
-                let _symbols = ctx.symbols();
+                let ptr_holder = self.get_ptr(inner)?;
+                let _symbols = self.symbols();
                 let pointee_type = match inner.get_type() {
                     hades_ast::Types::Pointer(t) => *t,
                     other => {
@@ -97,8 +86,8 @@ impl<'a> Assignment<'a> {
                     }
                 };
                 let llvm_ptr_type: inkwell::types::BasicTypeEnum =
-                    ctx.type_converter().ptr_type().into();
-                let loaded_ptr = ctx
+                    self.type_converter().ptr_type().into();
+                let loaded_ptr = self
                     .load(ptr_holder, llvm_ptr_type, "deref_assign_ptr")?
                     .into_pointer_value();
                 Ok(LLVMVariable::new(loaded_ptr, pointee_type))
@@ -111,9 +100,14 @@ impl<'a> Visit for Assignment<'a> {
     type Output<'ctx> = CodegenValue<'ctx>;
     fn visit<'ctx>(&self, context: &mut LLVMContext<'ctx>) -> CodegenResult<Self::Output<'ctx>> {
         let value_val = self.value.visit(context)?;
-        let var_ptr = self.get_target_ptr(context)?;
+        let var_ptr = context.assign_target_ptr(self.target)?;
 
         let current_value = self.target.visit(context)?;
+        if matches!(self.op, Op::PlusEqual | Op::MinusEqual)
+            && let Types::Pointer(pointee) = current_value.unwrap_concrete()?.type_info()
+        {
+            return self.offset_pointer(context, var_ptr, &current_value, pointee, value_val);
+        }
         match self.op {
             Op::PlusEqual => {
                 let new_value = generate_add(context, current_value.value()?, value_val.value()?)?;
@@ -162,6 +156,42 @@ impl<'a> Visit for Assignment<'a> {
     }
 }
 
+impl<'a> Assignment<'a> {
+    /// `p += n` and `p -= n` step the pointer by whole elements.
+    fn offset_pointer<'ctx>(
+        &self,
+        context: &mut LLVMContext<'ctx>,
+        var_ptr: LLVMVariable<'ctx>,
+        current_value: &CodegenValue<'ctx>,
+        pointee: &Types,
+        value_val: CodegenValue<'ctx>,
+    ) -> CodegenResult<CodegenValue<'ctx>> {
+        let offset = value_val.value()?.into_int_value();
+        let offset = match self.op {
+            Op::MinusEqual => context.builder().build_int_neg(offset, "ptr_neg_offset")?,
+            _ => offset,
+        };
+        let new_value = context
+            .build_ptr_offset(
+                current_value.value()?.into_pointer_value(),
+                pointee,
+                offset,
+                "ptr_offset_assign",
+            )?
+            .into();
+        context
+            .builder()
+            .build_store(var_ptr.value(), new_value)
+            .map_err(|e| CodegenError::LLVMBuild {
+                message: format!("Failed to build store for assignment: {e:?}"),
+            })?;
+        Ok(CodegenValue::new(
+            new_value,
+            current_value.unwrap_concrete()?.type_info().clone(),
+        ))
+    }
+}
+
 impl Visit for TypedAssignTarget {
     type Output<'ctx> = CodegenValue<'ctx>;
     fn visit<'ctx>(&self, context: &mut LLVMContext<'ctx>) -> CodegenResult<Self::Output<'ctx>> {
@@ -225,6 +255,18 @@ fn generate_add<'a>(
                 })?;
             Ok(BasicValueEnum::FloatValue(new_value))
         }
+        BasicValueEnum::VectorValue(curr) => {
+            let rhs = add.into_vector_value();
+            let new_value = if curr.get_type().get_element_type().is_float_type() {
+                context.builder().build_float_add(curr, rhs, "add_assign")
+            } else {
+                context.builder().build_int_add(curr, rhs, "add_assign")
+            }
+            .map_err(|e| CodegenError::LLVMBuild {
+                message: format!("Failed to build add for assignment: {e:?}"),
+            })?;
+            Ok(BasicValueEnum::VectorValue(new_value))
+        }
         _ => Ok(value),
     }
 }
@@ -232,27 +274,39 @@ fn generate_add<'a>(
 fn generic_sub<'a>(
     context: &mut LLVMContext<'a>,
     value: BasicValueEnum<'a>,
-    add: BasicValueEnum<'a>,
+    sub: BasicValueEnum<'a>,
 ) -> CodegenResult<BasicValueEnum<'a>> {
     match value {
         BasicValueEnum::IntValue(curr) => {
             let new_value = context
                 .builder()
-                .build_int_sub(curr, add.into_int_value(), "sub_assign")
+                .build_int_sub(curr, sub.into_int_value(), "sub_assign")
                 .map_err(|e| CodegenError::LLVMBuild {
-                    message: format!("Failed to build add for assignment: {e:?}"),
+                    message: format!("Failed to build sub for assignment: {e:?}"),
                 })?;
             Ok(BasicValueEnum::IntValue(new_value))
         }
         BasicValueEnum::FloatValue(curr) => {
             let new_value = context
                 .builder()
-                .build_float_sub(curr, add.into_float_value(), "sub_assign")
+                .build_float_sub(curr, sub.into_float_value(), "sub_assign")
                 .map_err(|e| CodegenError::LLVMBuild {
-                    message: format!("Failed to build add for assignment: {e:?}"),
+                    message: format!("Failed to build sub for assignment: {e:?}"),
                 })?;
             Ok(BasicValueEnum::FloatValue(new_value))
         }
+        BasicValueEnum::VectorValue(curr) => {
+            let rhs = sub.into_vector_value();
+            let new_value = if curr.get_type().get_element_type().is_float_type() {
+                context.builder().build_float_sub(curr, rhs, "sub_assign")
+            } else {
+                context.builder().build_int_sub(curr, rhs, "sub_assign")
+            }
+            .map_err(|e| CodegenError::LLVMBuild {
+                message: format!("Failed to build sub for assignment: {e:?}"),
+            })?;
+            Ok(BasicValueEnum::VectorValue(new_value))
+        }
         _ => Ok(value),
     }
 }
@@ -7,7 +7,7 @@ use hades_error::Span;
 use hades_tokens::Op;
 use inkwell::FloatPredicate;
 use inkwell::IntPredicate;
-use inkwell::values::{BasicValueEnum, FloatValue, IntValue};
+use inkwell::values::{BasicValueEnum, FloatValue, IntValue, PointerValue, VectorValue};
 
 pub struct BinaryOp<'a> {
     pub left: &'a TypedExpr,
@@ -58,6 +58,54 @@ impl<'a> Visit for BinaryOp<'a> {
                 right_val.value()?.into_int_value(),
                 context,
             )?,
+            (Types::Vector(elem, _), Types::Vector(..)) => generate_vector_binary_op(
+                left_val.value()?.into_vector_value(),
+                self.op,
+                right_val.value()?.into_vector_value(),
+                **elem == Types::Float,
+                context,
+            )?,
+            (Types::Pointer(pointee), Types::Int) => {
+                let offset = match self.op {
+                    Op::Sub | Op::Minus => context
+                        .builder()
+                        .build_int_neg(right_val.value()?.into_int_value(), "ptr_neg_offset")?,
+                    _ => right_val.value()?.into_int_value(),
+                };
+                context
+                    .build_ptr_offset(
+                        left_val.value()?.into_pointer_value(),
+                        pointee,
+                        offset,
+                        "ptr_offset",
+                    )?
+                    .into()
+            }
+            (Types::Int, Types::Pointer(pointee)) => context
+                .build_ptr_offset(
+                    right_val.value()?.into_pointer_value(),
+                    pointee,
+                    left_val.value()?.into_int_value(),
+                    "ptr_offset",
+                )?
+                .into(),
+            (Types::Pointer(pointee), Types::Pointer(_))
+                if matches!(self.op, Op::Sub | Op::Minus) =>
+            {
+                let _symbols = context.symbols();
+                let elem_type = context
+                    .type_converter()
+                    .to_llvm_type(pointee, context.module())?;
+                context
+                    .builder()
+                    .build_ptr_diff(
+                        elem_type,
+                        left_val.value()?.into_pointer_value(),
+                        right_val.value()?.into_pointer_value(),
+                        "ptr_diff",
+                    )?
+                    .into()
+            }
             (Types::Pointer(_), Types::Pointer(_)) => {
                 let i64_type = context.context().i64_type();
                 let lhs_int = context
@@ -83,6 +131,10 @@ impl<'a> Visit for BinaryOp<'a> {
                 let pred = match self.op {
                     Op::Eq | Op::EqualEqual => IntPredicate::EQ,
                     Op::Ne | Op::BangEqual => IntPredicate::NE,
+                    Op::Lt | Op::Less => IntPredicate::ULT,
+                    Op::Le | Op::LessEqual => IntPredicate::ULE,
+                    Op::Gt | Op::Greater => IntPredicate::UGT,
+                    Op::Ge | Op::GreaterEqual => IntPredicate::UGE,
                     _ => {
                         return Err(CodegenError::LLVMBuild {
                             message: format!("Unsupported pointer comparison op: {:?}", self.op),
@@ -109,6 +161,20 @@ impl<'a> Visit for BinaryOp<'a> {
     }
 }
 
+impl<'ctx> LLVMContext<'ctx> {
+    /// Advances `ptr` by `offset` elements of `pointee`, as C pointer arithmetic does.
+    pub(crate) fn build_ptr_offset(
+        &self,
+        ptr: PointerValue<'ctx>,
+        pointee: &Types,
+        offset: IntValue<'ctx>,
+        name: &str,
+    ) -> CodegenResult<PointerValue<'ctx>> {
+        let elem_type = self.type_converter().to_llvm_type(pointee, self.module())?;
+        Ok(unsafe { self.builder().build_gep(elem_type, ptr, &[offset], name)? })
+    }
+}
+
 fn generate_int_binary_op<'ctx>(
     left: IntValue<'ctx>,
     op: &Op,
@@ -305,6 +371,39 @@ fn generate_float_binary_op<'ctx>(
     Ok(result)
 }
 
+/// Element-wise arithmetic on two vectors of the same shape.
+fn generate_vector_binary_op<'ctx>(
+    left: VectorValue<'ctx>,
+    op: &Op,
+    right: VectorValue<'ctx>,
+    is_float: bool,
+    context: &mut LLVMContext<'ctx>,
+) -> CodegenResult<BasicValueEnum<'ctx>> {
+    let builder = context.builder();
+    let result = match (op, is_float) {
+        (Op::Add | Op::Plus | Op::PlusEqual, true) => builder.build_float_add(left, right, "vfadd"),
+        (Op::Add | Op::Plus | Op::PlusEqual, false) => builder.build_int_add(left, right, "vadd"),
+        (Op::Sub | Op::Minus | Op::MinusEqual, true) => {
+            builder.build_float_sub(left, right, "vfsub")
+        }
+        (Op::Sub | Op::Minus | Op::MinusEqual, false) => builder.build_int_sub(left, right, "vsub"),
+        (Op::Mul | Op::Multiply, true) => builder.build_float_mul(left, right, "vfmul"),
+        (Op::Mul | Op::Multiply, false) => builder.build_int_mul(left, right, "vmul"),
+        (Op::Div | Op::Divide, true) => builder.build_float_div(left, right, "vfdiv"),
+        (Op::Div | Op::Divide, false) => builder.build_int_signed_div(left, right, "vdiv"),
+        _ => {
+            return Err(CodegenError::LLVMBuild {
+                message: format!("Unsupported vector operation: {:?}", op),
+            });
+        }
+    }
+    .map_err(|e| CodegenError::LLVMBuild {
+        message: format!("Vector {:?} failed: {:?}", op, e),
+    })?;
+
+    Ok(result.into())
+}
+
 fn generate_bool_binary_op<'ctx>(
     left: IntValue<'ctx>,
     op: &Op,
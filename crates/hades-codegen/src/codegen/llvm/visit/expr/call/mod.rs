@@ -6,6 +6,7 @@ pub use method::MethodCall;
 
 use crate::codegen::context::LLVMContext;
 use crate::codegen::error::{CodegenError, CodegenResult, CodegenValue};
+use hades_ast::FuncKind;
 use inkwell::values::BasicMetadataValueEnum;
 
 pub fn build_call<'ctx>(
@@ -23,6 +24,10 @@ pub fn build_call<'ctx>(
         .clone();
 
     let function = context.get_function(name, &sig)?;
+    if let FuncKind::Extern { variadic, .. } = sig.kind {
+        return context.build_extern_call(function, &sig, variadic, arg_values);
+    }
+
     let call_site = context
         .builder()
         .build_call(function, arg_values, "call")
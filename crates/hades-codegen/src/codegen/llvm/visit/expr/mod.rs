@@ -8,6 +8,7 @@ use inkwell::AddressSpace;
 use inkwell::values::PointerValue;
 
 pub mod asexpr;
+pub mod asm;
 pub mod assign;
 pub mod binary;
 pub mod call;
@@ -40,6 +41,39 @@ impl<'ctx> LLVMContext<'ctx> {
         }
     }
 
+    /// Address of `base[i]`. Arrays and vectors are indexed in place, pointers are loaded and
+    /// offset.
+    pub(super) fn elem_ptr(
+        &mut self,
+        index: &TypedArrayIndex,
+        name: &str,
+    ) -> CodegenResult<PointerValue<'ctx>> {
+        if let Types::Pointer(pointee) = &index.typ {
+            let base = index.expr.visit(self)?.value()?.into_pointer_value();
+            let offset = index.index.visit(self)?.value()?.into_int_value();
+            return self.build_ptr_offset(base, pointee, offset, name);
+        }
+
+        let array_ptr = self.get_ptr(&index.expr)?;
+        let index_value = index.index.visit(self)?;
+        let _symbols = self.symbols();
+        let array_type = self
+            .type_converter()
+            .to_llvm_type(&index.typ, self.module())?;
+        let zero = self.context().i32_type().const_zero();
+        unsafe {
+            self.builder().build_in_bounds_gep(
+                array_type,
+                array_ptr,
+                &[zero, index_value.value()?.into_int_value()],
+                name,
+            )
+        }
+        .map_err(|_| CodegenError::LLVMBuild {
+            message: "Failed to create array element pointer".to_string(),
+        })
+    }
+
     pub(super) fn get_ptr(&mut self, expr: &TypedExpr) -> CodegenResult<PointerValue<'ctx>> {
         if let TypedExpr::Ident { ident, .. } = expr {
             return self.get_variable(ident).map(|v| v.value());
@@ -88,24 +122,7 @@ impl<'ctx> LLVMContext<'ctx> {
             });
         }
         if let TypedExpr::ArrayIndex(index) = expr {
-            let array_ptr = self.get_ptr(&index.expr)?;
-            let index_value = index.index.visit(self)?;
-            let _symbols = self.symbols();
-            let array_type = self
-                .type_converter()
-                .to_llvm_type(&index.typ, self.module())?;
-            let zero = self.context().i32_type().const_zero();
-            return unsafe {
-                self.builder().build_in_bounds_gep(
-                    array_type,
-                    array_ptr,
-                    &[zero, index_value.value()?.into_int_value()],
-                    "array_elem_ptr",
-                )
-            }
-            .map_err(|_| CodegenError::LLVMBuild {
-                message: "Failed to create array element lval pointer".to_string(),
-            });
+            return self.elem_ptr(index, "array_elem_ptr");
         }
         let val = expr.visit(self)?;
         if let Ok(ptr) = val.value()?.try_into() {
@@ -175,6 +192,13 @@ impl Visit for TypedExpr {
                 Ok(CodegenValue::new(ptr.into(), typ.clone()))
             }
             Self::As(as_expr) => as_expr.visit(context),
+            Self::If(if_expr) => if_expr.visit(context),
+            Self::Block(block) => block.visit(context),
+            Self::Loop(loop_expr) => loop_expr.visit(context),
+            Self::Asm(asm) => asm.visit(context),
+            Self::Type(typ) => Err(CodegenError::LLVMBuild {
+                message: format!("Type {typ} cannot be used as a value"),
+            }),
         }
     }
 }
@@ -190,29 +214,24 @@ impl Visit for TypedArrayIndex {
     type Output<'ctx> = CodegenValue<'ctx>;
 
     fn visit<'ctx>(&self, context: &mut LLVMContext<'ctx>) -> CodegenResult<Self::Output<'ctx>> {
-        let array_ptr = context.get_ptr(&self.expr)?;
-        let index_value = self.index.visit(context)?;
+        if let Types::Vector(..) = &self.typ {
+            let vector = self.expr.visit(context)?.value()?.into_vector_value();
+            let lane = self.index.visit(context)?.value()?.into_int_value();
+            let elem = context
+                .builder()
+                .build_extract_element(vector, lane, "lane")?;
+            return Ok(CodegenValue::new(elem, self.elem_type()));
+        }
+
+        let elem_ptr = context.elem_ptr(self, "array_elem_ptr")?;
         let _symbols = context.symbols();
         let elem_type = context
             .type_converter()
-            .to_llvm_type(&self.typ.get_array_elem_type(), context.module())?;
-        let array_type = context
-            .type_converter()
-            .to_llvm_type(&self.typ, context.module())?;
-
-        let zero = context.context().i32_type().const_zero();
-        let elem_ptr = unsafe {
-            context.builder().build_in_bounds_gep(
-                array_type,
-                array_ptr,
-                &[zero, index_value.value()?.into_int_value()],
-                "array_elem_ptr",
-            )?
-        };
+            .to_llvm_type(&self.elem_type(), context.module())?;
 
         context
             .load(elem_ptr, elem_type, "array_elem")
-            .map(|val| CodegenValue::new(val, self.typ.get_array_elem_type()))
+            .map(|val| CodegenValue::new(val, self.elem_type()))
     }
 }
 
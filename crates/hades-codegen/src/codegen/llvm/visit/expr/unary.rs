@@ -84,6 +84,18 @@ impl<'a> UnaryOp<'a> {
                     message: format!("Float neg failed: {:?}", e),
                 })?
                 .into(),
+            (Types::Vector(elem, _), Op::Minus | Op::Sub) if **elem == Types::Float => builder
+                .build_float_neg(operand_val.value()?.into_vector_value(), "vfneg")
+                .map_err(|e| CodegenError::LLVMBuild {
+                    message: format!("Vector neg failed: {:?}", e),
+                })?
+                .into(),
+            (Types::Vector(..), Op::Minus | Op::Sub) => builder
+                .build_int_neg(operand_val.value()?.into_vector_value(), "vneg")
+                .map_err(|e| CodegenError::LLVMBuild {
+                    message: format!("Vector neg failed: {:?}", e),
+                })?
+                .into(),
             (Types::Bool, Op::Not) => {
                 let true_val = context.context().bool_type().const_all_ones();
                 builder
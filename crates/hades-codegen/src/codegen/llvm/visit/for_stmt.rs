@@ -23,7 +23,7 @@ impl Visit for TypedFor {
         context.build_conditional_branch(cond_int.into(), loop_body, loop_exit)?;
 
         context.position_at_end(loop_body);
-        context.push_loop(loop_update, loop_exit);
+        context.push_loop(loop_update, loop_exit, None);
         self.body.visit(context)?;
         context.pop_loop();
 
@@ -1,7 +1,26 @@
 use crate::codegen::context::LLVMContext;
-use crate::codegen::error::{CodegenError, CodegenResult};
+use crate::codegen::error::{CodegenError, CodegenResult, CodegenValue};
 use crate::codegen::traits::Visit;
-use hades_ast::{FuncKind, TypedFuncDef, TypedReturn};
+use hades_ast::{Attribute, Attributes, FuncKind, TypedFuncDef, TypedReturn};
+use inkwell::attributes::AttributeLoc;
+use inkwell::values::FunctionValue;
+
+impl<'ctx> LLVMContext<'ctx> {
+    /// Adds the LLVM function attributes for `@inline`, `@noinline` and `@cold`.
+    pub(crate) fn apply_fn_attributes(&self, function: FunctionValue<'ctx>, attrs: &Attributes) {
+        for attr in attrs.iter() {
+            let kind = match attr {
+                Attribute::Inline => "alwaysinline",
+                Attribute::NoInline => "noinline",
+                Attribute::Cold => "cold",
+                _ => continue,
+            };
+            let kind_id = inkwell::attributes::Attribute::get_named_enum_kind_id(kind);
+            let llvm_attr = self.context().create_enum_attribute(kind_id, 0);
+            function.add_attribute(AttributeLoc::Function, llvm_attr);
+        }
+    }
+}
 
 impl Visit for TypedFuncDef {
     type Output<'ctx> = inkwell::values::FunctionValue<'ctx>;
@@ -14,14 +33,10 @@ impl Visit for TypedFuncDef {
             .params_to_llvm_types(&signature, context.module())?;
 
         match &signature.kind {
-            FuncKind::Extern { variadic } => {
-                let fn_type =
-                    context.build_fn_type(&signature.return_type, &param_types, *variadic)?;
-                let function = context.module().add_function(
-                    self.name.link_name(),
-                    fn_type,
-                    Some(inkwell::module::Linkage::External),
-                );
+            FuncKind::Extern { variadic, .. } => {
+                let function =
+                    context.declare_extern_fn(self.name.link_name(), &signature, *variadic)?;
+                context.apply_fn_attributes(function, &signature.attributes);
                 Ok(function)
             }
             FuncKind::Intrinsic(llvm_name) => {
@@ -48,9 +63,11 @@ impl Visit for TypedFuncDef {
             FuncKind::Normal => {
                 let fn_type = context.build_fn_type(&signature.return_type, &param_types, false)?;
 
-                let function = context
-                    .module()
-                    .add_function(self.name.inner(), fn_type, None);
+                let function =
+                    context
+                        .module()
+                        .add_function(signature.symbol_name(&self.name), fn_type, None);
+                context.apply_fn_attributes(function, &signature.attributes);
 
                 context.set_current_function(function);
 
@@ -99,12 +116,14 @@ impl Visit for TypedFuncDef {
                 }
 
                 let body = self.body.as_ref().expect("Normal function has no body");
-                body.visit(context)?;
+                let tail = body.visit(context)?;
 
-                if !context.is_block_terminated()
-                    && self.signature.return_type == hades_ast::Types::Void
-                {
-                    TypedReturn::void(self.span.clone()).visit(context)?;
+                if !context.is_block_terminated() {
+                    if self.signature.return_type == hades_ast::Types::Void {
+                        TypedReturn::void(self.span.clone()).visit(context)?;
+                    } else if let CodegenValue::Concrete(_) = tail {
+                        context.build_return(Some(tail.value()?))?;
+                    }
                 }
 
                 context.clear_current_function();
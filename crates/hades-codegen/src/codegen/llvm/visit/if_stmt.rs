@@ -1,15 +1,18 @@
 use crate::codegen::context::LLVMContext;
-use crate::codegen::error::CodegenResult;
+use crate::codegen::error::{CodegenResult, CodegenValue};
 use crate::codegen::traits::Visit;
-use hades_ast::TypedIf;
+use hades_ast::{TypedIf, Types};
+use inkwell::values::PointerValue;
 
 impl Visit for TypedIf {
-    type Output<'ctx> = ();
+    type Output<'ctx> = CodegenValue<'ctx>;
 
     fn visit<'ctx>(&self, context: &mut LLVMContext<'ctx>) -> CodegenResult<Self::Output<'ctx>> {
         let cond_val = self.cond.expr().visit(context)?;
         let cond_int = cond_val.value()?.into_int_value();
 
+        let result = context.create_result_slot(&self.typ, "if.result")?;
+
         let then_block = context.create_basic_block("if.then");
         let else_block = context.create_basic_block("if.else");
         let merge_block = context.create_basic_block("if.merge");
@@ -23,20 +26,73 @@ impl Visit for TypedIf {
         context.build_conditional_branch(cond_int.into(), then_block, final_else_block)?;
 
         context.position_at_end(then_block);
-        self.then_branch.visit(context)?;
-        if !context.is_block_terminated() {
+        let then_val = self.then_branch.visit(context)?;
+        let mut reaches_merge = !context.is_block_terminated();
+        if reaches_merge {
+            context.store_result(result, &then_val, &self.typ)?;
             context.build_unconditional_branch(merge_block)?;
         }
 
         if let Some(else_branch) = &self.else_branch {
             context.position_at_end(else_block);
-            else_branch.visit(context)?;
+            let else_val = else_branch.visit(context)?;
             if !context.is_block_terminated() {
+                reaches_merge = true;
+                context.store_result(result, &else_val, &self.typ)?;
                 context.build_unconditional_branch(merge_block)?;
             }
+        } else {
+            reaches_merge = true;
         }
 
         context.position_at_end(merge_block);
+        if !reaches_merge {
+            context.build_unreachable()?;
+            return Ok(CodegenValue::Void);
+        }
+        context.load_result(result, &self.typ, "if.value")
+    }
+}
+
+impl<'ctx> LLVMContext<'ctx> {
+    /// Allocates the slot that the branches of a value-yielding `if` or `loop` write into.
+    pub(crate) fn create_result_slot(
+        &mut self,
+        typ: &Types,
+        name: &str,
+    ) -> CodegenResult<Option<PointerValue<'ctx>>> {
+        if *typ == Types::Void {
+            return Ok(None);
+        }
+        let llvm_type = self.type_converter().to_llvm_type(typ, self.module())?;
+        self.create_alloca(name, llvm_type).map(Some)
+    }
+
+    pub(crate) fn store_result(
+        &self,
+        slot: Option<PointerValue<'ctx>>,
+        value: &CodegenValue<'ctx>,
+        typ: &Types,
+    ) -> CodegenResult<()> {
+        if let (Some(ptr), CodegenValue::Concrete(_)) = (slot, value) {
+            self.create_store(ptr, value.value()?, typ)?;
+        }
         Ok(())
     }
+
+    pub(crate) fn load_result(
+        &self,
+        slot: Option<PointerValue<'ctx>>,
+        typ: &Types,
+        name: &str,
+    ) -> CodegenResult<CodegenValue<'ctx>> {
+        match slot {
+            Some(ptr) => {
+                let llvm_type = self.type_converter().to_llvm_type(typ, self.module())?;
+                self.load(ptr, llvm_type, name)
+                    .map(|val| CodegenValue::new(val, typ.clone()))
+            }
+            None => Ok(CodegenValue::Void),
+        }
+    }
 }
@@ -0,0 +1,33 @@
+use crate::codegen::context::LLVMContext;
+use crate::codegen::error::{CodegenResult, CodegenValue};
+use crate::codegen::traits::Visit;
+use hades_ast::TypedLoop;
+
+impl Visit for TypedLoop {
+    type Output<'ctx> = CodegenValue<'ctx>;
+
+    fn visit<'ctx>(&self, context: &mut LLVMContext<'ctx>) -> CodegenResult<Self::Output<'ctx>> {
+        let result = context.create_result_slot(&self.typ, "loop.result")?;
+
+        let loop_body = context.create_basic_block("loop.body");
+        let loop_exit = context.create_basic_block("loop.exit");
+
+        context.build_unconditional_branch(loop_body)?;
+
+        context.position_at_end(loop_body);
+        context.push_loop(loop_body, loop_exit, result);
+        self.body.visit(context)?;
+        context.pop_loop();
+
+        if !context.is_block_terminated() {
+            context.build_unconditional_branch(loop_body)?;
+        }
+
+        context.position_at_end(loop_exit);
+        if !self.breaks {
+            context.build_unreachable()?;
+            return Ok(CodegenValue::Void);
+        }
+        context.load_result(result, &self.typ, "loop.value")
+    }
+}
@@ -8,6 +8,7 @@ pub mod for_stmt;
 pub mod func_def;
 pub mod if_stmt;
 pub mod let_stmt;
+pub mod loop_expr;
 pub mod program;
 pub mod return_stmt;
 pub mod stmt;
@@ -5,6 +5,7 @@ use hades_ast::TypedProgram;
 use hades_semantic::{ModuleSignatures, TypedModule};
 use inkwell::{
     OptimizationLevel,
+    passes::PassBuilderOptions,
     targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine},
 };
 
@@ -17,15 +18,52 @@ impl Visit for TypedProgram {
     }
 }
 
+fn target_machine(opt_level: OptimizationLevel) -> CodegenResult<TargetMachine> {
+    Target::initialize_all(&InitializationConfig::default());
+
+    let triple = TargetMachine::get_default_triple();
+    let target = Target::from_triple(&triple).map_err(|e| CodegenError::LLVMBuild {
+        message: format!("Failed to get target from triple: {e}"),
+    })?;
+    target
+        .create_target_machine(
+            &triple,
+            "generic",
+            "",
+            opt_level,
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .ok_or_else(|| CodegenError::LLVMBuild {
+            message: "Failed to create target machine".to_string(),
+        })
+}
+
 fn build<'ctx>(
     typed_module: &'ctx TypedModule,
     import_sigs: &[&ModuleSignatures],
     llvm_ctx: &'ctx inkwell::context::Context,
+    target_machine: &TargetMachine,
 ) -> CodegenResult<LLVMContext<'ctx>> {
     let llvm_module = llvm_ctx.create_module(&typed_module.path.to_string());
-    let mut context = LLVMContext::new(&typed_module.ctx, llvm_ctx, llvm_module);
+    let target_data = target_machine.get_target_data();
+    llvm_module.set_triple(&target_machine.get_triple());
+    llvm_module.set_data_layout(&target_data.get_data_layout());
+
+    let mut context = LLVMContext::new(&typed_module.ctx, llvm_ctx, llvm_module, target_data);
     context.declare_imports(import_sigs)?;
     typed_module.program.visit(&mut context)?;
+    // `@inline` functions are folded into their callers even when nothing else is optimized.
+    context
+        .module()
+        .run_passes(
+            "always-inline",
+            target_machine,
+            PassBuilderOptions::create(),
+        )
+        .map_err(|e| CodegenError::LLVMBuild {
+            message: format!("Failed to run inliner: {e}"),
+        })?;
     Ok(context)
 }
 
@@ -34,7 +72,8 @@ pub fn emit_ir<'ctx>(
     import_sigs: &[&ModuleSignatures],
     llvm_ctx: &'ctx inkwell::context::Context,
 ) -> CodegenResult<String> {
-    let context = build(typed_module, import_sigs, llvm_ctx)?;
+    let target_machine = target_machine(OptimizationLevel::Default)?;
+    let context = build(typed_module, import_sigs, llvm_ctx, &target_machine)?;
     Ok(context.module().print_to_string().to_string())
 }
 
@@ -43,27 +82,10 @@ pub fn compile<'ctx>(
     import_sigs: &[&ModuleSignatures],
     llvm_ctx: &'ctx inkwell::context::Context,
     output_path: &std::path::Path,
+    opt_level: OptimizationLevel,
 ) -> CodegenResult<()> {
-    Target::initialize_all(&InitializationConfig::default());
-
-    let context = build(typed_module, import_sigs, llvm_ctx)?;
-
-    let triple = TargetMachine::get_default_triple();
-    let target = Target::from_triple(&triple).map_err(|e| CodegenError::LLVMBuild {
-        message: format!("Failed to get target from triple: {e}"),
-    })?;
-    let target_machine = target
-        .create_target_machine(
-            &triple,
-            "generic",
-            "",
-            OptimizationLevel::Default,
-            RelocMode::PIC,
-            CodeModel::Default,
-        )
-        .ok_or_else(|| CodegenError::LLVMBuild {
-            message: "Failed to create target machine".to_string(),
-        })?;
+    let target_machine = target_machine(opt_level)?;
+    let context = build(typed_module, import_sigs, llvm_ctx, &target_machine)?;
 
     target_machine
         .write_to_file(context.module(), FileType::Object, output_path)
@@ -7,14 +7,7 @@ impl Visit for TypedReturn {
     type Output<'ctx> = ();
 
     fn visit<'ctx>(&self, context: &mut LLVMContext<'ctx>) -> CodegenResult<Self::Output<'ctx>> {
-        let defer_stmts: Vec<TypedBlock> = context
-            .current_function_unchecked()
-            .defer_iter()
-            .map(|d| d.stmt.clone())
-            .collect();
-        for block in defer_stmts {
-            block.visit(context)?;
-        }
+        context.emit_deferred()?;
 
         match &self.expr {
             Some(expr) => {
@@ -28,3 +21,23 @@ impl Visit for TypedReturn {
         Ok(())
     }
 }
+
+impl<'ctx> LLVMContext<'ctx> {
+    pub(crate) fn emit_deferred(&mut self) -> CodegenResult<()> {
+        self.emit_deferred_since(0)
+    }
+
+    /// Runs the defers of every scope opened at or above `depth`, as `break` and `continue`
+    /// leave those scopes.
+    pub(crate) fn emit_deferred_since(&mut self, depth: usize) -> CodegenResult<()> {
+        let defer_stmts: Vec<TypedBlock> = self
+            .current_function_unchecked()
+            .defer_iter_since(depth)
+            .map(|d| d.stmt.clone())
+            .collect();
+        for block in defer_stmts {
+            block.visit(self)?;
+        }
+        Ok(())
+    }
+}
@@ -10,10 +10,16 @@ impl Visit for TypedStmt {
         match self {
             Self::Let(let_stmt) => let_stmt.visit(context),
             Self::TypedExpr(expr) => expr.visit(context),
-            Self::If(if_stmt) => if_stmt.visit(context),
+            Self::If(if_stmt) => {
+                if_stmt.visit(context)?;
+                Ok(())
+            }
             Self::While(while_stmt) => while_stmt.visit(context),
             Self::For(for_stmt) => for_stmt.visit(context),
-            Self::Block(block) => block.visit(context),
+            Self::Block(block) => {
+                block.visit(context)?;
+                Ok(())
+            }
             Self::Return(return_stmt) => return_stmt.visit(context),
             Self::Continue(continue_stmt) => continue_stmt.visit(context),
             Self::FuncDef(func_def) => {
@@ -24,6 +30,7 @@ impl Visit for TypedStmt {
             Self::Break(break_stmt) => break_stmt.visit(context),
             Self::ModuleDecl(_) => Ok(()),
             Self::Import(_) => Ok(()),
+            Self::ExternType(_) => Ok(()),
             Self::Defer(d) => d.visit(context),
         }
     }
@@ -7,27 +7,31 @@ impl Visit for TypedStructDef {
     type Output<'ctx> = ();
 
     fn visit<'ctx>(&self, context: &mut LLVMContext<'ctx>) -> CodegenResult<Self::Output<'ctx>> {
-        let opaque_struct = context.context().opaque_struct_type(self.name.inner());
-        opaque_struct.set_body(
-            &self
-                .fields
-                .iter()
-                .filter_map(|(_, field)| match field {
-                    TypedFieldKind::Func(method) => {
-                        method.visit(context).ok()?;
-                        None
-                    }
-                    TypedFieldKind::Var(_) => {
-                        let typ = field.get_type();
-                        context
-                            .type_converter()
-                            .to_llvm_type(&typ, context.module())
-                            .ok()
-                    }
-                })
-                .collect::<Vec<_>>(),
-            false,
-        );
+        // A module importing this one may have been compiled first and laid the struct out.
+        let struct_type = context
+            .module()
+            .get_struct_type(self.name.inner())
+            .unwrap_or_else(|| context.context().opaque_struct_type(self.name.inner()));
+        let body = self
+            .fields
+            .iter()
+            .filter_map(|(_, field)| match field {
+                TypedFieldKind::Func(method) => {
+                    method.visit(context).ok()?;
+                    None
+                }
+                TypedFieldKind::Var(_) => {
+                    let typ = field.get_type();
+                    context
+                        .type_converter()
+                        .to_llvm_type(&typ, context.module())
+                        .ok()
+                }
+            })
+            .collect::<Vec<_>>();
+        if struct_type.is_opaque() {
+            struct_type.set_body(&body, self.packed);
+        }
         Ok(())
     }
 }
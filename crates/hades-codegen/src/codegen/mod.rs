@@ -1,3 +1,4 @@
+mod abi;
 mod builtin;
 mod context;
 mod error;
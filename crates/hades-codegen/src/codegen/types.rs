@@ -2,16 +2,39 @@ use std::num::NonZeroU32;
 
 use crate::codegen::error::{CodegenError, CodegenResult};
 use hades_ast::FunctionSignature;
-use hades_ast::{ArrayType, Types};
+use hades_ast::{ArrayType, CScalar, Types};
 use hades_tokens::{Name, ParamKind};
 use inkwell::AddressSpace;
 use inkwell::context::Context;
 use inkwell::module::Module;
+use inkwell::targets::TargetData;
 use inkwell::types::{
     AnyTypeEnum, BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FloatType, FunctionType, IntType,
     StructType,
 };
 
+/// Registers the System V x86-64 ABI sets aside for arguments.
+const SYSV_INT_REGS: usize = 6;
+const SYSV_SSE_REGS: usize = 8;
+
+/// How a value crosses a C call boundary under the System V x86-64 ABI.
+#[derive(Debug, Clone)]
+pub enum AbiClass<'ctx> {
+    /// Passed as its own LLVM type.
+    Direct,
+    /// Split into eightbyte pieces that travel in registers; a 16-byte vector is one piece.
+    Coerce(Vec<BasicTypeEnum<'ctx>>),
+    /// Passed in memory: `byval` for a parameter, `sret` for the return value.
+    Memory(BasicTypeEnum<'ctx>),
+}
+
+/// An extern function type lowered for the C ABI, with the class of every value.
+pub struct CAbiSignature<'ctx> {
+    pub fn_type: FunctionType<'ctx>,
+    pub ret: AbiClass<'ctx>,
+    pub params: Vec<AbiClass<'ctx>>,
+}
+
 pub struct TypeConverter<'ctx> {
     context: &'ctx Context,
 }
@@ -90,6 +113,10 @@ impl<'ctx> TypeConverter<'ctx> {
                 });
             }
             Types::Pointer(_) => self.ptr_type().into(),
+            Types::Vector(elem, lanes) => match **elem {
+                Types::Float => self.context.f64_type().vec_type(*lanes as u32).into(),
+                _ => self.context.i64_type().vec_type(*lanes as u32).into(),
+            },
         };
 
         Ok(llvm_type)
@@ -189,6 +216,190 @@ impl<'ctx> TypeConverter<'ctx> {
         }
     }
 
+    /// Lowers an extern signature so that structs passed or returned by value follow the
+    /// System V x86-64 classification instead of LLVM's default aggregate handling.
+    pub fn c_abi_signature(
+        &self,
+        sig: &FunctionSignature,
+        module: &Module<'ctx>,
+        data: &TargetData,
+        variadic: bool,
+    ) -> CodegenResult<CAbiSignature<'ctx>> {
+        let mut int_regs = SYSV_INT_REGS;
+        let mut sse_regs = SYSV_SSE_REGS;
+        let mut fn_params: Vec<BasicMetadataTypeEnum<'ctx>> = Vec::new();
+
+        let return_type = self.extern_return_llvm_type(sig, module)?;
+        let ret = match return_type {
+            None => AbiClass::Direct,
+            Some(typ) => self.classify(typ, data),
+        };
+        if matches!(ret, AbiClass::Memory(_)) {
+            // The hidden result pointer takes the first integer register.
+            int_regs -= 1;
+            fn_params.push(self.ptr_type().into());
+        }
+
+        let mut params = Vec::new();
+        for param_type in self.extern_params_to_llvm_types(sig, module)? {
+            let llvm_type = BasicTypeEnum::try_from(param_type).expect("param is not a basic type");
+            let class = match self.classify(llvm_type, data) {
+                AbiClass::Coerce(pieces) => {
+                    let sse = pieces.iter().filter(|p| !p.is_int_type()).count();
+                    let int = pieces.len() - sse;
+                    // A struct is never split between registers and the stack.
+                    if int <= int_regs && sse <= sse_regs {
+                        int_regs -= int;
+                        sse_regs -= sse;
+                        AbiClass::Coerce(pieces)
+                    } else {
+                        AbiClass::Memory(llvm_type)
+                    }
+                }
+                AbiClass::Direct => {
+                    if llvm_type.is_float_type() || llvm_type.is_vector_type() {
+                        sse_regs = sse_regs.saturating_sub(1);
+                    } else {
+                        int_regs = int_regs.saturating_sub(1);
+                    }
+                    AbiClass::Direct
+                }
+                memory => memory,
+            };
+
+            match &class {
+                AbiClass::Direct => fn_params.push(param_type),
+                AbiClass::Coerce(pieces) => fn_params.extend(pieces.iter().map(|p| (*p).into())),
+                AbiClass::Memory(_) => fn_params.push(self.ptr_type().into()),
+            }
+            params.push(class);
+        }
+
+        let fn_type = match (&ret, return_type) {
+            (AbiClass::Coerce(pieces), _) => {
+                self.coerced_type(pieces).fn_type(&fn_params, variadic)
+            }
+            (AbiClass::Memory(_), _) | (AbiClass::Direct, None) => {
+                self.void_type().fn_type(&fn_params, variadic)
+            }
+            (AbiClass::Direct, Some(typ)) => typ.fn_type(&fn_params, variadic),
+        };
+
+        Ok(CAbiSignature {
+            fn_type,
+            ret,
+            params,
+        })
+    }
+
+    /// The single LLVM type that carries a coerced value: the piece itself, or a pair of them.
+    pub fn coerced_type(&self, pieces: &[BasicTypeEnum<'ctx>]) -> BasicTypeEnum<'ctx> {
+        match pieces {
+            [single] => *single,
+            _ => self.context.struct_type(pieces, false).into(),
+        }
+    }
+
+    fn classify(&self, ty: BasicTypeEnum<'ctx>, data: &TargetData) -> AbiClass<'ctx> {
+        if !ty.is_struct_type() {
+            return AbiClass::Direct;
+        }
+        let size = data.get_abi_size(&ty);
+        if size == 0 {
+            return AbiClass::Direct;
+        }
+        if size > 16 {
+            return AbiClass::Memory(ty);
+        }
+
+        let mut scalars = Vec::new();
+        if !self.flatten(ty, 0, data, &mut scalars) {
+            // A `@packed` struct with a misaligned field never travels in registers.
+            return AbiClass::Memory(ty);
+        }
+
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        while start < size {
+            // A vector wider than an eightbyte fills a whole SSE register (SSE + SSEUP).
+            if let Some((_, vector)) = scalars
+                .iter()
+                .find(|(offset, t)| *offset == start && t.is_vector_type())
+                .filter(|(_, t)| data.get_abi_size(t) > 8)
+            {
+                pieces.push(*vector);
+                start += data.get_abi_size(vector);
+                continue;
+            }
+
+            let end = (start + 8).min(size);
+            let eightbyte: Vec<_> = scalars
+                .iter()
+                .filter(|(offset, _)| (start..end).contains(offset))
+                .map(|(offset, t)| (*offset - start, *t))
+                .collect();
+            let sse = eightbyte
+                .iter()
+                .all(|(_, t)| t.is_float_type() || t.is_vector_type());
+            pieces.push(if sse {
+                self.sse_piece(&eightbyte, end - start, data)
+            } else {
+                self.get_int_type((end - start) as u32 * 8).into()
+            });
+            start = end;
+        }
+        AbiClass::Coerce(pieces)
+    }
+
+    /// The register type of an SSE eightbyte: its only scalar when that fills it, otherwise
+    /// the `float`s it holds.
+    fn sse_piece(
+        &self,
+        eightbyte: &[(u64, BasicTypeEnum<'ctx>)],
+        bytes: u64,
+        data: &TargetData,
+    ) -> BasicTypeEnum<'ctx> {
+        match eightbyte {
+            [(0, only)] if data.get_abi_size(only) == bytes => *only,
+            _ if eightbyte.iter().all(|(offset, _)| *offset < 4) => self.context.f32_type().into(),
+            _ => self.context.f32_type().vec_type(2).into(),
+        }
+    }
+
+    /// Collects every scalar of `ty` with its byte offset. Vectors are kept whole, since the
+    /// ABI classifies them as SSE whatever their lanes. Returns `false` when a field sits off
+    /// its natural alignment, which only a `@packed` struct can do.
+    fn flatten(
+        &self,
+        ty: BasicTypeEnum<'ctx>,
+        offset: u64,
+        data: &TargetData,
+        scalars: &mut Vec<(u64, BasicTypeEnum<'ctx>)>,
+    ) -> bool {
+        match ty {
+            BasicTypeEnum::StructType(st) => {
+                st.get_field_types()
+                    .into_iter()
+                    .enumerate()
+                    .all(|(i, field)| {
+                        let field_offset =
+                            data.offset_of_element(&st, i as u32).unwrap_or_default();
+                        self.flatten(field, offset + field_offset, data, scalars)
+                    })
+            }
+            BasicTypeEnum::ArrayType(at) => {
+                let elem = at.get_element_type();
+                let elem_size = data.get_abi_size(&elem);
+                (0..at.len() as u64)
+                    .all(|i| self.flatten(elem, offset + i * elem_size, data, scalars))
+            }
+            scalar => {
+                scalars.push((offset, scalar));
+                offset % data.get_abi_alignment(&scalar) as u64 == 0
+            }
+        }
+    }
+
     pub fn params_to_llvm_types(
         &self,
         sig: &FunctionSignature,
@@ -208,4 +419,43 @@ impl<'ctx> TypeConverter<'ctx> {
         }
         Ok(param_types)
     }
+    /// The parameter types of an extern function as C declares them: a narrower C integer or
+    /// `float` in place of the `int` or `float` Hades passes.
+    pub fn extern_params_to_llvm_types(
+        &self,
+        sig: &FunctionSignature,
+        module: &Module<'ctx>,
+    ) -> CodegenResult<Vec<BasicMetadataTypeEnum<'ctx>>> {
+        Ok(self
+            .params_to_llvm_types(sig, module)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, typ)| match sig.c_param(i) {
+                Some(scalar) => self.c_scalar_type(scalar).into(),
+                None => typ,
+            })
+            .collect())
+    }
+
+    /// The return type of an extern function as C declares it, or `None` for `void`.
+    pub fn extern_return_llvm_type(
+        &self,
+        sig: &FunctionSignature,
+        module: &Module<'ctx>,
+    ) -> CodegenResult<Option<BasicTypeEnum<'ctx>>> {
+        if let Some(scalar) = sig.c_return() {
+            return Ok(Some(self.c_scalar_type(scalar)));
+        }
+        match sig.return_type() {
+            Types::Void => Ok(None),
+            typ => self.to_llvm_type(typ, module).map(Some),
+        }
+    }
+
+    pub fn c_scalar_type(&self, scalar: CScalar) -> BasicTypeEnum<'ctx> {
+        match scalar {
+            CScalar::Int { bits, .. } => self.get_int_type(bits as u32).into(),
+            CScalar::Float => self.get_float_type(32).into(),
+        }
+    }
 }
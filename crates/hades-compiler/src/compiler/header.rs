@@ -0,0 +1,206 @@
+use hades_ast::{TypedFieldKind, Types};
+use hades_module::ModuleSignatures;
+use hades_tokens::Name;
+
+/// Builds a C header declaring every `export` struct and function of `modules`.
+pub(crate) fn generate(guard: &str, modules: &[&ModuleSignatures]) -> Result<String, String> {
+    let mut header = CHeader {
+        exported: modules
+            .iter()
+            .flat_map(|sigs| sigs.structs.exported().cloned())
+            .collect(),
+        ..Default::default()
+    };
+    for sigs in modules {
+        header.add_module(sigs)?;
+    }
+    Ok(header.render(guard))
+}
+
+struct CStruct {
+    name: String,
+    definition: String,
+    /// Structs held by value, which C needs complete before this one.
+    by_value: Vec<String>,
+}
+
+#[derive(Default)]
+struct CHeader {
+    exported: Vec<Name>,
+    /// `typedef struct X X;` lines, so structs may refer to each other through pointers.
+    forward: Vec<String>,
+    structs: Vec<CStruct>,
+    functions: Vec<(String, String)>,
+}
+
+impl CHeader {
+    fn add_module(&mut self, sigs: &ModuleSignatures) -> Result<(), String> {
+        for name in sigs.structs.exported() {
+            let c_name = name.link_name().to_string();
+            if self.structs.iter().any(|existing| existing.name == c_name) {
+                continue;
+            }
+            let fields = sigs
+                .structs
+                .fields(name)
+                .ok_or_else(|| format!("exported struct {c_name} has no definition"))?;
+
+            let mut body = String::new();
+            let mut by_value = Vec::new();
+            for (field, kind) in fields {
+                if let TypedFieldKind::Var(typ) = kind {
+                    body.push_str(&format!("    {};\n", self.declarator(typ, field.inner())?));
+                    held_structs(typ, &mut by_value);
+                }
+            }
+            self.forward_declare(&c_name);
+            self.structs.push(CStruct {
+                definition: if sigs.structs.is_packed(name) {
+                    format!("struct __attribute__((packed)) {c_name} {{\n{body}}};\n")
+                } else {
+                    format!("struct {c_name} {{\n{body}}};\n")
+                },
+                name: c_name,
+                by_value,
+            });
+        }
+
+        for (name, sig) in &sigs.functions {
+            let c_name = name.link_name().to_string();
+            if !sig.export
+                || self
+                    .functions
+                    .iter()
+                    .any(|(existing, _)| *existing == c_name)
+            {
+                continue;
+            }
+            let params = sig
+                .to_fixed_params()
+                .iter()
+                .map(|(param, typ)| self.declarator(typ, param.name().inner()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let params = if params.is_empty() {
+                "void".to_string()
+            } else {
+                params.join(", ")
+            };
+            let prototype = self.declarator(sig.return_type(), &format!("{c_name}({params})"))?;
+            self.functions.push((c_name, format!("{prototype};\n")));
+        }
+        Ok(())
+    }
+
+    fn declarator(&mut self, typ: &Types, name: &str) -> Result<String, String> {
+        if let Types::Array(_) = typ {
+            let elem = self.declarator(&typ.get_array_elem_type(), name)?;
+            return Ok(format!("{elem}[{}]", typ.get_array_size()));
+        }
+        let c_type = self.c_type(typ, false)?;
+        if c_type.ends_with('*') {
+            Ok(format!("{c_type}{name}"))
+        } else {
+            Ok(format!("{c_type} {name}"))
+        }
+    }
+
+    fn c_type(&mut self, typ: &Types, behind_pointer: bool) -> Result<String, String> {
+        let c_type = match typ {
+            Types::Int => "int64_t".to_string(),
+            Types::Float => "double".to_string(),
+            Types::Bool => "bool".to_string(),
+            Types::Char => "char".to_string(),
+            Types::String => "const char *".to_string(),
+            Types::Void => "void".to_string(),
+            Types::Pointer(inner) => {
+                let inner = self.c_type(inner, true)?;
+                if inner.ends_with('*') {
+                    format!("{inner}*")
+                } else {
+                    format!("{inner} *")
+                }
+            }
+            Types::Struct(name) => {
+                let c_name = name.link_name().to_string();
+                if !self.exported.contains(name) {
+                    // Only a pointer to an unexported struct can be spelled in C.
+                    if !behind_pointer {
+                        return Err(format!(
+                            "struct {c_name} is used by value in an exported declaration but is not exported"
+                        ));
+                    }
+                    self.forward_declare(&c_name);
+                }
+                c_name
+            }
+            Types::Array(_) | Types::Vector(..) | Types::Generic(_) | Types::Self_ => {
+                return Err(format!("type {} has no C equivalent", typ.source_name()));
+            }
+        };
+        Ok(c_type)
+    }
+
+    fn forward_declare(&mut self, c_name: &str) {
+        let line = format!("typedef struct {c_name} {c_name};\n");
+        if !self.forward.contains(&line) {
+            self.forward.push(line);
+        }
+    }
+
+    /// Structs in an order where each follows every struct it holds by value.
+    fn ordered_structs(&self) -> Vec<&CStruct> {
+        let mut order = Vec::new();
+        for s in &self.structs {
+            self.visit(s, &mut order);
+        }
+        order
+    }
+
+    fn visit<'a>(&'a self, s: &'a CStruct, order: &mut Vec<&'a CStruct>) {
+        if order.iter().any(|done| done.name == s.name) {
+            return;
+        }
+        for held in &s.by_value {
+            if let Some(dep) = self.structs.iter().find(|dep| dep.name == *held) {
+                self.visit(dep, order);
+            }
+        }
+        order.push(s);
+    }
+
+    fn render(&self, guard: &str) -> String {
+        let mut out = String::new();
+        out.push_str("/* Generated by `hades header`. Do not edit. */\n");
+        out.push_str(&format!("#ifndef {guard}\n#define {guard}\n\n"));
+        out.push_str("#include <stdbool.h>\n#include <stdint.h>\n");
+
+        let sections = [
+            self.forward.concat(),
+            self.ordered_structs()
+                .iter()
+                .map(|s| s.definition.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            self.functions
+                .iter()
+                .map(|(_, prototype)| prototype.as_str())
+                .collect(),
+        ];
+        for section in sections.iter().filter(|s| !s.is_empty()) {
+            out.push('\n');
+            out.push_str(section);
+        }
+
+        out.push_str(&format!("\n#endif /* {guard} */\n"));
+        out
+    }
+}
+
+/// The struct types `typ` contains by value, through arrays but not pointers.
+fn held_structs(typ: &Types, out: &mut Vec<String>) {
+    match typ {
+        Types::Struct(name) => out.push(name.link_name().to_string()),
+        Types::Array(_) => held_structs(&typ.get_array_elem_type(), out),
+        _ => {}
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Extra inputs for the final `clang` link step, on top of the compiled modules.
+#[derive(Debug, Clone, Default)]
+pub struct LinkOptions {
+    /// Libraries passed as `-l<name>`.
+    pub libraries: Vec<String>,
+    /// Directories passed as `-L<dir>`.
+    pub search_paths: Vec<PathBuf>,
+    /// C sources or object files handed to clang as-is.
+    pub inputs: Vec<PathBuf>,
+}
+
+impl LinkOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends these options, plus the libraries named by `@link` markers, to `cmd`.
+    pub(crate) fn apply<'a>(&self, cmd: &mut Command, linked: impl Iterator<Item = &'a str>) {
+        for input in &self.inputs {
+            cmd.arg(input);
+        }
+        for dir in &self.search_paths {
+            cmd.arg(format!("-L{}", dir.display()));
+        }
+
+        let mut libraries: Vec<&str> = self.libraries.iter().map(String::as_str).collect();
+        for lib in linked {
+            if !libraries.contains(&lib) {
+                libraries.push(lib);
+            }
+        }
+        for lib in libraries {
+            cmd.arg(format!("-l{lib}"));
+        }
+    }
+}
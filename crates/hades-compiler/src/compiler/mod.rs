@@ -1,13 +1,20 @@
+mod header;
+mod link;
+
+pub use hades_module::CfgSet;
+pub use link::LinkOptions;
+
 use ariadne::{Cache, Source};
 use hades_ast::ModulePath as AstModulePath;
 use hades_codegen::llvm::visit::program as codegen;
 use hades_common as consts;
 use hades_error::ErrorSeverity;
 use hades_lexer as lexer;
-use hades_module::{Module, ModulePath, Registry};
+use hades_module::{Module, ModuleError, ModulePath, Profile, Project, Registry};
 use hades_parser as parser;
 use hades_semantic::analyzer::{Analyzer, Unprepared};
 use hades_semantic::lint::LintDiagnostic;
+use inkwell::OptimizationLevel;
 use inkwell::context::Context;
 use std::path::PathBuf;
 use std::{
@@ -102,7 +109,11 @@ impl Cache<PathBuf> for FileCache {
     }
 }
 
-pub struct Compiler {}
+pub struct Compiler {
+    link: LinkOptions,
+    cfg: CfgSet,
+    stdlib: Option<PathBuf>,
+}
 
 impl Default for Compiler {
     fn default() -> Self {
@@ -112,7 +123,36 @@ impl Default for Compiler {
 
 impl<'a> Compiler {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            link: LinkOptions::new(),
+            cfg: CfgSet::new(),
+            stdlib: None,
+        }
+    }
+
+    pub fn with_link_options(mut self, link: LinkOptions) -> Self {
+        self.link = link;
+        self
+    }
+
+    pub fn with_cfg(mut self, cfg: CfgSet) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    /// A directory of `std::` modules overriding or extending the bundled ones. Without one,
+    /// `HADES_STDLIB` is used if it is set.
+    pub fn with_stdlib(mut self, dir: Option<PathBuf>) -> Self {
+        self.stdlib = dir;
+        self
+    }
+
+    fn load(&self, entry_path: impl AsRef<Path>) -> Result<Project, ModuleError> {
+        let stdlib = self
+            .stdlib
+            .clone()
+            .or_else(|| std::env::var_os(lexer::Library::DIR_ENV).map(PathBuf::from));
+        Registry::load(entry_path, self.cfg.clone(), stdlib.as_deref())
     }
 
     pub fn prepare(&self) {
@@ -130,7 +170,7 @@ impl<'a> Compiler {
             .expect("Tokenizing failed");
 
         let mut parser = parser::Parser::new(lexer.into_tokens(), filename.to_string());
-        let program = match parser.parse() {
+        let mut program = match parser.parse() {
             Ok(prog) => prog,
             Err(err) => {
                 let err = err.into_errors();
@@ -140,9 +180,10 @@ impl<'a> Compiler {
                 return false;
             }
         };
+        self.cfg.apply(&mut program);
 
         let module = Module {
-            path: ModulePath::Local(filename.to_string()),
+            path: ModulePath::Local(vec![filename.to_string()]),
             ast: program,
             imports: vec![],
         };
@@ -175,8 +216,8 @@ impl<'a> Compiler {
         let output_path = output_path.as_ref();
         let mut cache = FileSourceCache::new();
 
-        let modules = match Registry::load(entry_path) {
-            Ok(m) => m,
+        let project = match self.load(entry_path) {
+            Ok(project) => project,
             Err(err) => {
                 eprintln!("Failed to load modules: {err}");
                 return false;
@@ -186,7 +227,7 @@ impl<'a> Compiler {
         let llvm_ctx = Context::create();
         let analyzer = Analyzer::<Unprepared>::new();
 
-        let prepared = match analyzer.prepare(modules) {
+        let prepared = match analyzer.prepare(project.modules) {
             Ok(p) => p,
             Err(err) => {
                 err.into_error().eprint(&mut cache);
@@ -224,7 +265,13 @@ impl<'a> Compiler {
                 .filter_map(|p| sig_map.get(p).copied())
                 .collect();
 
-            if let Err(err) = codegen::compile(typed_module, &import_sigs, &llvm_ctx, &obj_path) {
+            if let Err(err) = codegen::compile(
+                typed_module,
+                &import_sigs,
+                &llvm_ctx,
+                &obj_path,
+                optimization_level(&project.profile),
+            ) {
                 eprintln!("Compilation failed for {}: {err}", typed_module.path);
                 return false;
             }
@@ -236,6 +283,8 @@ impl<'a> Compiler {
         for obj in &obj_paths {
             cmd.arg(obj);
         }
+        self.link
+            .apply(&mut cmd, typed_modules.iter().flat_map(|m| m.ctx.links()));
         cmd.arg("-o").arg(output_path).arg("-lc").arg("-lm");
 
         let status = match cmd.status() {
@@ -263,7 +312,7 @@ impl<'a> Compiler {
         entry_path: impl AsRef<Path>,
         context: &inkwell::context::Context,
     ) -> Result<(), String> {
-        let modules = Registry::load(entry_path).map_err(|e| e.to_string())?;
+        let modules = self.load(entry_path).map_err(|e| e.to_string())?.modules;
 
         let analyzer = Analyzer::<Unprepared>::new();
         let prepared = analyzer
@@ -294,8 +343,38 @@ impl<'a> Compiler {
         Ok(())
     }
 
+    /// Writes a C header for the `export` items reachable from `entry_path`.
+    pub fn emit_header(
+        &self,
+        entry_path: impl AsRef<Path>,
+        output_path: impl AsRef<Path>,
+    ) -> Result<(), String> {
+        let output_path = output_path.as_ref();
+        let modules = self.load(entry_path).map_err(|e| e.to_string())?.modules;
+
+        let analyzer = Analyzer::<Unprepared>::new();
+        let prepared = analyzer
+            .prepare(modules)
+            .map_err(|e| e.into_error().to_string())?;
+        prepared.analyze().map_err(|e| e.to_string())?;
+
+        let signatures: Vec<_> = prepared.modules().iter().map(|m| &m.signatures).collect();
+        let stem = output_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let guard = format!(
+            "{}_H",
+            stem.to_uppercase()
+                .replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        );
+
+        let header = header::generate(&guard, &signatures)?;
+        fs::write(output_path, header).map_err(|e| e.to_string())
+    }
+
     pub fn emit_mir(&self, entry_path: impl AsRef<Path>) -> Result<(), String> {
-        let modules = Registry::load(entry_path).map_err(|e| e.to_string())?;
+        let modules = self.load(entry_path).map_err(|e| e.to_string())?.modules;
 
         let analyzer = Analyzer::<Unprepared>::new();
         let prepared = analyzer
@@ -330,6 +409,16 @@ impl<'a> Compiler {
     }
 }
 
+/// `opt-level` from the manifest's `[profile]`; builds without one use LLVM's default level.
+fn optimization_level(profile: &Profile) -> OptimizationLevel {
+    match profile.opt_level {
+        Some(0) => OptimizationLevel::None,
+        Some(1) => OptimizationLevel::Less,
+        Some(3) => OptimizationLevel::Aggressive,
+        _ => OptimizationLevel::Default,
+    }
+}
+
 fn report_diagnostics(
     diags: Vec<LintDiagnostic>,
     cache: &mut impl ariadne::Cache<PathBuf>,
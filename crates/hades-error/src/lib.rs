@@ -14,6 +14,8 @@ pub struct Error {
     pub severity: ErrorSeverity,
     pub help: Option<String>,
     pub note: Option<String>,
+    /// Secondary locations, each shown with its own message.
+    pub labels: Vec<(Span, String)>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -30,6 +32,7 @@ impl Error {
             severity: ErrorSeverity::Error,
             help: None,
             note: None,
+            labels: Vec::new(),
         }
     }
 
@@ -48,6 +51,11 @@ impl Error {
         self
     }
 
+    pub fn with_label(mut self, span: Span, message: String) -> Self {
+        self.labels.push((span, message));
+        self
+    }
+
     pub fn with_span(mut self, span: Span) -> Self {
         self.span = span;
         self
@@ -75,6 +83,22 @@ impl Error {
                 },
             ));
 
+        if let Some(definition) = self.span.expansion() {
+            report = report.with_label(
+                Label::new(definition.clone())
+                    .with_message("expanded from this macro")
+                    .with_color(ariadne::Color::Blue),
+            );
+        }
+
+        for (span, message) in &self.labels {
+            report = report.with_label(
+                Label::new(span.clone())
+                    .with_message(message)
+                    .with_color(ariadne::Color::Blue),
+            );
+        }
+
         if let Some(help) = &self.help {
             report = report.with_help(help);
         }
@@ -1,52 +1,50 @@
 use crate::{Error, Span};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SemanticError {
     message: String,
     span: Span,
+    /// Other locations worth showing, such as the declaration of the item the error is about.
+    related: Option<Vec<(Span, String)>>,
 }
 
 impl SemanticError {
-    pub fn type_mismatch(expected: String, found: String, span: Span) -> Self {
+    fn new(message: String, span: Span) -> Self {
         Self {
-            message: format!("Type mismatch: expected {}, found {}", expected, found),
+            message,
             span,
+            related: None,
         }
     }
 
-    pub fn undefined_variable(name: String, span: Span) -> Self {
-        Self {
-            message: format!("Undefined variable: {}", name),
+    pub fn type_mismatch(expected: String, found: String, span: Span) -> Self {
+        Self::new(
+            format!("Type mismatch: expected {}, found {}", expected, found),
             span,
-        }
+        )
+    }
+
+    pub fn undefined_variable(name: String, span: Span) -> Self {
+        Self::new(format!("Undefined variable: {}", name), span)
     }
 
     pub fn undefined_function(name: String, span: Span) -> Self {
-        Self {
-            message: format!("Undefined function: {}", name),
-            span,
-        }
+        Self::new(format!("Undefined function: {}", name), span)
     }
 
     pub fn undefined_struct(name: String, span: Span) -> Self {
-        Self {
-            message: format!("Undefined struct: {}", name),
-            span,
-        }
+        Self::new(format!("Undefined struct: {}", name), span)
     }
 
     pub fn not_a_struct(name: String, span: Span) -> Self {
-        Self {
-            message: format!("{} is not a struct", name),
-            span,
-        }
+        Self::new(format!("{} is not a struct", name), span)
     }
 
     pub fn unknown_field(struct_name: String, field_name: String, span: Span) -> Self {
-        Self {
-            message: format!("Unknown field '{}' in struct '{}'", field_name, struct_name),
+        Self::new(
+            format!("Unknown field '{}' in struct '{}'", field_name, struct_name),
             span,
-        }
+        )
     }
 
     pub fn argument_count_mismatch(
@@ -55,164 +53,423 @@ impl SemanticError {
         function: String,
         span: Span,
     ) -> Self {
-        Self {
-            message: format!(
+        Self::new(
+            format!(
                 "Function {} expects {} arguments, found {}",
                 function, expected, found
             ),
             span,
-        }
+        )
+    }
+
+    pub fn unknown_argument(param: String, function: String, span: Span) -> Self {
+        Self::new(
+            format!("Function {} has no parameter named {}", function, param),
+            span,
+        )
+    }
+
+    pub fn duplicate_argument(param: String, span: Span) -> Self {
+        Self::new(
+            format!("Parameter {} is given more than one argument", param),
+            span,
+        )
+    }
+
+    pub fn missing_argument(param: String, function: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Function {} is missing an argument for parameter {}",
+                function, param
+            ),
+            span,
+        )
+    }
+
+    pub fn no_matching_overload(function: String, args: String, span: Span) -> Self {
+        Self::new(
+            format!("No overload of {} takes ({})", function, args),
+            span,
+        )
+    }
+
+    pub fn ambiguous_overload(function: String, args: String, span: Span) -> Self {
+        Self::new(
+            format!("Call to {} with ({}) is ambiguous", function, args),
+            span,
+        )
+    }
+
+    pub fn non_literal_default(param: String, span: Span) -> Self {
+        Self::new(
+            format!("Default value of parameter {} must be a literal", param),
+            span,
+        )
     }
 
     pub fn return_type_mismatch(expected: String, found: String, span: Span) -> Self {
-        Self {
-            message: format!(
+        Self::new(
+            format!(
                 "Return type mismatch: expected {}, found {}",
                 expected, found
             ),
             span,
-        }
+        )
     }
 
     pub fn invalid_binary_operation(left: String, op: String, right: String, span: Span) -> Self {
-        Self {
-            message: format!("Invalid binary operation: {} {} {}", left, op, right),
+        Self::new(
+            format!("Invalid binary operation: {} {} {}", left, op, right),
             span,
-        }
+        )
     }
 
     pub fn invalid_unary_operation(op: String, operand: String, span: Span) -> Self {
-        Self {
-            message: format!("Invalid unary operation: {} {}", op, operand),
-            span,
-        }
+        Self::new(format!("Invalid unary operation: {} {}", op, operand), span)
     }
 
     pub fn redefined_variable(name: String, span: Span) -> Self {
-        Self {
-            message: format!("Variable {} is already defined", name),
-            span,
-        }
+        Self::new(format!("Variable {} is already defined", name), span)
     }
 
     pub fn redefined_function(name: String, span: Span) -> Self {
-        Self {
-            message: format!("Function {} is already defined", name),
-            span,
-        }
+        Self::new(format!("Function {} is already defined", name), span)
     }
 
     pub fn redefined_struct(name: String, span: Span) -> Self {
-        Self {
-            message: format!("Struct {} is already defined", name),
-            span,
-        }
+        Self::new(format!("Struct {} is already defined", name), span)
     }
 
     pub fn invalid_type(name: String, span: Span) -> Self {
-        Self {
-            message: format!("Invalid type: {}", name),
-            span,
-        }
+        Self::new(format!("Invalid type: {}", name), span)
     }
 
     pub fn invalid_module_name(name: String, span: Span) -> Self {
-        Self {
-            message: format!("Invalid module name: {}", name),
-            span,
-        }
+        Self::new(format!("Invalid module name: {}", name), span)
     }
 
     pub fn invalid_import(name: String, span: Span) -> Self {
-        Self {
-            message: format!("Invalid import: {}", name),
-            span,
-        }
+        Self::new(format!("Invalid import: {}", name), span)
+    }
+
+    pub fn module_not_imported(module: String, span: Span) -> Self {
+        Self::new(format!("Module {} is not imported here", module), span)
     }
 
     pub fn self_outside_method(span: Span) -> Self {
-        Self {
-            message: String::from("'self' parameter is only valid inside a method"),
+        Self::new(
+            String::from("'self' parameter is only valid inside a method"),
             span,
-        }
+        )
     }
 
     pub fn null_without_type(span: Span) -> Self {
-        Self {
-            message: String::from(
+        Self::new(
+            String::from(
                 "null requires an explicit pointer type annotation (e.g. let x: &T = null)",
             ),
             span,
-        }
+        )
     }
 
     pub fn null_non_pointer(declared: String, span: Span) -> Self {
-        Self {
-            message: format!(
+        Self::new(
+            format!(
                 "null can only be assigned to a pointer type, but declared type is {}",
                 declared
             ),
             span,
-        }
+        )
     }
 
     pub fn invalid_dereference(typ: String, span: Span) -> Self {
-        Self {
-            message: format!("Cannot dereference type {}", typ),
-            span,
-        }
+        Self::new(format!("Cannot dereference type {}", typ), span)
+    }
+
+    /// Adds another location worth showing, such as a candidate the error is about.
+    pub fn with_related(mut self, span: Span, message: String) -> Self {
+        self.related
+            .get_or_insert_with(Vec::new)
+            .push((span, message));
+        self
     }
 
     pub fn into_error(self) -> Error {
-        Error::new_with_span(self.message, self.span)
+        self.related.into_iter().flatten().fold(
+            Error::new_with_span(self.message, self.span),
+            |error, (span, message)| error.with_label(span, message),
+        )
     }
 
     pub fn intrinsic_outside_stdlib(name: String, span: Span) -> Self {
-        Self {
-            message: format!(
+        Self::new(
+            format!(
                 "'intrinsic fn {}' is only allowed in standard library modules",
                 name
             ),
             span,
-        }
+        )
     }
 
     pub fn extern_outside_stdlib(name: String, span: Span) -> Self {
-        Self {
-            message: format!(
-                "'extern fn {}' is only allowed in standard library modules",
+        Self::new(
+            format!(
+                "'extern fn {}' outside the standard library needs a @link(\"library\") marker",
                 name
             ),
             span,
-        }
+        )
     }
 
-    pub fn invalid_type_cast(source: String, target: String, span: Span) -> Self {
-        Self {
-            message: format!("Cannot cast type {} to {}", source, target),
+    pub fn unexportable_type(name: String, typ: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Exported function '{}' cannot pass {} by value; use a pointer instead",
+                name, typ
+            ),
             span,
-        }
+        )
+    }
+
+    pub fn variadic_export(name: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Exported function '{}' cannot take a variadic parameter; take a pointer and a length instead",
+                name
+            ),
+            span,
+        )
+    }
+
+    pub fn invalid_asm_operand(typ: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Inline assembly operands must be scalars or pointers, found {}",
+                typ
+            ),
+            span,
+        )
+    }
+
+    pub fn invalid_asm_constraint(constraint: String, expected: &str, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Invalid inline assembly constraint \"{}\": {}",
+                constraint, expected
+            ),
+            span,
+        )
+    }
+
+    pub fn deprecated_use(item: String, note: Option<&str>, span: Span) -> Self {
+        let message = match note {
+            Some(note) => format!("Use of deprecated {}: {}", item, note),
+            None => format!("Use of deprecated {}", item),
+        };
+        Self::new(message, span)
+    }
+
+    pub fn unevaluated_cfg(cfg: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "@cfg({}) must be evaluated by the module loader before type checking",
+                cfg
+            ),
+            span,
+        )
+    }
+
+    pub fn invalid_shuffle_index(found: String, limit: usize, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Shuffle index must be an integer literal below {}, found {}",
+                limit, found
+            ),
+            span,
+        )
+    }
+
+    pub fn lane_out_of_range(index: i64, lanes: usize, span: Span) -> Self {
+        Self::new(
+            format!("Lane index {index} is out of range for a vector of {lanes} lanes"),
+            span,
+        )
+    }
+
+    pub fn invalid_type_cast(source: String, target: String, span: Span) -> Self {
+        Self::new(format!("Cannot cast type {} to {}", source, target), span)
     }
 
     pub fn return_not_allowed_in_defer(span: Span) -> Self {
-        Self {
-            message: String::from("Return statements are not allowed inside defer blocks"),
+        Self::new(
+            String::from("Return statements are not allowed inside defer blocks"),
             span,
-        }
+        )
     }
 
     pub fn defer_outside_function(span: Span) -> Self {
-        Self {
-            message: String::from("Defer statements are only allowed inside functions"),
+        Self::new(
+            String::from("Defer statements are only allowed inside functions"),
             span,
-        }
+        )
+    }
+
+    pub fn break_outside_loop(span: Span) -> Self {
+        Self::new(String::from("'break' is only allowed inside a loop"), span)
+    }
+
+    pub fn break_value_outside_loop(span: Span) -> Self {
+        Self::new(
+            String::from("'break' with a value is only allowed inside 'loop'"),
+            span,
+        )
+    }
+
+    pub fn not_iterable(typ: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Type {} is not iterable: expected an array or a struct with an 'iter' method",
+                typ
+            ),
+            span,
+        )
+    }
+
+    pub fn invalid_iterator(typ: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Iterator type {} must have a 'next' method returning a pointer",
+                typ
+            ),
+            span,
+        )
+    }
+
+    /// The `then` branch of an `if` yields a value the missing `else` can't match.
+    pub fn if_without_else(typ: String, span: Span) -> Self {
+        Self::new(
+            format!("`if` without `else` yields {typ} on one branch only"),
+            span,
+        )
+    }
+
+    pub fn reserved_builtin(name: String, span: Span) -> Self {
+        Self::new(
+            format!("{name} is a builtin and can't be declared as a function"),
+            span,
+        )
+    }
+
+    pub fn unsized_type(typ: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Type {} has no known size and can only be used behind a pointer",
+                typ
+            ),
+            span,
+        )
+    }
+
+    pub fn invalid_variadic_type(typ: String, span: Span) -> Self {
+        Self::new(
+            format!("A variadic parameter can't take arguments of type {typ}"),
+            span,
+        )
+    }
+
+    pub fn unsettled_signatures(rounds: usize) -> Self {
+        Self::new(
+            format!("Module signatures still changed after {rounds} rounds of declaration"),
+            Span::default(),
+        )
+    }
+
+    pub fn default_before_variadic(param: String, variadic: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Parameter '{param}' can't have a default: the variadic parameter '{variadic}' \
+                 after it makes every argument positional"
+            ),
+            span,
+        )
+    }
+
+    pub fn invalid_sequence_use(name: String, span: Span) -> Self {
+        Self::new(
+            format!(
+                "Variadic parameter '{name}' can only be indexed, iterated, passed to len or \
+                 passed on to another variadic parameter"
+            ),
+            span,
+        )
+    }
+
+    /// `item` is private to its module, e.g. "function math::helper".
+    pub fn private_item(item: String, declared: Span, span: Span) -> Self {
+        Self::new(format!("{} is private", item), span)
+            .with_related(declared, String::from("declared private here"))
+    }
+
+    pub fn conflicting_import(
+        name: String,
+        items: (String, String),
+        first: Span,
+        span: Span,
+    ) -> Self {
+        Self::new(
+            format!("'{}' is imported as both {} and {}", name, items.0, items.1),
+            span,
+        )
+        .with_related(first, String::from("first imported here"))
+    }
+
+    pub fn conflicting_alias(
+        alias: String,
+        modules: (String, String),
+        first: Span,
+        span: Span,
+    ) -> Self {
+        Self::new(
+            format!(
+                "'{}' stands for both module {} and module {}",
+                alias, modules.0, modules.1
+            ),
+            span,
+        )
+        .with_related(first, String::from("first introduced here"))
+    }
+
+    /// The public `item`, e.g. "function shapes::make", names the private type `typ`, which
+    /// its importers could not use.
+    pub fn private_in_public(item: String, typ: String, declared: Span, span: Span) -> Self {
+        Self::new(
+            format!("{} is public but uses the private type {}", item, typ),
+            span,
+        )
+        .with_related(declared, String::from("declared private here"))
+    }
+
+    pub fn ambiguous_reexport(
+        name: String,
+        items: (String, String),
+        first: Span,
+        span: Span,
+    ) -> Self {
+        Self::new(
+            format!(
+                "'{}' is re-exported as both {} and {}",
+                name, items.0, items.1
+            ),
+            span,
+        )
+        .with_related(first, String::from("first re-exported here"))
     }
 
     pub fn missing_return(span: Span) -> Self {
-        Self {
-            message: String::from("Missing return statement in function with non-void return type"),
+        Self::new(
+            String::from("Missing return statement in function with non-void return type"),
             span,
-        }
+        )
     }
 }
 
@@ -1,17 +1,46 @@
 use ariadne::Span as AriadneSpan;
-use std::{ops::Range, path::PathBuf};
+use std::hash::{Hash, Hasher};
+use std::{ops::Range, path::PathBuf, sync::Arc};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Two spans are equal when they cover the same source range; where a macro expansion came
+/// from only matters for reporting.
+#[derive(Debug, Clone)]
 pub struct Span {
     range: std::ops::Range<usize>,
     file: PathBuf,
+    /// For code produced by a macro, where it was written in the macro definition. The range
+    /// itself then covers the macro call.
+    expansion: Option<Arc<Span>>,
 }
 
 impl Span {
     pub fn new(filename: PathBuf, start: usize, end: usize) -> Self {
         let path = filename;
         let range = start..end;
-        Self { range, file: path }
+        Self {
+            range,
+            file: path,
+            expansion: None,
+        }
+    }
+
+    /// This call-site span, marked as expanded from `definition`.
+    pub fn with_expansion(&self, definition: Span) -> Span {
+        Span {
+            expansion: Some(Arc::new(definition.without_expansion())),
+            ..self.clone()
+        }
+    }
+
+    pub fn without_expansion(&self) -> Span {
+        Span {
+            expansion: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn expansion(&self) -> Option<&Span> {
+        self.expansion.as_deref()
     }
 
     pub fn start(&self) -> usize {
@@ -27,19 +56,25 @@ impl Span {
     }
 
     pub fn to(&self, other: Span) -> Span {
-        Span::new(
-            self.file.clone(),
-            self.start().min(other.start()),
-            self.end().max(other.end()),
-        )
+        Span {
+            range: self.start().min(other.start())..self.end().max(other.end()),
+            file: self.file.clone(),
+            expansion: self.expansion.clone().or(other.expansion),
+        }
     }
 
     pub fn shrink_to_lo(&self) -> Span {
-        Span::new(self.file.clone(), self.start(), self.start())
+        Span {
+            range: self.start()..self.start(),
+            ..self.clone()
+        }
     }
 
     pub fn shrink_to_hi(&self) -> Span {
-        Span::new(self.file.clone(), self.end(), self.end())
+        Span {
+            range: self.end()..self.end(),
+            ..self.clone()
+        }
     }
 
     pub fn contains(&self, pos: usize) -> bool {
@@ -59,6 +94,21 @@ impl Span {
     }
 }
 
+impl PartialEq for Span {
+    fn eq(&self, other: &Self) -> bool {
+        self.range == other.range && self.file == other.file
+    }
+}
+
+impl Eq for Span {}
+
+impl Hash for Span {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.range.hash(state);
+        self.file.hash(state);
+    }
+}
+
 impl Default for Span {
     fn default() -> Self {
         Self::new(PathBuf::from("dummyfile"), 0, 0)
@@ -31,8 +31,14 @@ static KEYWORDS: phf::Map<&'static str, TokenKind> = phf_map! {
     "null" => TokenKind::Null,
     "as" => TokenKind::As,
     "extern" => TokenKind::Extern,
+    "export" => TokenKind::Export,
+    "asm" => TokenKind::Asm,
     "intrinsic" => TokenKind::Intrinsic,
     "defer" => TokenKind::Defer,
+    "loop" => TokenKind::Loop,
+    "in" => TokenKind::In,
+    "macro" => TokenKind::Macro,
+    "pub" => TokenKind::Pub,
 };
 
 pub struct Lexer {
@@ -454,6 +460,19 @@ impl Lexer {
         let start_pos = self.pos;
         if let Some(c) = self.peek() {
             match c {
+                c if c.eq(&b'@') => {
+                    self.next();
+                    self.push_token(tok!(&self.source_id, TokenKind::At, start_pos, self.pos));
+                }
+                c if c.eq(&b'$') => {
+                    self.next();
+                    self.push_token(tok!(
+                        &self.source_id,
+                        TokenKind::Dollar,
+                        start_pos,
+                        self.pos
+                    ));
+                }
                 c if c.eq(&b'(') => {
                     self.next();
                     self.push_token(tok!(
@@ -2,4 +2,4 @@ pub(super) const OPS: &[u8] = b"+-*/%&|^~=<>!";
 pub(super) const ASCII_DIGITS: &[u8] = b"0123456789";
 pub(super) const WHITESPACE: &[u8] = b" \t\n\r";
 pub(super) const ALPHABETIC: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
-pub(super) const PUNCTUATION: &[u8] = b"(){}[],;.:";
+pub(super) const PUNCTUATION: &[u8] = b"(){}[],;.:@$";
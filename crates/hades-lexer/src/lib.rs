@@ -2,4 +2,4 @@ pub mod lexer;
 pub mod stdlib;
 
 pub use lexer::*;
-pub use stdlib::Library;
+pub use stdlib::{Library, StdModule};
@@ -1,20 +1,80 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
 
 pub struct Library {
-    modules: HashMap<String, &'static str>,
+    modules: HashMap<String, StdModule>,
+}
+
+/// The source of a `std::` module, bundled with the compiler or read from a std directory.
+pub struct StdModule {
+    pub source: Cow<'static, str>,
+    /// Where the module was read from; `None` for a bundled module.
+    pub path: Option<PathBuf>,
+}
+
+impl StdModule {
+    fn bundled(source: &'static str) -> Self {
+        Self {
+            source: Cow::Borrowed(source),
+            path: None,
+        }
+    }
 }
 
 impl Library {
+    /// The environment variable naming a std directory when none is given explicitly.
+    pub const DIR_ENV: &'static str = "HADES_STDLIB";
+
     pub fn new() -> Self {
         let mut modules = HashMap::new();
-        modules.insert("math".to_string(), include_str!("../../../std/math.hd"));
-        modules.insert("io".to_string(), include_str!("../../../std/io.hd"));
-        modules.insert("time".to_string(), include_str!("../../../std/time.hd"));
+        modules.insert(
+            "math".to_string(),
+            StdModule::bundled(include_str!("../../../std/math.hd")),
+        );
+        modules.insert(
+            "io".to_string(),
+            StdModule::bundled(include_str!("../../../std/io.hd")),
+        );
+        modules.insert(
+            "time".to_string(),
+            StdModule::bundled(include_str!("../../../std/time.hd")),
+        );
+        modules.insert(
+            "mem".to_string(),
+            StdModule::bundled(include_str!("../../../std/mem.hd")),
+        );
         Self { modules }
     }
 
-    pub fn get_module(&self, name: &str) -> Option<&'static str> {
-        self.modules.get(name).copied()
+    /// The bundled modules plus every `<name>.hd` in `dir`, which replaces the bundled
+    /// `std::<name>` if there is one.
+    pub fn with_dir(dir: &Path) -> io::Result<Self> {
+        let mut library = Self::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_file() || path.extension().is_none_or(|ext| ext != "hd") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            let source = std::fs::read_to_string(&path)?;
+            library.modules.insert(
+                name.to_string(),
+                StdModule {
+                    source: Cow::Owned(source),
+                    path: Some(path),
+                },
+            );
+        }
+        Ok(library)
+    }
+
+    pub fn get_module(&self, name: &str) -> Option<&StdModule> {
+        self.modules.get(name)
     }
 
     pub fn has_module(&self, name: &str) -> bool {
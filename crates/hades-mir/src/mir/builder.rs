@@ -49,6 +49,11 @@ impl<'ctx> MirBuilder<'ctx> {
         self.guard_mut().add_local(local)
     }
 
+    pub fn build_temp(&mut self, typ: Types, span: Span) -> Place {
+        let tmp_name = Ident::new(format!("_tmp{}", self.local_count()), span);
+        Place::local(self.build_local(tmp_name, typ))
+    }
+
     pub fn start_block(&mut self) -> BasicBlock {
         self.guard_mut().start_new_block()
     }
@@ -93,8 +98,14 @@ impl<'ctx> MirBuilder<'ctx> {
         self.guard().locals.len()
     }
 
-    pub fn push_loop(&mut self, continue_block: BasicBlock, break_block: BasicBlock) {
-        self.guard_mut().push_loop(continue_block, break_block);
+    pub fn push_loop(
+        &mut self,
+        continue_block: BasicBlock,
+        break_block: BasicBlock,
+        result: Option<Place>,
+    ) {
+        self.guard_mut()
+            .push_loop(continue_block, break_block, result);
     }
 
     pub fn pop_loop(&mut self) {
@@ -105,6 +116,14 @@ impl<'ctx> MirBuilder<'ctx> {
         self.guard().current_loop()
     }
 
+    pub fn enter_defer_scope(&mut self) {
+        self.guard_mut().enter_defer_scope();
+    }
+
+    pub fn exit_defer_scope(&mut self) -> Vec<Statement> {
+        self.guard_mut().exit_defer_scope()
+    }
+
     pub fn push_defer(&mut self, stmts: Vec<Statement>, span: Span) {
         self.guard_mut().push_defer(stmts, span);
     }
@@ -117,6 +136,10 @@ impl<'ctx> MirBuilder<'ctx> {
         self.guard().deferred_stmts()
     }
 
+    pub fn deferred_stmts_since(&self, depth: usize) -> Vec<Statement> {
+        self.guard().deferred_stmts_since(depth)
+    }
+
     pub fn drain_scratch_block(&mut self, id: BasicBlock) -> Vec<Statement> {
         self.guard_mut().drain_scratch_block(id)
     }
@@ -27,6 +27,9 @@ impl ToMir for TypedExpr {
             TypedExpr::Null(typ) => {
                 block.and(Rvalue::Use(Operand::Const(MirConst::Null(typ.clone()))))
             }
+            TypedExpr::Type(typ) => {
+                block.and(Rvalue::Use(Operand::Const(MirConst::Type(typ.clone()))))
+            }
 
             TypedExpr::Ident { ident, .. } => {
                 let idx = builder.lookup_local(ident);
@@ -82,6 +85,40 @@ impl ToMir for TypedExpr {
                 }
             }
 
+            TypedExpr::If(if_expr) => {
+                let result = unpack!(block = if_expr.to_mir(builder, block));
+                block.and(place_or_unit(result))
+            }
+
+            TypedExpr::Block(inner) => {
+                let rvalue = unpack!(block = inner.to_mir(builder, block));
+                block.and(rvalue.unwrap_or(Rvalue::Use(Operand::Const(MirConst::Int(0)))))
+            }
+
+            TypedExpr::Loop(loop_expr) => {
+                let result = unpack!(block = loop_expr.to_mir(builder, block));
+                block.and(place_or_unit(result))
+            }
+
+            TypedExpr::Asm(asm) => {
+                let mut inputs = Vec::new();
+                for (_, input) in &asm.inputs {
+                    let rvalue = unpack!(block = input.to_mir(builder, block));
+                    let (block2, operand) =
+                        builder.as_operand(block, rvalue, &input.get_type(), span.clone());
+                    block = block2;
+                    inputs.push(operand);
+                }
+                let mut outputs = Vec::new();
+                for output in &asm.outputs {
+                    let (block2, place) = lower_assign_target(builder, block, &output.target);
+                    block = block2;
+                    outputs.push(place);
+                }
+                builder.push_stmt(block, Statement::inline_asm(inputs, outputs, span));
+                block.and(Rvalue::Use(Operand::Const(MirConst::Int(0))))
+            }
+
             TypedExpr::As(a) => {
                 let rvalue = unpack!(block = a.expr.to_mir(builder, block));
                 let (block2, operand) = builder.as_operand(block, rvalue, &a.expr.get_type(), span);
@@ -229,8 +266,16 @@ fn lower_field_access(
         Operand::Const(_) => unreachable!("struct base cannot be a constant"),
     };
     let struct_name = fa.struct_type.unwrap_struct_name();
-    let field_index = builder.symbols().structs().field_index(struct_name, &fa.field);
-    let place = Place::with_field(base_idx, fa.field.clone(), field_index, fa.field_type.clone());
+    let field_index = builder
+        .symbols()
+        .structs()
+        .field_index(struct_name, &fa.field);
+    let place = Place::with_field(
+        base_idx,
+        fa.field.clone(),
+        field_index,
+        fa.field_type.clone(),
+    );
     (block2, place)
 }
 
@@ -317,16 +362,23 @@ fn lower_array_literal(
     let elem_ty = arr.elem_typ.clone();
     if let Some(fill) = &arr.fill {
         let fill_rvalue = unpack!(block = fill.to_mir(builder, block));
-        let (b, fill_op) = builder.as_operand(block, fill_rvalue, &elem_ty, span.clone());
+        let (b, fill_op) = builder.as_operand(block, fill_rvalue, &fill.get_type(), span.clone());
         b.and(Rvalue::Repeat(fill_op, arr.size))
     } else {
         let mut operands = Vec::with_capacity(arr.elements.len());
         for elem in &arr.elements {
             let rvalue = unpack!(block = elem.to_mir(builder, block));
-            let (b, op) = builder.as_operand(block, rvalue, &elem_ty, span.clone());
+            let (b, op) = builder.as_operand(block, rvalue, &elem.get_type(), span.clone());
             block = b;
             operands.push(op);
         }
         block.and(Rvalue::Aggregate(AggregateKind::Array(elem_ty), operands))
     }
 }
+
+fn place_or_unit(place: Option<Place>) -> Rvalue {
+    match place {
+        Some(place) => Rvalue::Use(Operand::Copy(place)),
+        None => Rvalue::Use(Operand::Const(MirConst::Int(0))),
+    }
+}
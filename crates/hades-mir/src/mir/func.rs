@@ -4,7 +4,9 @@ use hades_tokens::{Ident, Name, ParamKind};
 
 use crate::mir::builder::MirBuilder;
 use crate::mir::guard::Guard;
-use crate::mir::terminator::{Terminator, TerminatorKind};
+use crate::mir::place::Place;
+use crate::mir::stmt::Statement;
+use crate::mir::terminator::{RETURN_LOCAL, Terminator, TerminatorKind};
 use crate::{BasicBlock, BlockAnd, BlockAndExt, ToMir, unpack};
 
 pub struct MirFunction {
@@ -63,12 +65,16 @@ impl ToMir for TypedFuncDef {
         builder.switch_to(bb0);
 
         let mut block = bb0;
-        unpack!(block = body.to_mir(builder, block));
+        let tail = unpack!(block = body.to_mir(builder, block));
 
         if !builder.is_block_terminated(block) {
-            let deferred = builder.deferred_stmts();
-            for stmt in deferred {
-                builder.push_stmt(block, stmt);
+            if let Some(rvalue) = tail
+                && self.signature.return_type != Types::Void
+            {
+                builder.push_stmt(
+                    block,
+                    Statement::assign(Place::local(RETURN_LOCAL), rvalue, span.clone()),
+                );
             }
             builder.switch_to(block);
             builder.terminate(Terminator::new(TerminatorKind::Return, span.clone()));
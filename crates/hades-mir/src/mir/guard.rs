@@ -6,6 +6,7 @@ use hades_tokens::Ident;
 use crate::BasicBlock;
 use crate::mir::block::BasicBlockData;
 use crate::mir::local::Local;
+use crate::mir::place::Place;
 use crate::mir::stmt::Statement;
 use crate::mir::terminator::Terminator;
 
@@ -13,6 +14,10 @@ use crate::mir::terminator::Terminator;
 pub struct LoopContext {
     pub continue_block: BasicBlock,
     pub break_block: BasicBlock,
+    pub result: Option<Place>,
+    /// Number of open defer scopes when the loop was entered; `break` and `continue`
+    /// run every scope above it.
+    pub defer_depth: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -27,7 +32,7 @@ pub struct Guard {
     pub basic_blocks: Vec<BasicBlockData>,
     pub current: BasicBlock,
     pub loop_stack: Vec<LoopContext>,
-    pub defer_stack: Vec<DeferEntry>,
+    pub defer_scopes: Vec<Vec<DeferEntry>>,
 }
 
 impl Default for Guard {
@@ -44,7 +49,7 @@ impl Guard {
             basic_blocks: vec![],
             current: BasicBlock(0),
             loop_stack: vec![],
-            defer_stack: vec![],
+            defer_scopes: vec![],
         }
     }
 
@@ -112,10 +117,17 @@ impl Guard {
         *self.local_map.get(name).expect("undeclared local")
     }
 
-    pub fn push_loop(&mut self, continue_block: BasicBlock, break_block: BasicBlock) {
+    pub fn push_loop(
+        &mut self,
+        continue_block: BasicBlock,
+        break_block: BasicBlock,
+        result: Option<Place>,
+    ) {
         self.loop_stack.push(LoopContext {
             continue_block,
             break_block,
+            result,
+            defer_depth: self.defer_scopes.len(),
         });
     }
 
@@ -127,18 +139,43 @@ impl Guard {
         self.loop_stack.last()
     }
 
+    pub fn enter_defer_scope(&mut self) {
+        self.defer_scopes.push(vec![]);
+    }
+
+    /// Closes the innermost defer scope, returning its statements in the order they must run.
+    pub fn exit_defer_scope(&mut self) -> Vec<Statement> {
+        let scope = self
+            .defer_scopes
+            .pop()
+            .expect("exit_defer_scope: no open scope");
+        scope.into_iter().rev().flat_map(|d| d.stmts).collect()
+    }
+
     pub fn push_defer(&mut self, stmts: Vec<Statement>, span: Span) {
-        self.defer_stack.push(DeferEntry { stmts, span });
+        self.defer_scopes
+            .last_mut()
+            .expect("push_defer: no open scope")
+            .push(DeferEntry { stmts, span });
     }
 
     pub fn pop_defer(&mut self) {
-        self.defer_stack.pop().expect("pop_defer: stack is empty");
+        self.defer_scopes
+            .last_mut()
+            .and_then(|scope| scope.pop())
+            .expect("pop_defer: stack is empty");
     }
 
     pub fn deferred_stmts(&self) -> Vec<Statement> {
-        self.defer_stack
+        self.deferred_stmts_since(0)
+    }
+
+    /// Statements of every defer scope opened at or above `depth`, innermost first.
+    pub fn deferred_stmts_since(&self, depth: usize) -> Vec<Statement> {
+        self.defer_scopes[depth..]
             .iter()
             .rev()
+            .flat_map(|scope| scope.iter().rev())
             .flat_map(|d| d.stmts.iter().cloned())
             .collect()
     }
@@ -1,25 +1,53 @@
 use hades_ast::{
-    TypedBlock, TypedBreak, TypedContinue, TypedDefer, TypedFor, TypedIf, TypedLet, TypedReturn,
-    TypedWhile,
+    TypedBlock, TypedBreak, TypedContinue, TypedDefer, TypedFor, TypedIf, TypedLet, TypedLoop,
+    TypedReturn, TypedWhile, Types,
 };
+use hades_error::Span;
 
 use crate::mir::builder::MirBuilder;
+use crate::mir::operand::Operand;
 use crate::mir::place::Place;
+use crate::mir::rvalue::Rvalue;
 use crate::mir::stmt::Statement;
 use crate::mir::terminator::{RETURN_LOCAL, SwitchTargets, Terminator, TerminatorKind};
 use crate::{BasicBlock, BlockAnd, BlockAndExt, ToMir, unpack};
 
 impl ToMir for TypedBlock {
-    type Output = ();
-
-    fn to_mir(&self, builder: &mut MirBuilder<'_>, mut block: BasicBlock) -> BlockAnd<()> {
+    type Output = Option<Rvalue>;
+
+    fn to_mir(
+        &self,
+        builder: &mut MirBuilder<'_>,
+        mut block: BasicBlock,
+    ) -> BlockAnd<Option<Rvalue>> {
+        builder.enter_defer_scope();
         for stmt in &self.stmts {
             unpack!(block = stmt.to_mir(builder, block));
             if builder.is_block_terminated(block) {
-                break;
+                builder.exit_defer_scope();
+                return block.and(None);
             }
         }
-        block.unit()
+
+        let mut value = self
+            .tail
+            .as_ref()
+            .map(|tail| unpack!(block = tail.expr.to_mir(builder, block)));
+
+        let deferred = builder.exit_defer_scope();
+        if !deferred.is_empty() {
+            if let (Some(tail), Some(rvalue)) = (&self.tail, value.take()) {
+                let span = self.span.clone();
+                let tmp = builder.build_temp(tail.expr.get_type(), span.clone());
+                builder.push_stmt(block, Statement::assign(tmp.clone(), rvalue, span));
+                value = Some(Rvalue::Use(Operand::Copy(tmp)));
+            }
+            for stmt in deferred {
+                builder.push_stmt(block, stmt);
+            }
+        }
+
+        block.and(value)
     }
 }
 
@@ -61,10 +89,16 @@ impl ToMir for TypedReturn {
 }
 
 impl ToMir for TypedIf {
-    type Output = ();
+    type Output = Option<Place>;
 
-    fn to_mir(&self, builder: &mut MirBuilder<'_>, mut block: BasicBlock) -> BlockAnd<()> {
+    fn to_mir(
+        &self,
+        builder: &mut MirBuilder<'_>,
+        mut block: BasicBlock,
+    ) -> BlockAnd<Option<Place>> {
         let span = self.span.clone();
+        let result =
+            (self.typ != Types::Void).then(|| builder.build_temp(self.typ.clone(), span.clone()));
 
         let cond_rvalue = unpack!(block = self.cond.expr.to_mir(builder, block));
         let (block2, cond_op) =
@@ -84,8 +118,9 @@ impl ToMir for TypedIf {
             span.clone(),
         ));
 
-        let BlockAnd(then_exit, _) = self.then_branch.to_mir(builder, then_block);
+        let BlockAnd(then_exit, then_value) = self.then_branch.to_mir(builder, then_block);
         if !builder.is_block_terminated(then_exit) {
+            assign_result(builder, then_exit, &result, then_value, &span);
             builder.switch_to(then_exit);
             builder.terminate(Terminator::new(
                 TerminatorKind::Goto(merge_block),
@@ -94,8 +129,9 @@ impl ToMir for TypedIf {
         }
 
         if let Some(else_b) = &self.else_branch {
-            let BlockAnd(else_exit, _) = else_b.to_mir(builder, else_block);
+            let BlockAnd(else_exit, else_value) = else_b.to_mir(builder, else_block);
             if !builder.is_block_terminated(else_exit) {
+                assign_result(builder, else_exit, &result, else_value, &span);
                 builder.switch_to(else_exit);
                 builder.terminate(Terminator::new(
                     TerminatorKind::Goto(merge_block),
@@ -110,7 +146,59 @@ impl ToMir for TypedIf {
             ));
         }
 
-        merge_block.unit()
+        merge_block.and(result)
+    }
+}
+
+fn assign_result(
+    builder: &mut MirBuilder<'_>,
+    block: BasicBlock,
+    result: &Option<Place>,
+    value: Option<Rvalue>,
+    span: &Span,
+) {
+    if let (Some(place), Some(rvalue)) = (result, value) {
+        builder.push_stmt(
+            block,
+            Statement::assign(place.clone(), rvalue, span.clone()),
+        );
+    }
+}
+
+impl ToMir for TypedLoop {
+    type Output = Option<Place>;
+
+    fn to_mir(&self, builder: &mut MirBuilder<'_>, block: BasicBlock) -> BlockAnd<Option<Place>> {
+        let span = self.span.clone();
+        let result =
+            (self.typ != Types::Void).then(|| builder.build_temp(self.typ.clone(), span.clone()));
+
+        let body_block = builder.start_block();
+        let exit_block = builder.start_block();
+
+        builder.switch_to(block);
+        builder.terminate(Terminator::new(
+            TerminatorKind::Goto(body_block),
+            span.clone(),
+        ));
+
+        builder.push_loop(body_block, exit_block, result.clone());
+        let BlockAnd(body_exit, _) = self.body.to_mir(builder, body_block);
+        builder.pop_loop();
+        if !builder.is_block_terminated(body_exit) {
+            builder.switch_to(body_exit);
+            builder.terminate(Terminator::new(
+                TerminatorKind::Goto(body_block),
+                span.clone(),
+            ));
+        }
+
+        if !self.breaks {
+            builder.switch_to(exit_block);
+            builder.terminate(Terminator::new(TerminatorKind::Unreachable, span));
+        }
+
+        exit_block.and(result)
     }
 }
 
@@ -144,7 +232,7 @@ impl ToMir for TypedWhile {
             span.clone(),
         ));
 
-        builder.push_loop(header_block, exit_block);
+        builder.push_loop(header_block, exit_block, None);
         let BlockAnd(body_exit, _) = self.body.to_mir(builder, body_block);
         builder.pop_loop();
         if !builder.is_block_terminated(body_exit) {
@@ -193,7 +281,7 @@ impl ToMir for TypedFor {
             span.clone(),
         ));
 
-        builder.push_loop(update_block, exit_block);
+        builder.push_loop(update_block, exit_block, None);
         let BlockAnd(body_exit, _) = self.body.to_mir(builder, body_block);
         builder.pop_loop();
         if !builder.is_block_terminated(body_exit) {
@@ -222,8 +310,17 @@ impl ToMir for TypedFor {
 impl ToMir for TypedBreak {
     type Output = ();
 
-    fn to_mir(&self, builder: &mut MirBuilder<'_>, block: BasicBlock) -> BlockAnd<()> {
+    fn to_mir(&self, builder: &mut MirBuilder<'_>, mut block: BasicBlock) -> BlockAnd<()> {
         let loop_ctx = builder.current_loop().expect("break outside loop").clone();
+        if let Some(value) = &self.value {
+            let rvalue = unpack!(block = value.expr.to_mir(builder, block));
+            if let Some(result) = loop_ctx.result {
+                builder.push_stmt(block, Statement::assign(result, rvalue, self.span.clone()));
+            }
+        }
+        for stmt in builder.deferred_stmts_since(loop_ctx.defer_depth) {
+            builder.push_stmt(block, stmt);
+        }
         builder.switch_to(block);
         builder.terminate(Terminator::new(
             TerminatorKind::Goto(loop_ctx.break_block),
@@ -241,6 +338,9 @@ impl ToMir for TypedContinue {
             .current_loop()
             .expect("continue outside loop")
             .clone();
+        for stmt in builder.deferred_stmts_since(loop_ctx.defer_depth) {
+            builder.push_stmt(block, stmt);
+        }
         builder.switch_to(block);
         builder.terminate(Terminator::new(
             TerminatorKind::Goto(loop_ctx.continue_block),
@@ -10,6 +10,8 @@ pub enum MirConst {
     Str(String),
     Char(char),
     Null(Types),
+    /// Type argument of a compile-time builtin such as `sizeof`.
+    Type(Types),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -51,6 +51,7 @@ impl fmt::Display for MirConst {
             MirConst::Str(s) => write!(f, "\"{s}\""),
             MirConst::Char(c) => write!(f, "'{c}'"),
             MirConst::Null(_) => write!(f, "null"),
+            MirConst::Type(typ) => write!(f, "{typ}"),
         }
     }
 }
@@ -140,6 +141,16 @@ fn fmt_block(
             StatementKind::Assign(place, rvalue) => {
                 writeln!(f, "        {place} = {rvalue};")?;
             }
+            StatementKind::InlineAsm { inputs, outputs } => {
+                let inputs = inputs.iter().map(ToString::to_string).collect::<Vec<_>>();
+                let outputs = outputs.iter().map(ToString::to_string).collect::<Vec<_>>();
+                writeln!(
+                    f,
+                    "        asm({}) -> ({});",
+                    inputs.join(", "),
+                    outputs.join(", ")
+                )?;
+            }
             StatementKind::Nop => {
                 writeln!(f, "        nop;")?;
             }
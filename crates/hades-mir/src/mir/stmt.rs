@@ -2,6 +2,7 @@ use hades_ast::TypedStmt;
 use hades_error::Span;
 
 use crate::mir::builder::MirBuilder;
+use crate::mir::operand::Operand;
 use crate::mir::place::Place;
 use crate::mir::rvalue::Rvalue;
 use crate::{BasicBlock, BlockAnd, BlockAndExt, ToMir, unpack};
@@ -9,6 +10,11 @@ use crate::{BasicBlock, BlockAnd, BlockAndExt, ToMir, unpack};
 #[derive(Debug, Clone)]
 pub enum StatementKind {
     Assign(Place, Box<Rvalue>),
+    /// Inline assembly reading `inputs` and overwriting `outputs`.
+    InlineAsm {
+        inputs: Vec<Operand>,
+        outputs: Vec<Place>,
+    },
     Nop,
 }
 
@@ -26,6 +32,13 @@ impl Statement {
         }
     }
 
+    pub fn inline_asm(inputs: Vec<Operand>, outputs: Vec<Place>, span: Span) -> Self {
+        Self {
+            kind: StatementKind::InlineAsm { inputs, outputs },
+            span,
+        }
+    }
+
     pub fn nop(span: Span) -> Self {
         Self {
             kind: StatementKind::Nop,
@@ -46,17 +59,24 @@ impl ToMir for TypedStmt {
                 unpack!(block = e.expr.to_mir(builder, block));
                 block.unit()
             }
-            TypedStmt::If(s) => s.to_mir(builder, block),
+            TypedStmt::If(s) => {
+                let BlockAnd(block, _) = s.to_mir(builder, block);
+                block.unit()
+            }
             TypedStmt::While(s) => s.to_mir(builder, block),
             TypedStmt::For(s) => s.to_mir(builder, block),
-            TypedStmt::Block(b) => b.to_mir(builder, block),
+            TypedStmt::Block(b) => {
+                let BlockAnd(block, _) = b.to_mir(builder, block);
+                block.unit()
+            }
             TypedStmt::Continue(s) => s.to_mir(builder, block),
             TypedStmt::Break(s) => s.to_mir(builder, block),
             TypedStmt::Defer(s) => s.to_mir(builder, block),
             TypedStmt::FuncDef(_)
             | TypedStmt::StructDef(_)
             | TypedStmt::ModuleDecl(_)
-            | TypedStmt::Import(_) => block.unit(),
+            | TypedStmt::Import(_)
+            | TypedStmt::ExternType(_) => block.unit(),
         }
     }
 }
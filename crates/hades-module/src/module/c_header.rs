@@ -0,0 +1,1192 @@
+//! Reads the declarations `import c "foo.h"` understands out of a C header.
+//!
+//! Only a practical subset of C is recognised: function prototypes, structs with plain
+//! fields, `typedef`s, `enum` constants and `#define NAME <integer>`. Anything else, or
+//! any declaration using a type Hades cannot represent, is skipped so that ordinary
+//! headers still import. Functions taking or returning C integers narrower than 64 bits
+//! are imported but refuse to be called, since Hades has no type that matches them.
+//!
+//! Conditional directives are evaluated, so only the active branch of each `#if` is read.
+//! A condition that can't be evaluated, or an `#error` in an active branch, fails the import.
+//!
+//! Hades has no global constants, so every integer macro and enum constant becomes a
+//! function returning its value: `#define BUF_SIZE 64` is called as `foo::BUF_SIZE()`.
+
+use hades_ast::{
+    Attribute, Attributes, Block, CScalar, CWidths, Expr, ExprAst, ExternType, FieldKind, FuncBody,
+    FuncDef, Program, Return, Stmt, StructDef, Types, Value, is_reserved_builtin,
+};
+use hades_error::Span;
+use hades_tokens::{Ident, Name, ParamKind};
+use indexmap::{IndexMap, IndexSet};
+use std::collections::HashMap;
+
+/// Storage classes and qualifiers that do not change how a declaration is called.
+const QUALIFIERS: &[&str] = &[
+    "const",
+    "volatile",
+    "restrict",
+    "__restrict",
+    "__restrict__",
+    "static",
+    "inline",
+    "__inline",
+    "__inline__",
+    "extern",
+    "register",
+    "__extension__",
+];
+
+const TYPE_WORDS: &[&str] = &[
+    "void", "_Bool", "bool", "char", "short", "int", "long", "signed", "unsigned", "float",
+    "double",
+];
+
+pub fn parse(source: &str) -> Result<Program, String> {
+    let (code, defines) = preprocess(source)?;
+    let mut reader = HeaderReader::new(tokenize(&code));
+    reader.constants.extend(defines);
+    reader.read();
+    Ok(reader.into_program())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Ident(String),
+    Number(Option<i64>),
+    Str,
+    Ellipsis,
+    Punct(char),
+}
+
+impl Tok {
+    fn is_punct(&self, c: char) -> bool {
+        matches!(self, Tok::Punct(p) if *p == c)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self, Tok::Ident(w) if w == word)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CType {
+    Void,
+    Bool,
+    Char,
+    /// Any C integer type, with its width in bits and whether it is signed.
+    Int {
+        bits: u8,
+        signed: bool,
+    },
+    Float,
+    Double,
+    /// A struct by tag; [`HeaderReader::tags`] gives the name Hades knows it by.
+    Struct(String),
+    Pointer(Box<CType>),
+    Array(Box<CType>, usize),
+    /// A type with no Hades counterpart, such as a union or `long double`.
+    Unsupported,
+}
+
+enum Decl {
+    /// `fields` is `None` when the struct uses something Hades cannot lay out.
+    Struct {
+        tag: String,
+        fields: Option<Vec<(String, CType)>>,
+    },
+    Func {
+        name: String,
+        ret: CType,
+        params: Vec<(Option<String>, CType)>,
+        variadic: bool,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Position {
+    Field,
+    Param,
+    Return,
+}
+
+/// One open `#if`, `#ifdef` or `#ifndef`.
+struct Conditional {
+    /// Whether the lines around the directive are read at all.
+    enclosing: bool,
+    /// Whether the current branch is read.
+    active: bool,
+    /// Whether an earlier branch was taken, so any `#elif` or `#else` after it is not.
+    taken: bool,
+}
+
+/// Drops comments and preprocessor lines, keeping the active branch of every conditional
+/// and the `#define`s in it that have an integer value.
+fn preprocess(source: &str) -> Result<(String, Vec<(String, i64)>), String> {
+    let joined = source.replace("\\\r\n", " ").replace("\\\n", " ");
+    let stripped = strip_comments(&joined);
+
+    let mut code = String::with_capacity(stripped.len());
+    let mut defines: Vec<(String, i64)> = Vec::new();
+    // Every defined macro, with its value when that is an integer.
+    let mut macros: HashMap<String, Option<i64>> = HashMap::new();
+    let mut conditionals: Vec<Conditional> = Vec::new();
+
+    for (index, line) in stripped.lines().enumerate() {
+        let active = conditionals.last().is_none_or(|c| c.active);
+        let trimmed = line.trim_start();
+        let Some(directive) = trimmed.strip_prefix('#') else {
+            if active {
+                code.push_str(line);
+                code.push('\n');
+            }
+            continue;
+        };
+        let directive = directive.trim_start();
+        let keyword_len = directive
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(directive.len());
+        let (keyword, rest) = directive.split_at(keyword_len);
+        let rest = rest.trim();
+        let at_line = |message: String| format!("line {}: {message}", index + 1);
+
+        match keyword {
+            "if" | "ifdef" | "ifndef" => {
+                let taken = active
+                    && match keyword {
+                        "ifdef" => macros.contains_key(macro_name(rest)),
+                        "ifndef" => !macros.contains_key(macro_name(rest)),
+                        _ => eval_condition(rest, &macros).map_err(at_line)?,
+                    };
+                conditionals.push(Conditional {
+                    enclosing: active,
+                    active: taken,
+                    taken,
+                });
+            }
+            "elif" | "else" => {
+                let Some(open) = conditionals.last_mut() else {
+                    return Err(at_line(format!("#{keyword} without #if")));
+                };
+                let taken = open.enclosing
+                    && !open.taken
+                    && (keyword == "else" || eval_condition(rest, &macros).map_err(at_line)?);
+                open.active = taken;
+                open.taken |= taken;
+            }
+            "endif" => {
+                conditionals
+                    .pop()
+                    .ok_or_else(|| at_line("#endif without #if".to_string()))?;
+            }
+            _ if !active => {}
+            "error" => return Err(at_line(format!("#error {rest}"))),
+            "undef" => {
+                let name = macro_name(rest);
+                macros.remove(name);
+                defines.retain(|(defined, _)| defined != name);
+            }
+            "define" => {
+                let name = macro_name(rest);
+                if name.is_empty() {
+                    continue;
+                }
+                let value = &rest[name.len()..];
+                // Function-like macros have a `(` straight after the name.
+                let value = if value.starts_with('(') {
+                    None
+                } else {
+                    parse_int(value)
+                };
+                macros.insert(name.to_string(), value);
+                defines.retain(|(defined, _)| defined != name);
+                if let Some(value) = value {
+                    defines.push((name.to_string(), value));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !conditionals.is_empty() {
+        return Err("#if without #endif".to_string());
+    }
+    Ok((code, defines))
+}
+
+/// The identifier at the start of `text`.
+fn macro_name(text: &str) -> &str {
+    let len = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+    &text[..len]
+}
+
+/// Evaluates an `#if` condition. It may use integers, `defined`, macros with an integer
+/// value and C's unary, arithmetic, bitwise, comparison and logical operators. As in C, a
+/// name that is not a macro counts as 0.
+fn eval_condition(text: &str, macros: &HashMap<String, Option<i64>>) -> Result<bool, String> {
+    let mut condition = Condition {
+        toks: tokenize(text),
+        pos: 0,
+        macros,
+    };
+    let value = condition.binary(0);
+    match value {
+        Ok(value) if condition.pos == condition.toks.len() => Ok(value != 0),
+        Ok(_) => Err(format!("can't evaluate `#if {text}`")),
+        Err(error) => Err(format!("can't evaluate `#if {text}`: {error}")),
+    }
+}
+
+/// Binary operators from the loosest binding to the tightest.
+const BINARY_OPS: &[&[&str]] = &[
+    &["||"],
+    &["&&"],
+    &["|"],
+    &["^"],
+    &["&"],
+    &["==", "!="],
+    &["<=", ">=", "<", ">"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+struct Condition<'a> {
+    toks: Vec<Tok>,
+    pos: usize,
+    macros: &'a HashMap<String, Option<i64>>,
+}
+
+impl Condition<'_> {
+    fn binary(&mut self, level: usize) -> Result<i64, String> {
+        let Some(ops) = BINARY_OPS.get(level) else {
+            return self.unary();
+        };
+        let mut left = self.binary(level + 1)?;
+        while let Some(op) = ops.iter().find(|op| self.at_op(op)) {
+            self.pos += op.len();
+            let right = self.binary(level + 1)?;
+            left = match *op {
+                "||" => (left != 0 || right != 0) as i64,
+                "&&" => (left != 0 && right != 0) as i64,
+                "|" => left | right,
+                "^" => left ^ right,
+                "&" => left & right,
+                "==" => (left == right) as i64,
+                "!=" => (left != right) as i64,
+                "<=" => (left <= right) as i64,
+                ">=" => (left >= right) as i64,
+                "<" => (left < right) as i64,
+                ">" => (left > right) as i64,
+                "<<" => left.wrapping_shl(right as u32),
+                ">>" => left.wrapping_shr(right as u32),
+                "+" => left.wrapping_add(right),
+                "-" => left.wrapping_sub(right),
+                "*" => left.wrapping_mul(right),
+                _ if right == 0 => return Err("division by zero".to_string()),
+                "/" => left.wrapping_div(right),
+                _ => left.wrapping_rem(right),
+            };
+        }
+        Ok(left)
+    }
+
+    /// Whether `op` is next, and not just the start of a longer operator.
+    fn at_op(&self, op: &str) -> bool {
+        let at = |i: usize, c: char| self.toks.get(self.pos + i).is_some_and(|t| t.is_punct(c));
+        if !op.chars().enumerate().all(|(i, c)| at(i, c)) {
+            return false;
+        }
+        match op {
+            "|" | "&" => !op.chars().all(|c| at(1, c)),
+            "<" | ">" => !op.chars().all(|c| at(1, c)) && !at(1, '='),
+            _ => true,
+        }
+    }
+
+    fn unary(&mut self) -> Result<i64, String> {
+        let tok = self.toks.get(self.pos).cloned();
+        self.pos += 1;
+        match tok {
+            Some(Tok::Punct('!')) => Ok((self.unary()? == 0) as i64),
+            Some(Tok::Punct('~')) => Ok(!self.unary()?),
+            Some(Tok::Punct('-')) => Ok(self.unary()?.wrapping_neg()),
+            Some(Tok::Punct('+')) => self.unary(),
+            Some(Tok::Punct('(')) => {
+                let value = self.binary(0)?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some(Tok::Number(Some(value))) => Ok(value),
+            Some(Tok::Ident(word)) if word == "defined" => {
+                let parenthesized = self.toks.get(self.pos).is_some_and(|t| t.is_punct('('));
+                if parenthesized {
+                    self.pos += 1;
+                }
+                let Some(Tok::Ident(name)) = self.toks.get(self.pos).cloned() else {
+                    return Err("`defined` needs a macro name".to_string());
+                };
+                self.pos += 1;
+                if parenthesized {
+                    self.expect(')')?;
+                }
+                Ok(self.macros.contains_key(&name) as i64)
+            }
+            Some(Tok::Ident(name)) => {
+                if self.toks.get(self.pos).is_some_and(|t| t.is_punct('(')) {
+                    return Err(format!("function-like macro `{name}` is not expanded"));
+                }
+                match self.macros.get(&name) {
+                    None => Ok(0),
+                    Some(Some(value)) => Ok(*value),
+                    Some(None) => Err(format!("`{name}` does not expand to an integer")),
+                }
+            }
+            Some(_) => Err("unsupported token".to_string()),
+            None => Err("unexpected end of condition".to_string()),
+        }
+    }
+
+    fn expect(&mut self, close: char) -> Result<(), String> {
+        if self.toks.get(self.pos).is_some_and(|t| t.is_punct(close)) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("expected `{close}`"))
+        }
+    }
+}
+
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    if c == '\n' {
+                        out.push('\n');
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            '"' | '\'' => {
+                out.push(c);
+                let mut escaped = false;
+                for inner in chars.by_ref() {
+                    out.push(inner);
+                    if inner == c && !escaped {
+                        break;
+                    }
+                    escaped = inner == '\\' && !escaped;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Parses an integer literal, allowing surrounding parentheses, a sign and `u`/`l` suffixes.
+fn parse_int(text: &str) -> Option<i64> {
+    let mut text = text.trim();
+    while let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        text = inner.trim();
+    }
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, text),
+    };
+    let digits = digits.trim_end_matches(['u', 'U', 'l', 'L']);
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse::<i64>().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+fn tokenize(code: &str) -> Vec<Tok> {
+    let bytes = code.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            tokens.push(Tok::Ident(code[start..i].to_string()));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                i += 1;
+            }
+            tokens.push(Tok::Number(parse_int(&code[start..i])));
+        } else if c == '"' || c == '\'' {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i] as char != c {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i += 1;
+            tokens.push(if c == '"' {
+                Tok::Str
+            } else {
+                let literal = code.get(start + 1..i - 1).unwrap_or_default();
+                Tok::Number(literal.chars().next().map(|ch| ch as i64))
+            });
+        } else if bytes[i..].starts_with(b"...") {
+            tokens.push(Tok::Ellipsis);
+            i += 3;
+        } else {
+            let c = code[i..].chars().next().unwrap_or_default();
+            tokens.push(Tok::Punct(c));
+            i += c.len_utf8();
+        }
+    }
+    tokens
+}
+
+/// Whether the tokens after a prototype's parameters are only macros such as
+/// `__THROW` or `__nonnull ((1))`.
+fn is_annotation_tail(toks: &[Tok]) -> bool {
+    let mut i = 0;
+    while i < toks.len() {
+        if !matches!(toks[i], Tok::Ident(_)) {
+            return false;
+        }
+        i += 1;
+        if toks.get(i).is_some_and(|t| t.is_punct('(')) {
+            i = matching(toks, i) + 1;
+        }
+    }
+    true
+}
+
+/// Index of the bracket closing the one at `open`, or the end of `toks`.
+fn matching(toks: &[Tok], open: usize) -> usize {
+    let mut depth = 0;
+    for (i, tok) in toks.iter().enumerate().skip(open) {
+        match tok {
+            Tok::Punct('(' | '[' | '{') => depth += 1,
+            Tok::Punct(')' | ']' | '}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    toks.len()
+}
+
+/// Splits `toks` on `sep` outside any brackets.
+fn split_top_level(toks: &[Tok], sep: char) -> Vec<&[Tok]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, tok) in toks.iter().enumerate() {
+        match tok {
+            Tok::Punct('(' | '[' | '{') => depth += 1,
+            Tok::Punct(')' | ']' | '}') => depth -= 1,
+            Tok::Punct(c) if *c == sep && depth == 0 => {
+                parts.push(&toks[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < toks.len() {
+        parts.push(&toks[start..]);
+    }
+    parts
+}
+
+/// Removes `__attribute__((...))`, `__declspec(...)` and `__asm__("...")` annotations.
+fn strip_annotations(toks: &[Tok]) -> Vec<Tok> {
+    let mut out = Vec::with_capacity(toks.len());
+    let mut i = 0;
+    while i < toks.len() {
+        let annotation = matches!(
+            &toks[i],
+            Tok::Ident(w) if matches!(w.as_str(), "__attribute__" | "__declspec" | "__asm__" | "__asm" | "asm")
+        );
+        if annotation && toks.get(i + 1).is_some_and(|t| t.is_punct('(')) {
+            i = matching(toks, i + 1) + 1;
+        } else {
+            out.push(toks[i].clone());
+            i += 1;
+        }
+    }
+    out
+}
+
+struct HeaderReader {
+    tokens: Vec<Tok>,
+    pos: usize,
+    typedefs: HashMap<String, CType>,
+    /// Struct tag to the name the struct is imported under, usually its typedef name.
+    tags: HashMap<String, String>,
+    decls: Vec<Decl>,
+    constants: Vec<(String, i64)>,
+}
+
+impl HeaderReader {
+    fn new(tokens: Vec<Tok>) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            typedefs: HashMap::new(),
+            tags: HashMap::new(),
+            decls: Vec::new(),
+            constants: Vec::new(),
+        }
+    }
+
+    fn read(&mut self) {
+        while let Some(tok) = self.tokens.get(self.pos) {
+            if tok.is_punct(';') || tok.is_punct('}') {
+                self.pos += 1;
+            } else if tok.is_word("extern") && self.tokens.get(self.pos + 1) == Some(&Tok::Str) {
+                // `extern "C" {` from C++ guards.
+                self.pos += 2;
+                if self.tokens.get(self.pos).is_some_and(|t| t.is_punct('{')) {
+                    self.pos += 1;
+                }
+            } else {
+                let Some(stmt) = self.next_statement() else {
+                    continue;
+                };
+                let stmt = strip_annotations(&stmt);
+                match stmt.split_first() {
+                    Some((first, rest)) if first.is_word("typedef") => self.typedef(rest),
+                    _ => self.declaration(&stmt),
+                }
+            }
+        }
+    }
+
+    /// Takes tokens up to the next top-level `;`. Function definitions are skipped whole
+    /// and yield `None`.
+    fn next_statement(&mut self) -> Option<Vec<Tok>> {
+        let start = self.pos;
+        while self.pos < self.tokens.len() {
+            let tok = &self.tokens[self.pos];
+            if tok.is_punct(';') {
+                self.pos += 1;
+                return Some(self.tokens[start..self.pos - 1].to_vec());
+            }
+            if tok.is_punct('(') || tok.is_punct('[') || tok.is_punct('{') {
+                let is_body = tok.is_punct('{')
+                    && self.pos > start
+                    && self.tokens[self.pos - 1].is_punct(')');
+                self.pos = matching(&self.tokens, self.pos) + 1;
+                if is_body {
+                    return None;
+                }
+            } else {
+                self.pos += 1;
+            }
+        }
+        Some(self.tokens[start..].to_vec())
+    }
+
+    fn declaration(&mut self, stmt: &[Tok]) {
+        let body = stmt.iter().position(|t| t.is_punct('{'));
+        let head = stmt
+            .iter()
+            .position(|t| !matches!(t, Tok::Ident(w) if QUALIFIERS.contains(&w.as_str())))
+            .unwrap_or(stmt.len());
+        match (stmt.get(head), body) {
+            (Some(kind), Some(open)) if kind.is_word("struct") || kind.is_word("union") => {
+                let tag = match stmt.get(head + 1) {
+                    Some(Tok::Ident(tag)) => tag.clone(),
+                    _ => return,
+                };
+                let close = matching(stmt, open);
+                self.define_struct(kind.is_word("struct"), tag, &stmt[open + 1..close]);
+            }
+            (Some(kind), Some(open)) if kind.is_word("enum") => {
+                let close = matching(stmt, open);
+                self.enum_constants(&stmt[open + 1..close]);
+            }
+            (_, None) => self.prototype(stmt),
+            _ => {}
+        }
+    }
+
+    fn typedef(&mut self, stmt: &[Tok]) {
+        let Some(open) = stmt.iter().position(|t| t.is_punct('{')) else {
+            let Some((base, start)) = self.specifiers(stmt) else {
+                return;
+            };
+            if let Some((typ, Some(name))) = self.declarator(base, &stmt[start..]) {
+                if let CType::Struct(tag) = &typ {
+                    self.tags.entry(tag.clone()).or_insert(name.clone());
+                }
+                self.typedefs.insert(name, typ);
+            }
+            return;
+        };
+
+        let close = matching(stmt, open);
+        let body = &stmt[open + 1..close];
+        let declarators = split_top_level(&stmt[close + 1..], ',');
+        let base = match stmt.first() {
+            Some(kind) if kind.is_word("enum") => {
+                self.enum_constants(body);
+                CType::Int {
+                    bits: 32,
+                    signed: true,
+                }
+            }
+            Some(kind) if kind.is_word("struct") || kind.is_word("union") => {
+                let plain_name = declarators.iter().find_map(|d| match d {
+                    [Tok::Ident(name)] => Some(name.clone()),
+                    _ => None,
+                });
+                let tag = match (stmt.get(1), plain_name) {
+                    (Some(Tok::Ident(tag)), Some(name)) => {
+                        self.tags.entry(tag.clone()).or_insert(name);
+                        tag.clone()
+                    }
+                    (Some(Tok::Ident(tag)), None) => tag.clone(),
+                    (_, Some(name)) => name,
+                    (_, None) => return,
+                };
+                self.define_struct(kind.is_word("struct"), tag.clone(), body);
+                CType::Struct(tag)
+            }
+            _ => return,
+        };
+        for decl in declarators {
+            if let Some((typ, Some(name))) = self.declarator(base.clone(), decl) {
+                self.typedefs.insert(name, typ);
+            }
+        }
+    }
+
+    fn define_struct(&mut self, is_struct: bool, tag: String, body: &[Tok]) {
+        self.tags.entry(tag.clone()).or_insert(tag.clone());
+        let fields = if is_struct {
+            self.struct_fields(body)
+        } else {
+            None
+        };
+        self.decls.push(Decl::Struct { tag, fields });
+    }
+
+    fn struct_fields(&self, body: &[Tok]) -> Option<Vec<(String, CType)>> {
+        let mut fields = Vec::new();
+        for field in split_top_level(body, ';') {
+            if field.is_empty() {
+                continue;
+            }
+            // Nested definitions and bitfields have no Hades layout.
+            if field.iter().any(|t| t.is_punct('{') || t.is_punct(':')) {
+                return None;
+            }
+            let (base, start) = self.specifiers(field)?;
+            for declarator in split_top_level(&field[start..], ',') {
+                let (typ, name) = self.declarator(base.clone(), declarator)?;
+                fields.push((name?, typ));
+            }
+        }
+        Some(fields)
+    }
+
+    fn enum_constants(&mut self, body: &[Tok]) {
+        let mut next = Some(0);
+        for item in split_top_level(body, ',') {
+            let Some(Tok::Ident(name)) = item.first() else {
+                continue;
+            };
+            let value = match item.get(1..) {
+                Some([Tok::Punct('='), value @ ..]) => self.constant_value(value),
+                _ => next,
+            };
+            if let Some(value) = value {
+                self.constants.push((name.clone(), value));
+            }
+            next = value.map(|v| v + 1);
+        }
+    }
+
+    fn constant_value(&self, toks: &[Tok]) -> Option<i64> {
+        match toks {
+            [Tok::Number(value)] => *value,
+            [Tok::Punct('-'), Tok::Number(value)] => value.map(|v| -v),
+            [Tok::Ident(name)] => self
+                .constants
+                .iter()
+                .find(|(constant, _)| constant == name)
+                .map(|(_, value)| *value),
+            _ => None,
+        }
+    }
+
+    fn prototype(&mut self, stmt: &[Tok]) {
+        let Some(open) = stmt.iter().position(|t| t.is_punct('(')) else {
+            return;
+        };
+        // `int (*callback)(int);` declares a variable, not a function.
+        if open == 0 || stmt.get(open + 1).is_some_and(|t| t.is_punct('*')) {
+            return;
+        }
+        let close = matching(stmt, open);
+        if !is_annotation_tail(&stmt[close + 1..]) {
+            return;
+        }
+        let Some((base, start)) = self.specifiers(&stmt[..open]) else {
+            return;
+        };
+        let Some((ret, Some(name))) = self.declarator(base, &stmt[start..open]) else {
+            return;
+        };
+
+        let mut params = Vec::new();
+        let mut variadic = false;
+        let args = &stmt[open + 1..close];
+        let no_params = args.is_empty() || (args.len() == 1 && args[0].is_word("void"));
+        if !no_params {
+            for param in split_top_level(args, ',') {
+                if param == [Tok::Ellipsis] {
+                    variadic = true;
+                    continue;
+                }
+                let Some((base, start)) = self.specifiers(param) else {
+                    return;
+                };
+                let Some(param) = self.declarator(base, &param[start..]) else {
+                    return;
+                };
+                params.push((param.1, param.0));
+            }
+        }
+        self.decls.push(Decl::Func {
+            name,
+            ret,
+            params,
+            variadic,
+        });
+    }
+
+    /// Reads the type specifiers at the start of a declaration, returning the base type
+    /// and where the declarator begins.
+    fn specifiers(&self, toks: &[Tok]) -> Option<(CType, usize)> {
+        let mut words = Vec::new();
+        let mut base = None;
+        let mut i = 0;
+        while let Some(Tok::Ident(word)) = toks.get(i) {
+            let word = word.as_str();
+            if QUALIFIERS.contains(&word) {
+                i += 1;
+            } else if TYPE_WORDS.contains(&word) {
+                words.push(word);
+                i += 1;
+            } else if matches!(word, "struct" | "union" | "enum") && base.is_none() {
+                let Some(Tok::Ident(tag)) = toks.get(i + 1) else {
+                    return None;
+                };
+                base = Some(match word {
+                    "struct" => CType::Struct(tag.clone()),
+                    "enum" => CType::Int {
+                        bits: 32,
+                        signed: true,
+                    },
+                    _ => CType::Unsupported,
+                });
+                i += 2;
+            } else if base.is_none() && words.is_empty() {
+                // Export macros such as `API int f(void);` are left unexpanded.
+                if self.is_annotation(word, toks.get(i + 1)) {
+                    i += 1;
+                    continue;
+                }
+                base = Some(self.named_type(word));
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        let base = match base {
+            Some(base) => base,
+            None if words.is_empty() => return None,
+            None => type_from_words(&words),
+        };
+        Some((base, i))
+    }
+
+    /// Applies pointers, the name and array suffixes of a declarator to `base`.
+    fn declarator(&self, base: CType, toks: &[Tok]) -> Option<(CType, Option<String>)> {
+        let mut typ = base;
+        let mut i = 0;
+        while let Some(tok) = toks.get(i) {
+            match tok {
+                Tok::Punct('*') => typ = CType::Pointer(Box::new(typ)),
+                Tok::Ident(word) if QUALIFIERS.contains(&word.as_str()) => {}
+                _ => break,
+            }
+            i += 1;
+        }
+        // Function pointers are passed around as untyped pointers.
+        if toks.get(i).is_some_and(|t| t.is_punct('(')) {
+            let close = matching(toks, i);
+            let name = toks[i + 1..close].iter().find_map(|t| match t {
+                Tok::Ident(name) if !QUALIFIERS.contains(&name.as_str()) => Some(name.clone()),
+                _ => None,
+            });
+            return Some((CType::Pointer(Box::new(CType::Void)), name));
+        }
+        let name = match toks.get(i) {
+            Some(Tok::Ident(name)) => {
+                i += 1;
+                Some(name.clone())
+            }
+            _ => None,
+        };
+        let mut dims = Vec::new();
+        while let Some(tok) = toks.get(i) {
+            match (tok, toks.get(i + 1), toks.get(i + 2)) {
+                (Tok::Punct('['), Some(Tok::Number(Some(len))), Some(Tok::Punct(']'))) => {
+                    dims.push(usize::try_from(*len).ok()?);
+                    i += 3;
+                }
+                (Tok::Punct('['), Some(Tok::Ident(constant)), Some(Tok::Punct(']'))) => {
+                    let len = self.constant_value(&[Tok::Ident(constant.clone())])?;
+                    dims.push(usize::try_from(len).ok()?);
+                    i += 3;
+                }
+                (Tok::Punct('['), Some(Tok::Punct(']')), _) => {
+                    typ = CType::Pointer(Box::new(typ));
+                    i += 2;
+                }
+                _ => return None,
+            }
+        }
+        for len in dims.into_iter().rev() {
+            typ = CType::Array(Box::new(typ), len);
+        }
+        Some((typ, name))
+    }
+
+    /// An unknown identifier directly before a type is an unexpanded macro.
+    fn is_annotation(&self, word: &str, next: Option<&Tok>) -> bool {
+        let Some(Tok::Ident(next)) = next else {
+            return false;
+        };
+        self.named_type(word) == CType::Unsupported
+            && (QUALIFIERS.contains(&next.as_str())
+                || TYPE_WORDS.contains(&next.as_str())
+                || matches!(next.as_str(), "struct" | "union" | "enum")
+                || self.named_type(next) != CType::Unsupported)
+    }
+
+    fn named_type(&self, name: &str) -> CType {
+        if let Some(typ) = self.typedefs.get(name) {
+            return typ.clone();
+        }
+        let (bits, signed) = match name {
+            "int8_t" => (8, true),
+            "uint8_t" => (8, false),
+            "int16_t" => (16, true),
+            "uint16_t" => (16, false),
+            "int32_t" => (32, true),
+            "uint32_t" => (32, false),
+            "int64_t" | "ssize_t" | "ptrdiff_t" | "intptr_t" | "intmax_t" | "off_t" => (64, true),
+            "uint64_t" | "size_t" | "uintptr_t" | "uintmax_t" => (64, false),
+            _ => return CType::Unsupported,
+        };
+        CType::Int { bits, signed }
+    }
+
+    fn into_program(self) -> Program {
+        Lowering::new(&self.tags).program(self.decls, self.constants)
+    }
+}
+
+/// The C type a call converts an `int` or `float` to or from, when it is narrower.
+fn narrow(typ: &CType) -> Option<CScalar> {
+    match typ {
+        CType::Int { bits, signed } if *bits < 64 => Some(CScalar::Int {
+            bits: *bits,
+            signed: *signed,
+        }),
+        CType::Float => Some(CScalar::Float),
+        _ => None,
+    }
+}
+
+fn type_from_words(words: &[&str]) -> CType {
+    let has = |word| words.contains(&word);
+    if has("void") {
+        CType::Void
+    } else if has("_Bool") || has("bool") {
+        CType::Bool
+    } else if has("char") {
+        CType::Char
+    } else if has("float") {
+        CType::Float
+    } else if has("double") {
+        if has("long") {
+            CType::Unsupported
+        } else {
+            CType::Double
+        }
+    } else {
+        let bits = if has("short") {
+            16
+        } else if has("long") {
+            64
+        } else {
+            32
+        };
+        CType::Int {
+            bits,
+            signed: !has("unsigned"),
+        }
+    }
+}
+
+/// Turns the collected declarations into synthetic `extern` Hades items.
+struct Lowering<'a> {
+    tags: &'a HashMap<String, String>,
+    structs: IndexSet<String>,
+    /// Structs only ever seen behind a pointer; they become `extern type`s.
+    referenced: IndexSet<String>,
+}
+
+impl<'a> Lowering<'a> {
+    fn new(tags: &'a HashMap<String, String>) -> Self {
+        Self {
+            tags,
+            structs: IndexSet::new(),
+            referenced: IndexSet::new(),
+        }
+    }
+
+    fn program(mut self, decls: Vec<Decl>, constants: Vec<(String, i64)>) -> Program {
+        let mut items = Vec::new();
+        let mut functions = IndexSet::new();
+        for decl in decls {
+            match decl {
+                Decl::Struct { tag, fields } => {
+                    let name = self.struct_name(&tag);
+                    if self.structs.contains(&name) {
+                        continue;
+                    }
+                    let Some(fields) = fields.and_then(|fields| self.fields(fields)) else {
+                        continue;
+                    };
+                    self.structs.insert(name.clone());
+                    items.push(Stmt::StructDef(StructDef {
+                        name: Name::new(name, Span::default()),
+                        public_fields: fields.keys().cloned().collect(),
+                        fields,
+                        export: false,
+                        public: true,
+                        attributes: Attributes::new(vec![Attribute::ReprC]),
+                        field_attributes: IndexMap::new(),
+                        span: Span::default(),
+                    }));
+                }
+                Decl::Func {
+                    name,
+                    ret,
+                    params,
+                    variadic,
+                } => {
+                    // A C function named like a builtin such as `splat` can't be called.
+                    if functions.contains(&name) || is_reserved_builtin(&name) {
+                        continue;
+                    }
+                    let Some(func) = self.extern_fn(&name, ret, params, variadic) else {
+                        continue;
+                    };
+                    functions.insert(name);
+                    items.push(Stmt::FuncDef(func));
+                }
+            }
+        }
+        for (name, value) in constants {
+            if !is_reserved_builtin(&name) && functions.insert(name.clone()) {
+                items.push(Stmt::FuncDef(constant_fn(name, value)));
+            }
+        }
+
+        let opaque = self
+            .referenced
+            .iter()
+            .filter(|name| !self.structs.contains(*name))
+            .map(|name| {
+                Stmt::ExternType(ExternType {
+                    name: Name::new(name.clone(), Span::default()),
+                    public: true,
+                    span: Span::default(),
+                })
+            });
+        Program::new(opaque.chain(items).collect())
+    }
+
+    fn struct_name(&self, tag: &str) -> String {
+        self.tags
+            .get(tag)
+            .cloned()
+            .unwrap_or_else(|| tag.to_string())
+    }
+
+    fn fields(&mut self, fields: Vec<(String, CType)>) -> Option<IndexMap<Ident, FieldKind>> {
+        fields
+            .into_iter()
+            .map(|(name, typ)| {
+                let typ = self.lower(&typ, Position::Field)?;
+                Some((Ident::new(name, Span::default()), FieldKind::Var(typ)))
+            })
+            .collect()
+    }
+
+    fn extern_fn(
+        &mut self,
+        name: &str,
+        ret: CType,
+        params: Vec<(Option<String>, CType)>,
+        variadic: bool,
+    ) -> Option<FuncDef> {
+        let widths = CWidths {
+            ret: narrow(&ret),
+            params: params.iter().map(|(_, typ)| narrow(typ)).collect(),
+        };
+        let return_type = self.lower(&ret, Position::Return)?;
+        let params = params
+            .into_iter()
+            .enumerate()
+            .map(|(i, (param, typ))| {
+                let param = param.unwrap_or_else(|| format!("arg{i}"));
+                let typ = self.lower(&typ, Position::Param)?;
+                Some((ParamKind::Ident(Ident::new(param, Span::default())), typ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(FuncDef {
+            name: Name::new(name.to_string(), Span::default()),
+            receiver: None,
+            params,
+            defaults: IndexMap::new(),
+            variadic: None,
+            return_type,
+            body: FuncBody::Extern {
+                variadic,
+                link: None,
+                widths,
+            },
+            export: false,
+            public: true,
+            attributes: Attributes::default(),
+            span: Span::default(),
+        })
+    }
+
+    /// Maps a C type onto Hades. A narrower C integer or `float` parameter or return value
+    /// is seen as `int` or `float`, and converted at the call from the width
+    /// [`Lowering::extern_fn`] records. A struct field must match its C layout exactly.
+    fn lower(&mut self, typ: &CType, pos: Position) -> Option<Types> {
+        match typ {
+            CType::Void if pos == Position::Return => Some(Types::Void),
+            CType::Bool => Some(Types::Bool),
+            CType::Char => Some(Types::Char),
+            CType::Int { bits: 64, .. } => Some(Types::Int),
+            CType::Int { .. } if pos != Position::Field => Some(Types::Int),
+            CType::Float if pos != Position::Field => Some(Types::Float),
+            CType::Double => Some(Types::Float),
+            CType::Struct(tag) => {
+                let name = self.struct_name(tag);
+                self.structs
+                    .contains(&name)
+                    .then(|| Types::Struct(Name::new(name, Span::default())))
+            }
+            CType::Pointer(inner) => Some(self.lower_pointer(inner)),
+            CType::Array(elem, _) if pos == Position::Param => Some(self.lower_pointer(elem)),
+            CType::Array(elem, len) => self.lower_array(elem, *len),
+            _ => None,
+        }
+    }
+
+    fn lower_pointer(&mut self, pointee: &CType) -> Types {
+        match pointee {
+            CType::Char => Types::String,
+            CType::Struct(tag) => {
+                let name = self.struct_name(tag);
+                if !self.structs.contains(&name) {
+                    self.referenced.insert(name.clone());
+                }
+                Types::Pointer(Box::new(Types::Struct(Name::new(name, Span::default()))))
+            }
+            CType::Pointer(inner) => Types::Pointer(Box::new(self.lower_pointer(inner))),
+            other => {
+                // Pointees without an exact Hades layout fall back to `&void`.
+                let pointee = self.lower(other, Position::Field).unwrap_or(Types::Void);
+                Types::Pointer(Box::new(pointee))
+            }
+        }
+    }
+
+    fn lower_array(&mut self, elem: &CType, len: usize) -> Option<Types> {
+        match self.lower(elem, Position::Field)? {
+            Types::Array(_) => None,
+            elem => Some(Types::Array(elem.array_type(len))),
+        }
+    }
+}
+
+/// `#define NAME 42` becomes `fn NAME(): int { return 42; }`.
+fn constant_fn(name: String, value: i64) -> FuncDef {
+    let value = ExprAst {
+        expr: Expr::Value(Value::Number(value)),
+        span: Span::default(),
+    };
+    let body = Block::new(
+        Program::new(vec![Stmt::Return(Return {
+            expr: Some(value),
+            span: Span::default(),
+        })]),
+        Span::default(),
+    );
+    FuncDef {
+        name: Name::new(name, Span::default()),
+        receiver: None,
+        params: Vec::new(),
+        defaults: IndexMap::new(),
+        variadic: None,
+        return_type: Types::Int,
+        body: FuncBody::Block(body),
+        export: false,
+        public: true,
+        attributes: Attributes::default(),
+        span: Span::default(),
+    }
+}
@@ -0,0 +1,239 @@
+use hades_ast::{
+    AssignTarget, Attributes, Block, CallKind, Cfg, Expr, FieldKind, FuncBody, FuncDef, If, Let,
+    Program, Stmt, StructDef, Value,
+};
+use std::collections::HashSet;
+
+/// The conditions that hold for one build. `@cfg(...)` declarations and statements whose
+/// conditions are not all in the set are dropped right after parsing, so they are never
+/// type-checked or compiled.
+#[derive(Clone, Debug)]
+pub struct CfgSet {
+    enabled: HashSet<Cfg>,
+}
+
+impl Default for CfgSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CfgSet {
+    /// Only `os` is set by default, to the host operating system. Flags such as `debug` are
+    /// only set by `--cfg`; the build profile doesn't imply any.
+    pub fn new() -> Self {
+        let mut enabled = HashSet::new();
+        enabled.insert(Cfg::new("os", Some(std::env::consts::OS.to_string())));
+        Self { enabled }
+    }
+
+    /// `--feature name`, the same as `--cfg feature=name`.
+    pub fn with_feature(mut self, name: impl Into<String>) -> Self {
+        self.enabled.insert(Cfg::new("feature", Some(name.into())));
+        self
+    }
+
+    /// `--cfg debug` sets a flag, `--cfg key=value` a pair. A pair for `os` replaces the
+    /// host operating system.
+    pub fn with_cfg(mut self, spec: &str) -> Self {
+        let cfg = match spec.split_once('=') {
+            Some((key, value)) => {
+                Cfg::new(key.trim(), Some(value.trim().trim_matches('"').to_string()))
+            }
+            None => Cfg::new(spec.trim(), None),
+        };
+        if cfg.key == "os" {
+            self.enabled.retain(|enabled| enabled.key != "os");
+        }
+        self.enabled.insert(cfg);
+        self
+    }
+
+    pub fn holds<'a>(&self, mut cfgs: impl Iterator<Item = &'a Cfg>) -> bool {
+        cfgs.all(|cfg| self.enabled.contains(cfg))
+    }
+
+    /// Drops every disabled item, field, method and statement in `program` and unwraps the
+    /// enabled `@cfg` statements.
+    pub fn apply(&self, program: &mut Program) {
+        let stmts = std::mem::replace(program, Program::new(Vec::new()));
+        *program = stmts
+            .into_iter()
+            .filter_map(|stmt| self.stmt(stmt))
+            .collect::<Vec<_>>()
+            .into();
+    }
+
+    fn stmt(&self, stmt: Stmt) -> Option<Stmt> {
+        match stmt {
+            Stmt::Cfg(cfg) if self.holds(cfg.cfgs.iter()) => self.stmt(*cfg.stmt),
+            Stmt::Cfg(_) => None,
+            Stmt::FuncDef(mut func) if self.enabled(&func.attributes) => {
+                self.func(&mut func);
+                Some(Stmt::FuncDef(func))
+            }
+            Stmt::StructDef(mut def) if self.enabled(&def.attributes) => {
+                self.struct_def(&mut def);
+                Some(Stmt::StructDef(def))
+            }
+            Stmt::FuncDef(_) | Stmt::StructDef(_) => None,
+            mut stmt => {
+                self.nested(&mut stmt);
+                Some(stmt)
+            }
+        }
+    }
+
+    fn enabled(&self, attributes: &Attributes) -> bool {
+        self.holds(attributes.cfgs())
+    }
+
+    fn struct_def(&self, def: &mut StructDef) {
+        let field_attributes = &def.field_attributes;
+        def.fields.retain(|name, field| match field {
+            FieldKind::Var(_) => field_attributes
+                .get(name)
+                .is_none_or(|attrs| self.enabled(attrs)),
+            FieldKind::Func(method) => self.enabled(&method.attributes),
+        });
+        for field in def.fields.values_mut() {
+            if let FieldKind::Func(method) = field {
+                self.func(method);
+            }
+        }
+        let fields = &def.fields;
+        def.field_attributes
+            .retain(|name, _| fields.contains_key(name));
+    }
+
+    fn func(&self, func: &mut FuncDef) {
+        if let FuncBody::Block(body) = &mut func.body {
+            self.block(body);
+        }
+    }
+
+    fn nested(&self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::Let(decl) => self.let_(decl),
+            Stmt::Expr(expr) => self.expr(&mut expr.expr),
+            Stmt::If(if_stmt) => self.if_(if_stmt),
+            Stmt::While(w) => {
+                self.expr(&mut w.cond);
+                self.block(&mut w.body);
+            }
+            Stmt::For(f) => {
+                self.let_(&mut f.init);
+                self.expr(&mut f.cond.left);
+                self.expr(&mut f.cond.right);
+                self.assign_target(&mut f.update.target);
+                self.expr(&mut f.update.value);
+                self.block(&mut f.body);
+            }
+            Stmt::ForEach(each) => {
+                self.expr(&mut each.iterable.expr);
+                self.block(&mut each.body);
+            }
+            Stmt::Block(block) => self.block(block),
+            Stmt::Return(ret) => {
+                if let Some(expr) = &mut ret.expr {
+                    self.expr(&mut expr.expr);
+                }
+            }
+            Stmt::Break(b) => {
+                if let Some(expr) = &mut b.value {
+                    self.expr(&mut expr.expr);
+                }
+            }
+            Stmt::Defer(d) => self.block(&mut d.stmt),
+            Stmt::Continue(_)
+            | Stmt::StructDef(_)
+            | Stmt::ExternType(_)
+            | Stmt::FuncDef(_)
+            | Stmt::ModuleDecl(_)
+            | Stmt::Import(_)
+            | Stmt::Cfg(_) => {}
+        }
+    }
+
+    fn let_(&self, decl: &mut Let) {
+        self.expr(&mut decl.value.expr);
+    }
+
+    fn if_(&self, if_stmt: &mut If) {
+        self.expr(&mut if_stmt.cond.expr);
+        self.block(&mut if_stmt.then_branch);
+        if let Some(else_branch) = &mut if_stmt.else_branch {
+            self.block(else_branch);
+        }
+    }
+
+    fn block(&self, block: &mut Block) {
+        self.apply(&mut block.stmts);
+        if let Some(tail) = &mut block.tail {
+            self.expr(&mut tail.expr);
+        }
+    }
+
+    /// Blocks can sit anywhere inside an expression, e.g. in an `if` passed as an argument.
+    fn expr(&self, expr: &mut Expr) {
+        match expr {
+            Expr::Value(Value::Array(array)) => {
+                array.elem.iter_mut().for_each(|elem| self.expr(elem));
+                if let Some(fill) = &mut array.fill {
+                    self.expr(fill);
+                }
+            }
+            Expr::StructInit(init) => init.fields.values_mut().for_each(|e| self.expr(e)),
+            Expr::ArrayIndex(index) => {
+                self.expr(&mut index.expr);
+                self.expr(&mut index.index);
+            }
+            Expr::Binary(bin) => {
+                self.expr(&mut bin.left);
+                self.expr(&mut bin.right);
+            }
+            Expr::Unary { expr, .. } => self.expr(expr),
+            Expr::Assign(assign) => {
+                self.assign_target(&mut assign.target);
+                self.expr(&mut assign.value);
+            }
+            Expr::As(cast) => self.expr(&mut cast.expr),
+            Expr::FieldAccess(access) => self.expr(&mut access.expr),
+            Expr::Call(call) => {
+                let (args, named_args) = match call {
+                    CallKind::Function(call) => (&mut call.args, &mut call.named_args),
+                    CallKind::Method(call) => {
+                        self.expr(&mut call.receiver);
+                        (&mut call.args, &mut call.named_args)
+                    }
+                    CallKind::Qualified(call) => (&mut call.args, &mut call.named_args),
+                };
+                args.iter_mut()
+                    .chain(named_args.values_mut())
+                    .for_each(|arg| self.expr(arg));
+            }
+            Expr::If(if_expr) => self.if_(if_expr),
+            Expr::Block(block) => self.block(block),
+            Expr::Loop(lp) => self.block(&mut lp.body),
+            Expr::Asm(asm) => {
+                asm.inputs.iter_mut().for_each(|(_, e)| self.expr(e));
+                asm.outputs
+                    .iter_mut()
+                    .for_each(|(_, target)| self.assign_target(target));
+            }
+            Expr::Value(_) | Expr::Ident(_) | Expr::Null | Expr::Type(_) => {}
+        }
+    }
+
+    fn assign_target(&self, target: &mut AssignTarget) {
+        match target {
+            AssignTarget::Ident(_) => {}
+            AssignTarget::FieldAccess(access) => self.expr(&mut access.expr),
+            AssignTarget::ArrayIndex(index) => {
+                self.expr(&mut index.expr);
+                self.expr(&mut index.index);
+            }
+            AssignTarget::Deref(expr) => self.expr(expr),
+        }
+    }
+}
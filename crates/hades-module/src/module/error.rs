@@ -5,12 +5,18 @@ pub enum ModuleError {
     #[error("Module not found: {0}")]
     NotFound(String),
 
-    #[error("Circular dependency detected")]
-    CircularDependency,
-
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("Parse error in module {module}: {error}")]
     ParseError { module: String, error: String },
+
+    #[error("Invalid manifest {path}: {error}")]
+    Manifest { path: String, error: String },
+
+    #[error("Invalid std library directory {path}: {error}")]
+    StdLibrary { path: String, error: String },
+
+    #[error("Package '{package}' imported by {module} is not one of its dependencies")]
+    UnknownPackage { package: String, module: String },
 }
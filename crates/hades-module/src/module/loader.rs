@@ -1,3 +1,5 @@
+use crate::module::c_header;
+use crate::module::cfg::CfgSet;
 use crate::module::error::ModuleError;
 use crate::module::path::ModulePath;
 use crate::module::resolver::Resolver;
@@ -45,23 +47,37 @@ impl Cache<PathBuf> for SourceCache {
 pub struct Loader {
     resolver: Resolver,
     lib: Library,
+    cfg: CfgSet,
 }
 
 impl Loader {
-    pub fn new(resolver: Resolver) -> Self {
-        Self {
-            resolver,
-            lib: Library::new(),
-        }
+    pub fn new(resolver: Resolver, lib: Library, cfg: CfgSet) -> Self {
+        Self { resolver, lib, cfg }
     }
 
     pub fn load(&self, module_path: &ModulePath) -> Result<Module, ModuleError> {
         match module_path {
-            ModulePath::Local(_) => self.load_local_module(module_path),
+            ModulePath::Local(_) | ModulePath::Package(..) => self.load_local_module(module_path),
             ModulePath::Std(name) => self.load_std_module(name, module_path),
+            ModulePath::C(_) => self.load_c_header(module_path),
         }
     }
 
+    /// A C header becomes a module of synthetic `extern` declarations with no imports.
+    fn load_c_header(&self, module_path: &ModulePath) -> Result<Module, ModuleError> {
+        let file_path = self.resolver.to_file_path(module_path)?;
+        let source = std::fs::read_to_string(&file_path)?;
+        let ast = c_header::parse(&source).map_err(|error| ModuleError::ParseError {
+            module: module_path.to_string(),
+            error,
+        })?;
+        Ok(Module {
+            path: module_path.clone(),
+            ast,
+            imports: Vec::new(),
+        })
+    }
+
     fn load_local_module(&self, module_path: &ModulePath) -> Result<Module, ModuleError> {
         let file_path = self.resolver.to_file_path(module_path)?;
         let source = std::fs::read_to_string(&file_path)?;
@@ -77,14 +93,15 @@ impl Loader {
         name: &String,
         module_path: &ModulePath,
     ) -> Result<Module, ModuleError> {
-        if let Some(source) = self.lib.get_module(name) {
-            self.parse_source(source, module_path, format!("std::{}", name))
-        } else {
-            Err(ModuleError::NotFound(format!(
-                "Standard library module '{}' not found",
-                name
-            )))
-        }
+        let module = self.lib.get_module(name).ok_or_else(|| {
+            ModuleError::NotFound(format!("Standard library module '{}' not found", name))
+        })?;
+        // Modules from a std directory report their real file so diagnostics can show it.
+        let filename = match &module.path {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => format!("std::{}", name),
+        };
+        self.parse_source(&module.source, module_path, filename)
     }
 
     pub fn load_from_file(&self, file_path: PathBuf) -> Result<Module, ModuleError> {
@@ -95,7 +112,7 @@ impl Loader {
             .and_then(|s| s.to_str())
             .ok_or_else(|| ModuleError::NotFound(file_path.display().to_string()))?;
 
-        let module_path = ModulePath::Local(file_stem.to_string());
+        let module_path = ModulePath::Local(vec![file_stem.to_string()]);
         self.parse_source(
             &source,
             &module_path,
@@ -116,7 +133,7 @@ impl Loader {
         })?;
 
         let mut parser = Parser::new(lexer.into_tokens(), filename.clone());
-        let ast = parser.parse().map_err(|e| {
+        let mut ast = parser.parse().map_err(|e| {
             let mut cache = SourceCache::new(PathBuf::from(&filename), source.to_string());
             for err in e.into_errors() {
                 err.eprint(&mut cache);
@@ -126,17 +143,18 @@ impl Loader {
                 error: "Parse error".to_string(),
             }
         })?;
-
-        let imports = ast
-            .iter()
-            .filter_map(|stmt| {
-                if let Stmt::Import(imp) = stmt {
-                    self.resolver.resolve(imp).ok()
-                } else {
-                    None
+        self.cfg.apply(&mut ast);
+
+        // `import m` and `import m::{item}` name the same module; load it once.
+        let mut imports: Vec<ModulePath> = Vec::new();
+        for stmt in ast.iter() {
+            if let Stmt::Import(imp) = stmt {
+                let path = self.resolver.resolve(imp, module_path)?;
+                if !imports.contains(&path) {
+                    imports.push(path);
                 }
-            })
-            .collect();
+            }
+        }
 
         Ok(Module {
             path: module_path.clone(),
@@ -0,0 +1,86 @@
+use crate::module::error::ModuleError;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// `hades.toml` at the root of a package.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub package: PackageInfo,
+    #[serde(default)]
+    pub profile: Profile,
+    /// Other Hades packages by the name they are imported under:
+    /// `geometry = { path = "../geometry" }` makes `import geometry::shapes` available.
+    #[serde(default)]
+    pub dependencies: IndexMap<String, Dependency>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackageInfo {
+    /// The namespace of the package's modules when another package depends on it.
+    pub name: String,
+    /// The file holding `main`, relative to the manifest. `self::` imports resolve from its
+    /// directory.
+    #[serde(default = "default_entry")]
+    pub entry: PathBuf,
+}
+
+/// Build options, applied when the package is the one being built.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Profile {
+    /// LLVM code generation level, 0 to 3. Builds without it use 2.
+    pub opt_level: Option<u8>,
+    /// Enabled for `@cfg(feature = "name")` as if passed with `--feature`.
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Dependency {
+    /// The dependency's package directory, relative to this manifest.
+    pub path: PathBuf,
+}
+
+fn default_entry() -> PathBuf {
+    PathBuf::from("main.hd")
+}
+
+impl Manifest {
+    pub const FILE_NAME: &'static str = "hades.toml";
+
+    /// The manifest in `dir`, if it has one.
+    pub fn find(dir: &Path) -> Result<Option<Self>, ModuleError> {
+        let path = dir.join(Self::FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let invalid = |error: String| ModuleError::Manifest {
+            path: path.display().to_string(),
+            error,
+        };
+
+        let source = std::fs::read_to_string(&path)?;
+        let manifest: Self = toml::from_str(&source).map_err(|e| invalid(e.to_string()))?;
+        if let Some(level) = manifest.profile.opt_level
+            && level > 3
+        {
+            return Err(invalid(format!(
+                "opt-level must be between 0 and 3, found {}",
+                level
+            )));
+        }
+        Ok(Some(manifest))
+    }
+
+    /// The directory `self::` imports of the package resolve from.
+    pub fn module_root(&self, package_dir: &Path) -> PathBuf {
+        package_dir
+            .join(&self.package.entry)
+            .parent()
+            .map_or_else(|| package_dir.to_path_buf(), Path::to_path_buf)
+    }
+}
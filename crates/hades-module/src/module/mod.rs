@@ -1,13 +1,20 @@
+pub mod c_header;
+pub mod cfg;
 pub mod error;
 pub mod loader;
+pub mod manifest;
+pub mod package;
 pub mod path;
 pub mod registry;
 pub mod resolver;
 pub mod typed;
 
+pub use cfg::CfgSet;
 pub use error::ModuleError;
 pub use loader::{Loader, Module};
+pub use manifest::{Manifest, Profile};
+pub use package::Packages;
 pub use path::ModulePath;
-pub use registry::Registry;
+pub use registry::{Project, Registry};
 pub use resolver::Resolver;
 pub use typed::{ModuleSignatures, TypedModule, make_typed_module};
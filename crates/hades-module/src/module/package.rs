@@ -0,0 +1,131 @@
+use crate::module::error::ModuleError;
+use crate::module::manifest::Manifest;
+use indexmap::{IndexMap, IndexSet};
+use std::path::{Path, PathBuf};
+
+/// The package being built and every package it depends on, directly or not. Dependencies are
+/// known by their package names, which namespace their modules, so no two may share one.
+#[derive(Clone, Debug)]
+pub struct Packages {
+    root: Package,
+    dependencies: IndexMap<String, Package>,
+}
+
+#[derive(Clone, Debug)]
+struct Package {
+    /// The directory `self::` imports resolve from.
+    module_root: PathBuf,
+    /// Where the manifest is, to tell two packages of the same name apart.
+    dir: PathBuf,
+    /// The packages it may import.
+    dependencies: IndexSet<String>,
+}
+
+impl Packages {
+    /// A project without a manifest: its modules sit in `project_dir` and it has no
+    /// dependencies.
+    pub fn single(project_dir: impl AsRef<Path>) -> Self {
+        Self {
+            root: Package {
+                module_root: project_dir.as_ref().to_path_buf(),
+                dir: project_dir.as_ref().to_path_buf(),
+                dependencies: IndexSet::new(),
+            },
+            dependencies: IndexMap::new(),
+        }
+    }
+
+    /// The package whose manifest is in `dir`, along with everything it depends on.
+    pub fn load(dir: &Path, manifest: &Manifest) -> Result<Self, ModuleError> {
+        let mut packages = Self::single(manifest.module_root(dir));
+        packages.root.dir = dir.canonicalize()?;
+        packages.root.dependencies = packages.add_dependencies(dir, manifest)?;
+        Ok(packages)
+    }
+
+    fn add_dependencies(
+        &mut self,
+        dir: &Path,
+        manifest: &Manifest,
+    ) -> Result<IndexSet<String>, ModuleError> {
+        let invalid = |error: String| ModuleError::Manifest {
+            path: dir.join(Manifest::FILE_NAME).display().to_string(),
+            error,
+        };
+
+        let mut names = IndexSet::new();
+        for (name, dependency) in &manifest.dependencies {
+            let dep_dir = dir.join(&dependency.path);
+            let dep_manifest = Manifest::find(&dep_dir)?.ok_or_else(|| {
+                invalid(format!(
+                    "dependency '{}' has no {} in {}",
+                    name,
+                    Manifest::FILE_NAME,
+                    dep_dir.display()
+                ))
+            })?;
+            if &dep_manifest.package.name != name {
+                return Err(invalid(format!(
+                    "dependency '{}' is the package '{}'",
+                    name, dep_manifest.package.name
+                )));
+            }
+
+            let canonical = dep_dir.canonicalize()?;
+            names.insert(name.clone());
+            match self.dependencies.get(name) {
+                Some(known) if known.dir == canonical => continue,
+                Some(known) => {
+                    return Err(invalid(format!(
+                        "two packages are named '{}': {} and {}",
+                        name,
+                        known.dir.display(),
+                        canonical.display()
+                    )));
+                }
+                None if canonical == self.root.dir => {
+                    return Err(invalid(format!(
+                        "'{}' depends on the package being built",
+                        name
+                    )));
+                }
+                None => {}
+            }
+
+            self.dependencies.insert(
+                name.clone(),
+                Package {
+                    module_root: dep_manifest.module_root(&dep_dir),
+                    dir: canonical,
+                    dependencies: IndexSet::new(),
+                },
+            );
+            let nested = self.add_dependencies(&dep_dir, &dep_manifest)?;
+            if let Some(package) = self.dependencies.get_mut(name) {
+                package.dependencies = nested;
+            }
+        }
+        Ok(names)
+    }
+
+    /// The directory the `self::` imports of `package` resolve from; `None` is the package
+    /// being built.
+    pub fn module_root(&self, package: Option<&str>) -> Option<&Path> {
+        match package {
+            None => Some(&self.root.module_root),
+            Some(name) => self
+                .dependencies
+                .get(name)
+                .map(|package| package.module_root.as_path()),
+        }
+    }
+
+    /// Whether `package` lists `dependency` in its manifest.
+    pub fn depends_on(&self, package: Option<&str>, dependency: &str) -> bool {
+        let package = match package {
+            None => Some(&self.root),
+            Some(name) => self.dependencies.get(name),
+        };
+        package.is_some_and(|package| package.dependencies.contains(dependency))
+    }
+}
@@ -1,16 +1,47 @@
+use std::borrow::Cow;
 use std::fmt;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum ModulePath {
     Std(String),
-    Local(String),
+    /// `import self::net::http`: the directories below the project and the file stem.
+    Local(Vec<String>),
+    /// A C header brought in with `import c "foo.h"`, named after its file stem.
+    C(String),
+    /// A module of a dependency package: `import geometry::shapes` or, inside the package,
+    /// `import self::shapes`.
+    Package(String, Vec<String>),
 }
 
 impl ModulePath {
-    pub fn name(&self) -> &str {
+    /// The qualifier of the module's item names. Nested local modules join their segments
+    /// with `.`, as in `net.http`, and dependency modules start with their package's name and
+    /// `$`, as in `geometry$shapes`. Neither can appear in an identifier, so no file name or
+    /// package collides with another module.
+    pub fn name(&self) -> Cow<'_, str> {
         match self {
-            ModulePath::Std(name) => name,
-            ModulePath::Local(name) => name,
+            ModulePath::Std(name) => Cow::Borrowed(name),
+            ModulePath::Local(segments) => match segments.as_slice() {
+                [name] => Cow::Borrowed(name),
+                _ => Cow::Owned(segments.join(".")),
+            },
+            ModulePath::Package(package, segments) => {
+                Cow::Owned(format!("{}${}", package, segments.join(".")))
+            }
+            ModulePath::C(header) => Cow::Borrowed(
+                std::path::Path::new(header)
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or(header),
+            ),
+        }
+    }
+
+    /// The dependency package the module belongs to; `None` for the package being built.
+    pub fn package(&self) -> Option<&str> {
+        match self {
+            ModulePath::Package(package, _) => Some(package),
+            _ => None,
         }
     }
 }
@@ -19,7 +50,11 @@ impl fmt::Display for ModulePath {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ModulePath::Std(name) => write!(f, "std::{}", name),
-            ModulePath::Local(name) => write!(f, "self::{}", name),
+            ModulePath::Local(segments) => write!(f, "self::{}", segments.join("::")),
+            ModulePath::C(header) => write!(f, "c \"{}\"", header),
+            ModulePath::Package(package, segments) => {
+                write!(f, "{}::{}", package, segments.join("::"))
+            }
         }
     }
 }
@@ -1,32 +1,69 @@
 use indexmap::IndexMap;
 use petgraph::Graph;
-use petgraph::algo::{is_cyclic_directed, toposort};
+use petgraph::algo::tarjan_scc;
 use petgraph::graph::NodeIndex;
 use std::path::{Path, PathBuf};
 
+use crate::module::cfg::CfgSet;
 use crate::module::error::ModuleError;
 use crate::module::loader::{Loader, Module};
+use crate::module::manifest::{Manifest, Profile};
+use crate::module::package::Packages;
 use crate::module::path::ModulePath;
 use crate::module::resolver::Resolver;
+use hades_lexer::Library;
 
 pub struct Registry {
     modules: IndexMap<ModulePath, Module>,
-    dag: Graph<ModulePath, ()>,
+    graph: Graph<ModulePath, ()>,
     node_map: IndexMap<ModulePath, NodeIndex>,
     loader: Loader,
 }
 
 pub struct EntryPath {
     pub path: PathBuf,
+    /// The package directory: where `hades.toml` is, or the entry's directory without one.
     pub project_dir: PathBuf,
+    pub manifest: Option<Manifest>,
 }
 
 impl EntryPath {
+    /// A directory or a `hades.toml` names a package, whose entry is the one in its manifest
+    /// or `main.hd`. An entry file must be named `main.hd` unless a manifest sits beside it.
     pub fn new_checked(entry_path: PathBuf) -> Result<Self, ModuleError> {
-        let (main_file, project_dir) = if entry_path.is_dir() {
-            let main_file = entry_path.join("main.hd");
-            (main_file, entry_path.clone())
-        } else {
+        let is_manifest = entry_path
+            .file_name()
+            .is_some_and(|name| name == Manifest::FILE_NAME);
+        if entry_path.is_dir() || is_manifest {
+            let project_dir = if is_manifest {
+                entry_path
+                    .parent()
+                    .unwrap_or_else(|| Path::new("."))
+                    .to_path_buf()
+            } else {
+                entry_path.clone()
+            };
+            let manifest = Manifest::find(&project_dir)?;
+            if is_manifest && manifest.is_none() {
+                return Err(ModuleError::NotFound(entry_path.display().to_string()));
+            }
+            let main_file = match &manifest {
+                Some(manifest) => project_dir.join(&manifest.package.entry),
+                None => project_dir.join("main.hd"),
+            };
+            return Ok(Self {
+                path: main_file,
+                project_dir,
+                manifest,
+            });
+        }
+
+        let project_dir = entry_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        let manifest = Manifest::find(&project_dir)?;
+        if manifest.is_none() {
             let filename = entry_path
                 .file_name()
                 .and_then(|n| n.to_str())
@@ -38,37 +75,76 @@ impl EntryPath {
                     filename
                 )));
             }
-            let project_dir = entry_path
-                .parent()
-                .unwrap_or_else(|| Path::new("."))
-                .to_path_buf();
-            (entry_path.to_path_buf(), project_dir)
-        };
+        }
         Ok(Self {
-            path: main_file,
+            path: entry_path,
             project_dir,
+            manifest,
         })
     }
+
+    /// The packages whose modules the entry can import.
+    pub fn packages(&self) -> Result<Packages, ModuleError> {
+        match &self.manifest {
+            Some(manifest) => Packages::load(&self.project_dir, manifest),
+            None => Ok(Packages::single(&self.project_dir)),
+        }
+    }
+}
+
+/// Everything `Registry::load` finds for a build.
+pub struct Project {
+    /// Dependencies come before the modules importing them.
+    pub modules: Vec<Module>,
+    /// The `[profile]` of the manifest, or the defaults without one.
+    pub profile: Profile,
 }
 
 impl Registry {
-    pub fn new(project_dir: impl AsRef<Path>) -> Self {
-        let resolver = Resolver::new(project_dir);
-        let loader = Loader::new(resolver.clone());
+    pub fn new(packages: Packages, lib: Library, cfg: CfgSet) -> Self {
+        let resolver = Resolver::new(packages);
+        let loader = Loader::new(resolver.clone(), lib, cfg);
 
         Self {
             modules: IndexMap::new(),
-            dag: Graph::new(),
+            graph: Graph::new(),
             node_map: IndexMap::new(),
             loader,
         }
     }
 
-    pub fn load(entry_path: impl AsRef<Path>) -> Result<Vec<Module>, ModuleError> {
+    /// Loads the entry and every module it imports. The manifest's features are enabled on top
+    /// of `cfg`, and the modules in `stdlib` override or extend the bundled `std::` modules.
+    pub fn load(
+        entry_path: impl AsRef<Path>,
+        cfg: CfgSet,
+        stdlib: Option<&Path>,
+    ) -> Result<Project, ModuleError> {
         let entry = EntryPath::new_checked(entry_path.as_ref().to_path_buf())?;
-        let mut registry = Self::new(&entry.project_dir);
+        let profile = entry
+            .manifest
+            .as_ref()
+            .map(|manifest| manifest.profile.clone())
+            .unwrap_or_default();
+        let cfg = profile
+            .features
+            .iter()
+            .fold(cfg, |cfg, name| cfg.with_feature(name.clone()));
+
+        let lib = match stdlib {
+            Some(dir) => Library::with_dir(dir).map_err(|e| ModuleError::StdLibrary {
+                path: dir.display().to_string(),
+                error: e.to_string(),
+            })?,
+            None => Library::new(),
+        };
+
+        let mut registry = Self::new(entry.packages()?, lib, cfg);
         registry.load_entry(&entry.path)?;
-        registry.into_sorted_modules()
+        Ok(Project {
+            modules: registry.into_sorted_modules()?,
+            profile,
+        })
     }
 
     fn load_entry(&mut self, entry_file: &Path) -> Result<ModulePath, ModuleError> {
@@ -85,7 +161,7 @@ impl Registry {
             return Ok(());
         }
 
-        let node_idx = self.dag.add_node(module_path.clone());
+        let node_idx = self.graph.add_node(module_path.clone());
         self.node_map.insert(module_path.clone(), node_idx);
 
         let imports = module.imports.clone();
@@ -99,27 +175,23 @@ impl Registry {
             }
 
             let dep_idx = self.node_map[&dep_path];
-            self.dag.add_edge(node_idx, dep_idx, ());
-
-            if is_cyclic_directed(&self.dag) {
-                return Err(ModuleError::CircularDependency);
-            }
+            self.graph.add_edge(node_idx, dep_idx, ());
         }
 
         Ok(())
     }
 
+    /// Dependencies come before the modules importing them. Modules that import each other
+    /// form one strongly connected component and come out in no particular order.
     fn into_sorted_modules(mut self) -> Result<Vec<Module>, ModuleError> {
-        let sorted = toposort(&self.dag, None).map_err(|_| ModuleError::CircularDependency)?;
-
-        sorted
+        tarjan_scc(&self.graph)
             .into_iter()
-            .rev()
+            .flatten()
             .map(|idx| {
-                let path = &self.dag[idx].clone();
+                let path = &self.graph[idx].clone();
                 self.modules
                     .swap_remove(path)
-                    .ok_or(ModuleError::CircularDependency)
+                    .ok_or_else(|| ModuleError::NotFound(path.to_string()))
             })
             .collect()
     }
@@ -1,24 +1,45 @@
 use crate::module::error::ModuleError;
+use crate::module::package::Packages;
 use crate::module::path::ModulePath;
 use hades_ast::{Import, ImportPrefix};
 use std::path::{Path, PathBuf};
 
 #[derive(Clone)]
 pub struct Resolver {
-    project_dir: PathBuf,
+    packages: Packages,
 }
 
 impl Resolver {
-    pub fn new(project_dir: impl AsRef<Path>) -> Self {
-        Self {
-            project_dir: project_dir.as_ref().to_path_buf(),
-        }
+    pub fn new(packages: Packages) -> Self {
+        Self { packages }
     }
 
-    pub fn resolve(&self, import: &Import) -> Result<ModulePath, ModuleError> {
-        match import.prefix {
+    /// The module `import` names when it appears in the module at `from`. `self::` stays
+    /// inside the importing module's package.
+    pub fn resolve(&self, import: &Import, from: &ModulePath) -> Result<ModulePath, ModuleError> {
+        let mut segments = import.parents.clone();
+        segments.push(import.module.clone());
+        match &import.prefix {
+            ImportPrefix::Local => Ok(match from.package() {
+                Some(package) => ModulePath::Package(package.to_string(), segments),
+                None => ModulePath::Local(segments),
+            }),
+            ImportPrefix::Package(package) => {
+                if !self.packages.depends_on(from.package(), package) {
+                    return Err(ModuleError::UnknownPackage {
+                        package: package.clone(),
+                        module: from.to_string(),
+                    });
+                }
+                Ok(ModulePath::Package(package.clone(), segments))
+            }
+            ImportPrefix::Std if !import.parents.is_empty() => Err(ModuleError::NotFound(format!(
+                "std::{}::{}",
+                import.parents.join("::"),
+                import.module
+            ))),
             ImportPrefix::Std => Ok(ModulePath::Std(import.module.clone())),
-            ImportPrefix::Local => Ok(ModulePath::Local(import.module.clone())),
+            ImportPrefix::C => Ok(ModulePath::C(import.module.clone())),
         }
     }
 
@@ -28,10 +49,20 @@ impl Resolver {
                 "Standard library modules are bundled: {}",
                 module
             ))),
-            ModulePath::Local(_) => {
-                let mut path = self.project_dir.clone();
-                path.push(module.name());
-                path.set_extension("hd");
+            ModulePath::Local(segments) | ModulePath::Package(_, segments) => {
+                let root = self
+                    .packages
+                    .module_root(module.package())
+                    .ok_or_else(|| ModuleError::NotFound(module.to_string()))?;
+                Self::find_module(root, segments)
+                    .ok_or_else(|| ModuleError::NotFound(module.to_string()))
+            }
+            ModulePath::C(header) => {
+                let root = self
+                    .packages
+                    .module_root(None)
+                    .ok_or_else(|| ModuleError::NotFound(module.to_string()))?;
+                let path = root.join(header);
 
                 if !path.exists() {
                     return Err(ModuleError::NotFound(module.to_string()));
@@ -41,4 +72,13 @@ impl Resolver {
             }
         }
     }
+
+    /// `net/http.hd`, or `net/http/mod.hd` for a module with submodules of its own.
+    fn find_module(root: &Path, segments: &[String]) -> Option<PathBuf> {
+        let dir: PathBuf = segments.iter().collect();
+        let file = root.join(&dir).with_extension("hd");
+        let nested = root.join(&dir).join("mod.hd");
+
+        [file, nested].into_iter().find(|path| path.exists())
+    }
 }
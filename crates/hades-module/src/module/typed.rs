@@ -1,22 +1,43 @@
 use hades_ast::{CompilerContext, FunctionSignature, ModulePath, Structs, TypedProgram};
+use hades_error::Span;
 use hades_tokens::Name;
 use indexmap::IndexMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ModuleSignatures {
     pub path: ModulePath,
     pub functions: IndexMap<Name, FunctionSignature>,
     pub structs: Structs,
+    /// Items left out because they are not `pub`, with the spans of their declarations.
+    pub private: IndexMap<Name, Span>,
+    /// `pub import`ed items by their names under this module. Their signatures are already in
+    /// `functions` and `structs`, which keep everything the module imported.
+    pub reexports: IndexMap<Name, Name>,
 }
 
 impl ModuleSignatures {
+    /// Only `pub` functions, methods and structs are visible to importers. Public structs keep
+    /// their private fields, which importers need for the layout.
     pub fn from_context(ctx: CompilerContext, path: ModulePath) -> Self {
-        let structs = ctx.structs().clone();
-        let functions = ctx.into_functions().into_user_defined();
+        let private = ctx.private_items().clone();
+        let reexports = ctx
+            .own_reexports()
+            .map(|(key, item)| (key.clone(), item.clone()))
+            .collect();
+        let mut structs = ctx.structs().clone();
+        structs.retain(|name| !private.contains_key(name));
+        let functions = ctx
+            .into_functions()
+            .into_user_defined()
+            .into_iter()
+            .filter(|(name, _)| !private.contains_key(name))
+            .collect();
         Self {
             path,
             functions,
             structs,
+            private,
+            reexports,
         }
     }
 }
@@ -0,0 +1,200 @@
+use crate::parser::Parse;
+use crate::parser::ParserCtx;
+use crate::parser::error::{ParseError, ParseResult};
+use crate::parser::func::{FuncDef, parse_extern_fn};
+use crate::parser::stmt::{parse_export, parse_pub};
+use crate::parser::struct_::{StructDef, is_extern_type};
+use hades_ast::*;
+use hades_common::token_matches;
+use hades_error::Span;
+use hades_tokens::TokenKind;
+
+/// Attributes as written, each with the span reported when it is used in the wrong place.
+pub(super) struct ParsedAttributes(Vec<(Attribute, Span)>);
+
+impl ParsedAttributes {
+    pub fn check(self, ctx: &ParserCtx, target: AttributeTarget) -> ParseResult<Attributes> {
+        let inline = self.0.iter().any(|(attr, _)| *attr == Attribute::Inline);
+        let packed = self.0.iter().any(|(attr, _)| *attr == Attribute::Packed);
+        for (attr, span) in &self.0 {
+            if inline && *attr == Attribute::NoInline {
+                return Err(ParseError::misplaced_attribute(
+                    attr.name(),
+                    "a function marked @inline",
+                    span.into_range(),
+                    ctx.source_id.clone(),
+                ));
+            }
+            if packed && *attr == Attribute::ReprC {
+                return Err(ParseError::misplaced_attribute(
+                    attr.name(),
+                    "a struct marked @packed",
+                    span.into_range(),
+                    ctx.source_id.clone(),
+                ));
+            }
+            if !attr.applies_to(target) {
+                return Err(ParseError::misplaced_attribute(
+                    attr.name(),
+                    &target.to_string(),
+                    span.into_range(),
+                    ctx.source_id.clone(),
+                ));
+            }
+        }
+        Ok(Attributes::new(
+            self.0.into_iter().map(|(attr, _)| attr).collect(),
+        ))
+    }
+}
+
+/// Zero or more `@name` / `@name(arg)` attributes in front of a declaration.
+pub(super) fn parse_attributes(ctx: &mut ParserCtx) -> ParseResult<ParsedAttributes> {
+    let mut attrs = Vec::new();
+    while ctx
+        .peek()
+        .is_some_and(|tok| token_matches!(tok, TokenKind::At))
+    {
+        let start = ctx.current_span();
+        ctx.next();
+        let name = ctx.expect_identifier()?;
+        let attr = match name.inner() {
+            "inline" => Attribute::Inline,
+            "noinline" => Attribute::NoInline,
+            "cold" => Attribute::Cold,
+            "packed" => Attribute::Packed,
+            "repr" => {
+                ctx.expect(&TokenKind::LeftParen)?;
+                if !matches!(ctx.peek().map(|t| t.kind()), Some(TokenKind::Ident(repr)) if repr.inner() == "C")
+                {
+                    let span = ctx.current_span().into_range();
+                    return Err(ParseError::unexpected_token(
+                        ctx.peek().cloned(),
+                        "C",
+                        span,
+                        ctx.source_id.clone(),
+                    ));
+                }
+                ctx.next();
+                ctx.expect(&TokenKind::RightParen)?;
+                Attribute::ReprC
+            }
+            "deprecated" => {
+                let note = if ctx.consume_if(&TokenKind::LeftParen) {
+                    let note = ctx.expect_string_literal()?;
+                    ctx.expect(&TokenKind::RightParen)?;
+                    Some(note)
+                } else {
+                    None
+                };
+                Attribute::Deprecated(note)
+            }
+            "cfg" => {
+                ctx.expect(&TokenKind::LeftParen)?;
+                let key = ctx.expect_identifier()?;
+                let value = if ctx.consume_if(&TokenKind::Assign) {
+                    Some(ctx.expect_string_literal()?)
+                } else {
+                    None
+                };
+                ctx.expect(&TokenKind::RightParen)?;
+                Attribute::Cfg(Cfg::new(key.inner(), value))
+            }
+            "link" => {
+                ctx.expect(&TokenKind::LeftParen)?;
+                let library = ctx.expect_string_literal()?;
+                ctx.expect(&TokenKind::RightParen)?;
+                Attribute::Link(library)
+            }
+            other => {
+                return Err(ParseError::unknown_attribute(
+                    other,
+                    name.span().into_range(),
+                    ctx.source_id.clone(),
+                ));
+            }
+        };
+        attrs.push((attr, start.to(ctx.prev_span())));
+    }
+    Ok(ParsedAttributes(attrs))
+}
+
+impl ParsedAttributes {
+    fn only_cfg(&self) -> bool {
+        self.0
+            .iter()
+            .all(|(attr, _)| matches!(attr, Attribute::Cfg(_)))
+    }
+
+    fn into_cfgs(self) -> Vec<Cfg> {
+        self.0
+            .into_iter()
+            .filter_map(|(attr, _)| match attr {
+                Attribute::Cfg(cfg) => Some(cfg),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// A function, extern function or struct preceded by its attributes, or any other statement
+/// preceded only by `@cfg(...)`.
+pub(super) fn parse_attributed_item(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
+    let start_tok = ctx.current_span();
+    let attrs = parse_attributes(ctx)?;
+    let is_item = ctx.peek().is_some_and(|tok| {
+        token_matches!(
+            tok,
+            TokenKind::Struct | TokenKind::Fn | TokenKind::Export | TokenKind::Pub
+        ) || (token_matches!(tok, TokenKind::Extern) && !is_extern_type(ctx))
+    });
+    if !is_item && attrs.only_cfg() {
+        let stmt = Stmt::parse(ctx)?;
+        let span = start_tok.to(stmt.span().clone());
+        return Ok(Stmt::Cfg(CfgStmt {
+            cfgs: attrs.into_cfgs(),
+            stmt: Box::new(stmt),
+            span,
+        }));
+    }
+
+    let mut stmt = match ctx.peek() {
+        Some(tok) if token_matches!(tok, TokenKind::Struct) => StructDef::parse(ctx)?,
+        Some(tok) if token_matches!(tok, TokenKind::Fn) => FuncDef::parse(ctx)?,
+        Some(tok) if token_matches!(tok, TokenKind::Extern) && !is_extern_type(ctx) => {
+            parse_extern_fn(ctx)?
+        }
+        Some(tok) if token_matches!(tok, TokenKind::Export) => parse_export(ctx)?,
+        Some(tok) if token_matches!(tok, TokenKind::Pub) => parse_pub(ctx)?,
+        _ => {
+            let span = ctx
+                .peek()
+                .map_or_else(|| ctx.eof_span(), |tok| tok.span().clone())
+                .into_range();
+            return Err(ParseError::unexpected_token(
+                ctx.peek().cloned(),
+                "function or struct after attributes",
+                span,
+                ctx.source_id.clone(),
+            ));
+        }
+    };
+
+    match &mut stmt {
+        Stmt::FuncDef(func) => {
+            if let FuncBody::Extern { link, .. } = &mut func.body {
+                func.attributes = attrs.check(ctx, AttributeTarget::ExternFunction)?;
+                *link = func.attributes.link().map(str::to_string);
+            } else {
+                func.attributes = attrs.check(ctx, AttributeTarget::Function)?;
+            }
+            func.span = start_tok.to(func.span.clone());
+        }
+        Stmt::StructDef(def) => {
+            def.attributes = attrs.check(ctx, AttributeTarget::Struct)?;
+            def.span = start_tok.to(def.span.clone());
+        }
+        _ => {}
+    }
+    Ok(stmt)
+}
@@ -1,7 +1,7 @@
 use crate::parser::Parse;
 use crate::parser::{ParserCtx, error::ParseResult, stmt};
 use hades_ast::DeferStmt;
-use hades_ast::{Block, Stmt};
+use hades_ast::Stmt;
 use hades_tokens::TokenKind::Defer;
 
 impl Parse for DeferStmt {
@@ -13,7 +13,7 @@ impl Parse for DeferStmt {
         let block = stmt::parse_block(ctx)?;
         let end = ctx.prev_span();
         Ok(Stmt::Defer(DeferStmt {
-            stmt: Block::new(block.into(), end.clone()),
+            stmt: block.into_block(end.clone()),
             span: start_tok.to(end),
         }))
     }
@@ -55,6 +55,112 @@ impl ParseError {
         ))
     }
 
+    pub fn duplicate_argument(name: &str, span: Range<usize>, source_id: String) -> Self {
+        Self(Box::new(Error::new_with_span(
+            format!("Argument {name} is given more than once"),
+            Span::new(PathBuf::from(source_id), span.start, span.end),
+        )))
+    }
+
+    pub fn positional_after_named(span: Range<usize>, source_id: String) -> Self {
+        Self(Box::new(
+            Error::new_with_span(
+                "Positional argument after a named one".to_string(),
+                Span::new(PathBuf::from(source_id), span.start, span.end),
+            )
+            .with_help("Pass positional arguments before any `name: value` ones".to_string()),
+        ))
+    }
+
+    pub fn parameter_after_variadic(span: Range<usize>, source_id: String) -> Self {
+        Self(Box::new(
+            Error::new_with_span(
+                "Parameter after a variadic one".to_string(),
+                Span::new(PathBuf::from(source_id), span.start, span.end),
+            )
+            .with_help("A `name: ...T` parameter has to come last".to_string()),
+        ))
+    }
+
+    pub fn variadic_intrinsic(span: Range<usize>, source_id: String) -> Self {
+        Self(Box::new(
+            Error::new_with_span(
+                "An intrinsic can't take a variadic parameter".to_string(),
+                Span::new(PathBuf::from(source_id), span.start, span.end),
+            )
+            .with_help("LLVM intrinsics take a fixed list of parameters".to_string()),
+        ))
+    }
+
+    pub fn unknown_attribute(name: &str, span: Range<usize>, source_id: String) -> Self {
+        Self(Box::new(
+            Error::new_with_span(
+                format!("Unknown attribute @{name}"),
+                Span::new(PathBuf::from(source_id), span.start, span.end),
+            )
+            .with_help(
+                "Known attributes are @inline, @noinline, @cold, @repr(C), @packed, @deprecated, @link and @cfg"
+                    .to_string(),
+            ),
+        ))
+    }
+
+    pub fn misplaced_attribute(
+        name: &str,
+        target: &str,
+        span: Range<usize>,
+        source_id: String,
+    ) -> Self {
+        Self(Box::new(Error::new_with_span(
+            format!("Attribute @{name} cannot be applied to {target}"),
+            Span::new(PathBuf::from(source_id), span.start, span.end),
+        )))
+    }
+
+    pub fn invalid_macro(message: String, span: Span) -> Self {
+        Self(Box::new(Error::new_with_span(message, span)))
+    }
+
+    pub fn unknown_macro(name: &str, span: Span) -> Self {
+        Self(Box::new(
+            Error::new_with_span(format!("Unknown macro {name}!"), span).with_help(format!(
+                "Define it with `macro {name} {{ ... }}` in this file; macros can't be imported"
+            )),
+        ))
+    }
+
+    /// `span` is the call, marked as expanded from the macro's name.
+    pub fn no_matching_macro_rule(name: &str, span: Span) -> Self {
+        Self(Box::new(
+            Error::new_with_span(format!("No rule of macro {name}! matches this call"), span)
+                .with_help("Check the arguments against the macro's patterns".to_string()),
+        ))
+    }
+
+    pub fn macro_recursion_limit(name: &str, limit: usize, span: Span) -> Self {
+        Self(Box::new(
+            Error::new_with_span(
+                format!("Expanding macro {name}! recursed more than {limit} times"),
+                span,
+            )
+            .with_note(
+                "A macro that always expands to a call of itself never terminates".to_string(),
+            ),
+        ))
+    }
+
+    /// Labels the macro definition when the error is at `token` and `token` came from a macro.
+    pub(crate) fn at_token(mut self, token: Option<&hades_tokens::Token>) -> Self {
+        if let Some(token) = token
+            && let Some(definition) = token.span().expansion()
+            && self.0.span.expansion().is_none()
+            && self.0.span.into_range() == token.span().into_range()
+        {
+            self.0.span = self.0.span.with_expansion(definition.clone());
+        }
+        self
+    }
+
     pub fn missing_semicolon(span: Range<usize>, source_id: String) -> Self {
         Self(Box::new(
             Error::new_with_span(
@@ -0,0 +1,69 @@
+use crate::parser::Parse;
+use crate::parser::ParserCtx;
+use crate::parser::error::{ParseError, ParseResult};
+use crate::parser::expr::{assign_target, parse_assignment, parse_binary};
+use hades_ast::*;
+use hades_tokens::TokenKind;
+
+impl Parse for AsmExpr {
+    type Output = Expr;
+
+    /// Parses the rest of `asm("template", [inputs], [outputs], [clobbers])` after the
+    /// `asm` keyword. Trailing operand lists may be left out.
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Expr> {
+        ctx.expect(&TokenKind::LeftParen)?;
+        let template = ctx.expect_string_literal()?;
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut clobbers = Vec::new();
+        if ctx.consume_if(&TokenKind::Comma) {
+            inputs = parse_operands(ctx, parse_assignment)?;
+        }
+        if ctx.consume_if(&TokenKind::Comma) {
+            outputs = parse_operands(ctx, |ctx| {
+                let expr = parse_binary(ctx, 0)?;
+                assign_target(expr).ok_or_else(|| {
+                    ParseError::invalid_assignment_target(
+                        ctx.prev_span().into_range(),
+                        ctx.source_id.clone(),
+                    )
+                })
+            })?;
+        }
+        if ctx.consume_if(&TokenKind::Comma) {
+            ctx.expect(&TokenKind::LeftBracket)?;
+            clobbers = ctx.parse_comma_separated(
+                ParserCtx::expect_string_literal,
+                &TokenKind::RightBracket,
+            )?;
+            ctx.expect(&TokenKind::RightBracket)?;
+        }
+        ctx.expect(&TokenKind::RightParen)?;
+
+        Ok(Expr::Asm(AsmExpr {
+            template,
+            inputs,
+            outputs,
+            clobbers,
+        }))
+    }
+}
+
+/// `["constraint": operand, ...]`
+fn parse_operands<T>(
+    ctx: &mut ParserCtx,
+    mut parse_operand: impl FnMut(&mut ParserCtx) -> ParseResult<T>,
+) -> ParseResult<Vec<(String, T)>> {
+    ctx.expect(&TokenKind::LeftBracket)?;
+    let operands = ctx.parse_comma_separated(
+        |ctx| {
+            let constraint = ctx.expect_string_literal()?;
+            ctx.expect(&TokenKind::Colon)?;
+            Ok((constraint, parse_operand(ctx)?))
+        },
+        &TokenKind::RightBracket,
+    )?;
+    ctx.expect(&TokenKind::RightBracket)?;
+    Ok(operands)
+}
@@ -1,13 +1,16 @@
 mod array;
+mod asm;
 
 use crate::parser::Parse;
 use crate::parser::ParserCtx;
 use crate::parser::error::ParseResult;
+use crate::parser::stmt::{parse_block, parse_if};
 use crate::parser::struct_::parse_struct_literal;
 use array::ArrayLiteral;
 use hades_ast::*;
 use hades_common::token_matches;
-use hades_tokens::{Assoc, Name, Op, TokenKind};
+use hades_tokens::{Assoc, Ident, Name, Op, TokenKind};
+use indexmap::IndexMap;
 
 impl Parse for Expr {
     type Output = Expr;
@@ -21,59 +24,39 @@ pub(crate) fn parse_assignment(ctx: &mut ParserCtx) -> ParseResult<Expr> {
     let expr = parse_binary(ctx, 0)?;
 
     if peek_assignment_op(ctx).is_some() {
-        match expr {
-            Expr::Ident(name) => {
-                let op = Op::from_token(&ctx.next().unwrap()).unwrap();
-                let value = parse_assignment(ctx)?;
-                return Ok(Expr::Assign(AssignExpr {
-                    target: AssignTarget::Ident(name),
-                    op,
-                    value: Box::new(value),
-                }));
-            }
-            Expr::FieldAccess(field) => {
-                let op = Op::from_token(&ctx.next().unwrap()).unwrap();
-                let value = parse_assignment(ctx)?;
-                return Ok(Expr::Assign(AssignExpr {
-                    target: AssignTarget::FieldAccess(field),
-                    op,
-                    value: Box::new(value),
-                }));
-            }
-            Expr::ArrayIndex(index) => {
-                let op = Op::from_token(&ctx.next().unwrap()).unwrap();
-                let value = parse_assignment(ctx)?;
-                return Ok(Expr::Assign(AssignExpr {
-                    target: AssignTarget::ArrayIndex(index),
-                    op,
-                    value: Box::new(value),
-                }));
-            }
-            Expr::Unary {
-                op: Op::Deref,
-                expr: inner,
-            } => {
-                let op = Op::from_token(&ctx.next().unwrap()).unwrap();
-                let value = parse_assignment(ctx)?;
-                return Ok(Expr::Assign(AssignExpr {
-                    target: AssignTarget::Deref(inner),
-                    op,
-                    value: Box::new(value),
-                }));
-            }
-            _ => {
-                let span = ctx.current_span().into_range();
-                let source_id = ctx.source_id.clone();
-                return Err(crate::parser::error::ParseError::invalid_assignment_target(
-                    span, source_id,
-                ));
-            }
-        }
+        let Some(target) = assign_target(expr) else {
+            let span = ctx.current_span().into_range();
+            let source_id = ctx.source_id.clone();
+            return Err(crate::parser::error::ParseError::invalid_assignment_target(
+                span, source_id,
+            ));
+        };
+        let op = Op::from_token(&ctx.next().unwrap()).unwrap();
+        let value = parse_assignment(ctx)?;
+        return Ok(Expr::Assign(AssignExpr {
+            target,
+            op,
+            value: Box::new(value),
+        }));
     }
 
     Ok(expr)
 }
 
+/// The place an expression names, if it can be written to.
+pub(super) fn assign_target(expr: Expr) -> Option<AssignTarget> {
+    match expr {
+        Expr::Ident(name) => Some(AssignTarget::Ident(name)),
+        Expr::FieldAccess(field) => Some(AssignTarget::FieldAccess(field)),
+        Expr::ArrayIndex(index) => Some(AssignTarget::ArrayIndex(index)),
+        Expr::Unary {
+            op: Op::Deref,
+            expr: inner,
+        } => Some(AssignTarget::Deref(inner)),
+        _ => None,
+    }
+}
+
 pub(crate) fn parse_binary_with_flags(
     ctx: &mut ParserCtx,
     min_prec: u8,
@@ -173,6 +156,18 @@ fn parse_unary_with_flags(ctx: &mut ParserCtx, allow_struct_literals: bool) -> P
 fn parse_primary_with_flags(ctx: &mut ParserCtx, allow_struct_literals: bool) -> ParseResult<Expr> {
     let source_id = ctx.source_id.clone();
 
+    match ctx.peek().map(|tok| tok.kind()) {
+        Some(TokenKind::If) => return Ok(Expr::If(Box::new(parse_if(ctx)?))),
+        Some(TokenKind::Loop) => return Loop::parse(ctx),
+        Some(TokenKind::LeftBrace) => {
+            let start_tok = ctx.current_span();
+            let body = parse_block(ctx)?;
+            let span = start_tok.to(ctx.prev_span());
+            return Ok(Expr::Block(Box::new(body.into_block(span))));
+        }
+        _ => {}
+    }
+
     let token = ctx.next();
     match token {
         Some(tok) => match tok.kind() {
@@ -183,6 +178,17 @@ fn parse_primary_with_flags(ctx: &mut ParserCtx, allow_struct_literals: bool) ->
             TokenKind::True => Ok(Expr::Value(Value::Boolean(true))),
             TokenKind::False => Ok(Expr::Value(Value::Boolean(false))),
             TokenKind::Null => Ok(Expr::Null),
+            TokenKind::Ident(name)
+                if ctx
+                    .peek()
+                    .is_some_and(|tok| token_matches!(tok, TokenKind::Bang)) =>
+            {
+                // Every macro of the file has been expanded, so this one is undefined.
+                Err(crate::parser::error::ParseError::unknown_macro(
+                    name.inner(),
+                    tok.span().clone(),
+                ))
+            }
             TokenKind::Ident(name) => {
                 parse_postfix_chain(ctx, Expr::Ident(name.clone()), allow_struct_literals)
             }
@@ -196,6 +202,7 @@ fn parse_primary_with_flags(ctx: &mut ParserCtx, allow_struct_literals: bool) ->
                 parse_postfix_chain(ctx, expr, allow_struct_literals)
             }
             TokenKind::LeftBracket => ArrayLiteral::parse(ctx),
+            TokenKind::Asm => AsmExpr::parse(ctx),
             TokenKind::Self_ => {
                 let self_ident = hades_tokens::Ident::new("self".to_string(), tok.span().clone());
                 parse_postfix_chain(ctx, Expr::Ident(self_ident), allow_struct_literals)
@@ -237,13 +244,13 @@ fn parse_postfix_chain(
                     .is_some_and(|tok| token_matches!(tok, TokenKind::LeftParen))
                 {
                     ctx.next();
-                    let args =
-                        ctx.parse_comma_separated(parse_assignment, &TokenKind::RightParen)?;
+                    let (args, named_args) = parse_call_args(ctx)?;
                     ctx.expect(&TokenKind::RightParen)?;
                     expr = Expr::Call(CallKind::Method(MethodCall {
                         receiver: Box::new(expr),
                         func: Name::new(field_name.inner().to_string(), field_name.span().clone()),
                         args,
+                        named_args,
                     }));
                 } else {
                     expr = Expr::FieldAccess(FieldAccessExpr {
@@ -281,13 +288,13 @@ fn parse_postfix_chain(
                         .is_some_and(|t| token_matches!(t, TokenKind::LeftParen))
                     {
                         ctx.next(); // consume (
-                        let args =
-                            ctx.parse_comma_separated(parse_assignment, &TokenKind::RightParen)?;
+                        let (args, named_args) = parse_call_args(ctx)?;
                         ctx.expect(&TokenKind::RightParen)?;
                         expr = Expr::Call(CallKind::Qualified(QualifiedCall {
                             path,
                             func: Name::new(segment.inner().to_string(), segment.span().clone()),
                             args,
+                            named_args,
                         }));
                         break;
                     } else if allow_struct_literals
@@ -317,12 +324,16 @@ fn parse_postfix_chain(
             Some(tok) if token_matches!(tok, TokenKind::LeftParen) => {
                 if let Expr::Ident(func_name) = expr {
                     ctx.next();
-                    let args =
-                        ctx.parse_comma_separated(parse_assignment, &TokenKind::RightParen)?;
+                    let (args, named_args) = if takes_type_arg(func_name.inner()) {
+                        (parse_type_builtin_args(ctx)?, IndexMap::new())
+                    } else {
+                        parse_call_args(ctx)?
+                    };
                     ctx.expect(&TokenKind::RightParen)?;
                     expr = Expr::Call(CallKind::Function(FunctionCall {
                         func: Name::new(func_name.inner().to_string(), func_name.span().clone()),
                         args,
+                        named_args,
                     }));
                 } else {
                     break;
@@ -341,6 +352,58 @@ fn parse_postfix_chain(
     Ok(expr)
 }
 
+/// Positional arguments, then `name: value` ones, up to the closing parenthesis.
+fn parse_call_args(ctx: &mut ParserCtx) -> ParseResult<(Vec<Expr>, IndexMap<Ident, Expr>)> {
+    let mut args = Vec::new();
+    let mut named_args = IndexMap::new();
+
+    while !ctx
+        .peek()
+        .is_some_and(|tok| token_matches!(tok, TokenKind::RightParen))
+    {
+        let named = ctx
+            .peek()
+            .is_some_and(|tok| matches!(tok.kind(), TokenKind::Ident(_)))
+            && ctx
+                .peek_nth(1)
+                .is_some_and(|tok| token_matches!(tok, TokenKind::Colon));
+        if named {
+            let name = ctx.expect_identifier()?;
+            ctx.expect(&TokenKind::Colon)?;
+            if named_args.contains_key(&name) {
+                return Err(crate::parser::error::ParseError::duplicate_argument(
+                    name.inner(),
+                    name.span().into_range(),
+                    ctx.source_id.clone(),
+                ));
+            }
+            named_args.insert(name, parse_assignment(ctx)?);
+        } else if !named_args.is_empty() {
+            return Err(crate::parser::error::ParseError::positional_after_named(
+                ctx.current_span().into_range(),
+                ctx.source_id.clone(),
+            ));
+        } else {
+            args.push(parse_assignment(ctx)?);
+        }
+
+        if !ctx.consume_if(&TokenKind::Comma) {
+            break;
+        }
+    }
+
+    Ok((args, named_args))
+}
+
+/// The first argument of a type builtin is a type; any further ones are expressions.
+fn parse_type_builtin_args(ctx: &mut ParserCtx) -> ParseResult<Vec<Expr>> {
+    let mut args = vec![Expr::Type(ctx.expect_type()?)];
+    while ctx.consume_if(&TokenKind::Comma) {
+        args.push(parse_assignment(ctx)?);
+    }
+    Ok(args)
+}
+
 fn peek_assignment_op(ctx: &ParserCtx) -> Option<&hades_tokens::Token> {
     match ctx.peek() {
         Some(token)
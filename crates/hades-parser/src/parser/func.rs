@@ -1,9 +1,11 @@
 use crate::parser::Parse;
 use crate::parser::ParserCtx;
 use crate::parser::error::ParseResult;
+use crate::parser::expr::parse_assignment;
 use crate::parser::stmt::parse_block;
 use hades_ast::*;
-use hades_tokens::{Name, ParamKind, Selff, TokenKind};
+use hades_tokens::{Ident, Name, ParamKind, Selff, TokenKind};
+use indexmap::IndexMap;
 
 pub(super) struct FuncDef;
 
@@ -15,7 +17,11 @@ impl Parse for FuncDef {
         ctx.expect(&TokenKind::Fn)?;
         let name_ident = ctx.expect_identifier()?;
         let name = Name::new(name_ident.inner().to_string(), name_ident.span().clone());
-        let params = parse_parameter_list(ctx)?;
+        let ParamList {
+            params,
+            defaults,
+            variadic,
+        } = parse_parameter_list(ctx)?;
         let return_type = parse_optional_return_type(ctx)?;
         let body = parse_block(ctx)?;
         let end = ctx.prev_span();
@@ -25,8 +31,13 @@ impl Parse for FuncDef {
             name,
             receiver: None,
             params,
+            defaults,
+            variadic,
             return_type,
-            body: FuncBody::Block(Block::new(body.into(), span.clone())),
+            body: FuncBody::Block(body.into_block(span.clone())),
+            export: false,
+            public: false,
+            attributes: Attributes::default(),
             span,
         }))
     }
@@ -48,8 +59,17 @@ pub(super) fn parse_extern_fn(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
         name,
         receiver: None,
         params,
+        defaults: IndexMap::new(),
+        variadic: None,
         return_type,
-        body: FuncBody::Extern { variadic },
+        body: FuncBody::Extern {
+            variadic,
+            link: None,
+            widths: CWidths::default(),
+        },
+        export: false,
+        public: false,
+        attributes: Attributes::default(),
         span,
     }))
 }
@@ -60,7 +80,17 @@ pub(super) fn parse_intrinsic_fn(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
     ctx.expect(&TokenKind::Fn)?;
     let name_ident = ctx.expect_identifier()?;
     let name = Name::new(name_ident.inner().to_string(), name_ident.span().clone());
-    let params = parse_parameter_list(ctx)?;
+    let ParamList {
+        params,
+        defaults,
+        variadic,
+    } = parse_parameter_list(ctx)?;
+    if let Some((name, _)) = variadic {
+        return Err(crate::parser::error::ParseError::variadic_intrinsic(
+            name.span().into_range(),
+            ctx.source_id.clone(),
+        ));
+    }
     let return_type = parse_optional_return_type(ctx)?;
     ctx.expect(&TokenKind::Assign)?;
     let llvm_name = ctx.expect_string_literal()?;
@@ -72,16 +102,31 @@ pub(super) fn parse_intrinsic_fn(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
         name,
         receiver: None,
         params,
+        defaults,
+        variadic: None,
         return_type,
         body: FuncBody::Intrinsic(llvm_name),
+        export: false,
+        public: false,
+        attributes: Attributes::default(),
         span,
     }))
 }
 
-pub(super) fn parse_parameter_list(ctx: &mut ParserCtx) -> ParseResult<Vec<(ParamKind, Types)>> {
+/// The parameters of a function, along with the default values of those that have one.
+pub(super) struct ParamList {
+    pub params: Vec<(ParamKind, Types)>,
+    pub defaults: IndexMap<Ident, Expr>,
+    /// The trailing `name: ...T` parameter, kept apart from `params`.
+    pub variadic: Option<(Ident, Types)>,
+}
+
+pub(super) fn parse_parameter_list(ctx: &mut ParserCtx) -> ParseResult<ParamList> {
     ctx.expect(&TokenKind::LeftParen)?;
 
     let mut params = Vec::new();
+    let mut defaults = IndexMap::new();
+    let mut variadic = None;
 
     if ctx.peek().is_some_and(|t| t.kind().eq(&TokenKind::Self_)) {
         ctx.expect_keyword(&TokenKind::Self_)?;
@@ -92,8 +137,14 @@ pub(super) fn parse_parameter_list(ctx: &mut ParserCtx) -> ParseResult<Vec<(Para
         ctx.consume_if(&TokenKind::Comma);
     }
 
-    let mut rest = ctx.parse_comma_separated(
+    let rest = ctx.parse_comma_separated(
         |c| {
+            if variadic.is_some() {
+                return Err(crate::parser::error::ParseError::parameter_after_variadic(
+                    c.current_span().into_range(),
+                    c.source_id.clone(),
+                ));
+            }
             if c.peek().is_some_and(|t| t.kind().eq(&TokenKind::Self_)) {
                 let span = c.current_span().into_range();
                 let source_id = c.source_id.clone();
@@ -106,15 +157,26 @@ pub(super) fn parse_parameter_list(ctx: &mut ParserCtx) -> ParseResult<Vec<(Para
             }
             let name = c.expect_identifier()?;
             c.expect(&TokenKind::Colon)?;
+            if c.consume_if(&TokenKind::Ellipsis) {
+                variadic = Some((name, c.expect_type()?));
+                return Ok(None);
+            }
             let param_type = c.expect_type()?;
-            Ok((ParamKind::Ident(name), param_type))
+            if c.consume_if(&TokenKind::Assign) {
+                defaults.insert(name.clone(), parse_assignment(c)?);
+            }
+            Ok(Some((ParamKind::Ident(name), param_type)))
         },
         &TokenKind::RightParen,
     )?;
-    params.append(&mut rest);
+    params.extend(rest.into_iter().flatten());
 
     ctx.expect(&TokenKind::RightParen)?;
-    Ok(params)
+    Ok(ParamList {
+        params,
+        defaults,
+        variadic,
+    })
 }
 
 pub(super) fn parse_parameter_list_with_variadic(
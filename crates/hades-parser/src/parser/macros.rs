@@ -0,0 +1,661 @@
+use crate::parser::Parse;
+use crate::parser::ParserCtx;
+use crate::parser::error::{ParseError, ParseResult};
+use hades_ast::Expr;
+use hades_common::token_matches;
+use hades_error::Span;
+use hades_tokens::{Ident, Token, TokenKind};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+
+/// Expansions nested deeper than this are reported as unbounded recursion.
+const RECURSION_LIMIT: usize = 64;
+
+/// `macro name { (pattern) => { body } ... }`. A `name!(...)` call is replaced by the body of
+/// the first rule whose pattern matches the call's tokens.
+struct MacroDef {
+    name: Ident,
+    rules: Vec<MacroRule>,
+}
+
+struct MacroRule {
+    pattern: Vec<Matcher>,
+    body: Vec<Transcriber>,
+    /// Names bound by `let` or `for` in the body itself. They are renamed in every expansion
+    /// so they can neither shadow nor be captured by names at the call site.
+    hygienic: HashSet<String>,
+}
+
+/// The kind of tokens a `$name:kind` pattern variable matches.
+#[derive(Clone, Copy, PartialEq)]
+enum Fragment {
+    Expr,
+    Ident,
+    Ty,
+    Literal,
+    Block,
+    Tt,
+}
+
+impl Fragment {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "expr" => Some(Fragment::Expr),
+            "ident" => Some(Fragment::Ident),
+            "ty" => Some(Fragment::Ty),
+            "literal" => Some(Fragment::Literal),
+            "block" => Some(Fragment::Block),
+            "tt" => Some(Fragment::Tt),
+            _ => None,
+        }
+    }
+}
+
+enum Matcher {
+    Token(TokenKind),
+    Fragment(String, Fragment),
+    /// `$( ... ) sep *` or `$( ... ) sep +`.
+    Repeat {
+        inner: Vec<Matcher>,
+        separator: Option<TokenKind>,
+        at_least_one: bool,
+    },
+}
+
+enum Transcriber {
+    Token(Token),
+    Var(Ident),
+    Repeat {
+        inner: Vec<Transcriber>,
+        separator: Option<Token>,
+    },
+}
+
+/// What a pattern variable matched: its tokens, or one capture per repetition.
+#[derive(Clone)]
+enum Capture {
+    Tokens(Vec<Token>, Fragment),
+    Repeated(Vec<Capture>),
+}
+
+type Captures = HashMap<String, Capture>;
+
+impl ParserCtx {
+    /// Collects the `macro` definitions of the file and replaces every call with its
+    /// expansion, before any statement is parsed.
+    ///
+    /// Macros are local to the file that defines them: they are expanded before imports are
+    /// resolved, so there is no way to export one or to call another module's.
+    pub(super) fn expand_macros(&mut self) -> ParseResult<()> {
+        if !self
+            .tokens
+            .iter()
+            .any(|tok| token_matches!(tok, TokenKind::Macro))
+        {
+            return Ok(());
+        }
+
+        let tokens = std::mem::take(&mut self.tokens);
+        let mut ctx = self.sub(tokens);
+        let mut macros = HashMap::new();
+        let mut rest = Vec::new();
+        while let Some(tok) = ctx.peek() {
+            if token_matches!(tok, TokenKind::Macro) {
+                let def = parse_macro_def(&mut ctx)?;
+                macros.insert(def.name.inner().to_string(), def);
+            } else {
+                rest.extend(ctx.next());
+            }
+        }
+
+        let expander = Expander {
+            macros,
+            source_id: self.source_id.clone(),
+            expansions: Cell::new(0),
+        };
+        expander.expand(rest, 0, &mut self.tokens)
+    }
+
+    fn sub(&self, tokens: Vec<Token>) -> ParserCtx {
+        ParserCtx::new(tokens, self.source_id.clone())
+    }
+
+    /// The tokens between a `(`, `[` or `{` and its matching closer, consuming both.
+    fn delimited(&mut self) -> ParseResult<Vec<Token>> {
+        let open = self.next();
+        let Some(close) = open.as_ref().and_then(|tok| closing(tok.kind())) else {
+            let span = open
+                .as_ref()
+                .map_or_else(|| self.eof_span(), |tok| tok.span().clone());
+            return Err(ParseError::unexpected_token(
+                open,
+                "'(', '[' or '{'",
+                span.into_range(),
+                self.source_id.clone(),
+            ));
+        };
+        let len = tree_len(&self.tokens[self.pos - 1..]).ok_or_else(|| {
+            ParseError::unexpected_eof(
+                &close.to_string(),
+                self.eof_span().into_range(),
+                self.source_id.clone(),
+            )
+        })?;
+        let inner = self.tokens[self.pos..self.pos + len - 2].to_vec();
+        self.pos += len - 1;
+        Ok(inner)
+    }
+}
+
+fn closing(kind: &TokenKind) -> Option<TokenKind> {
+    match kind {
+        TokenKind::LeftParen => Some(TokenKind::RightParen),
+        TokenKind::LeftBracket => Some(TokenKind::RightBracket),
+        TokenKind::LeftBrace => Some(TokenKind::RightBrace),
+        _ => None,
+    }
+}
+
+/// The number of tokens in the token tree starting at `tokens[0]`: one token, or a delimited
+/// group including both delimiters.
+fn tree_len(tokens: &[Token]) -> Option<usize> {
+    let first = tokens.first()?;
+    if closing(first.kind()).is_none() {
+        return (!token_matches!(
+            first,
+            TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace
+        ))
+        .then_some(1);
+    }
+
+    let mut depth = 0usize;
+    for (i, tok) in tokens.iter().enumerate() {
+        match tok.kind() {
+            TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::LeftBrace => depth += 1,
+            TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_macro_def(ctx: &mut ParserCtx) -> ParseResult<MacroDef> {
+    ctx.expect(&TokenKind::Macro)?;
+    let name = ctx.expect_identifier()?;
+    let rules_tokens = ctx.delimited()?;
+
+    let mut rules_ctx = ctx.sub(rules_tokens);
+    let mut rules = Vec::new();
+    while !rules_ctx.is_eof() {
+        let pattern = rules_ctx.delimited()?;
+        rules_ctx.expect(&TokenKind::Assign)?;
+        rules_ctx.expect(&TokenKind::Greater)?;
+        let body = rules_ctx.delimited()?;
+        rules_ctx.consume_if(&TokenKind::Semicolon);
+
+        let pattern = parse_matchers(&mut ctx.sub(pattern))?;
+        let body = parse_transcribers(&mut ctx.sub(body))?;
+        let mut hygienic = HashSet::new();
+        collect_bindings(&body, &mut hygienic);
+        rules.push(MacroRule {
+            pattern,
+            body,
+            hygienic,
+        });
+    }
+
+    if rules.is_empty() {
+        return Err(ParseError::invalid_macro(
+            format!("Macro {name} has no rules"),
+            name.span().clone(),
+        ));
+    }
+    Ok(MacroDef { name, rules })
+}
+
+fn parse_matchers(ctx: &mut ParserCtx) -> ParseResult<Vec<Matcher>> {
+    let mut matchers = Vec::new();
+    while let Some(tok) = ctx.next() {
+        if !token_matches!(tok, TokenKind::Dollar) {
+            matchers.push(Matcher::Token(tok.kind));
+            continue;
+        }
+        if ctx
+            .peek()
+            .is_some_and(|tok| token_matches!(tok, TokenKind::LeftParen))
+        {
+            let inner = ctx.delimited()?;
+            let inner = parse_matchers(&mut ctx.sub(inner))?;
+            let (separator, at_least_one) = parse_repetition(ctx)?;
+            matchers.push(Matcher::Repeat {
+                inner,
+                separator: separator.map(|tok| tok.kind),
+                at_least_one,
+            });
+            continue;
+        }
+
+        let name = ctx.expect_identifier()?;
+        ctx.expect(&TokenKind::Colon)?;
+        let kind = ctx.expect_identifier()?;
+        let fragment = Fragment::from_name(kind.inner()).ok_or_else(|| {
+            ParseError::invalid_macro(
+                format!(
+                    "Unknown fragment kind {kind}, expected expr, ident, ty, literal, block or tt"
+                ),
+                kind.span().clone(),
+            )
+        })?;
+        matchers.push(Matcher::Fragment(name.inner().to_string(), fragment));
+    }
+    Ok(matchers)
+}
+
+fn parse_transcribers(ctx: &mut ParserCtx) -> ParseResult<Vec<Transcriber>> {
+    let mut body = Vec::new();
+    while let Some(tok) = ctx.next() {
+        if !token_matches!(tok, TokenKind::Dollar) {
+            body.push(Transcriber::Token(tok));
+            continue;
+        }
+        if ctx
+            .peek()
+            .is_some_and(|tok| token_matches!(tok, TokenKind::LeftParen))
+        {
+            let inner = ctx.delimited()?;
+            let inner = parse_transcribers(&mut ctx.sub(inner))?;
+            let (separator, _) = parse_repetition(ctx)?;
+            body.push(Transcriber::Repeat { inner, separator });
+            continue;
+        }
+        body.push(Transcriber::Var(ctx.expect_identifier()?));
+    }
+    Ok(body)
+}
+
+/// The optional separator and the `*` or `+` after `$( ... )`.
+fn parse_repetition(ctx: &mut ParserCtx) -> ParseResult<(Option<Token>, bool)> {
+    let is_operator = |tok: &Token| token_matches!(tok, TokenKind::Multiply | TokenKind::Plus);
+    let separator = match ctx.peek() {
+        Some(tok) if !is_operator(tok) => ctx.next(),
+        _ => None,
+    };
+    match ctx.next() {
+        Some(tok) if is_operator(&tok) => Ok((separator, token_matches!(tok, TokenKind::Plus))),
+        other => {
+            let span = other
+                .as_ref()
+                .map_or_else(|| ctx.eof_span(), |tok| tok.span().clone());
+            Err(ParseError::unexpected_token(
+                other,
+                "'*' or '+' after a repetition",
+                span.into_range(),
+                ctx.source_id.clone(),
+            ))
+        }
+    }
+}
+
+fn collect_bindings(body: &[Transcriber], names: &mut HashSet<String>) {
+    for pair in body.windows(2) {
+        if let [Transcriber::Token(binder), Transcriber::Token(name)] = pair
+            && token_matches!(binder, TokenKind::Let | TokenKind::For)
+            && let TokenKind::Ident(name) = name.kind()
+        {
+            names.insert(name.inner().to_string());
+        }
+    }
+    for part in body {
+        if let Transcriber::Repeat { inner, .. } = part {
+            collect_bindings(inner, names);
+        }
+    }
+}
+
+fn pattern_vars(matchers: &[Matcher], names: &mut Vec<String>) {
+    for matcher in matchers {
+        match matcher {
+            Matcher::Token(_) => {}
+            Matcher::Fragment(name, _) => names.push(name.clone()),
+            Matcher::Repeat { inner, .. } => pattern_vars(inner, names),
+        }
+    }
+}
+
+fn body_vars<'a>(body: &'a [Transcriber], names: &mut Vec<&'a Ident>) {
+    for part in body {
+        match part {
+            Transcriber::Token(_) => {}
+            Transcriber::Var(name) => names.push(name),
+            Transcriber::Repeat { inner, .. } => body_vars(inner, names),
+        }
+    }
+}
+
+struct Expander {
+    macros: HashMap<String, MacroDef>,
+    source_id: String,
+    expansions: Cell<usize>,
+}
+
+impl Expander {
+    fn expand(&self, tokens: Vec<Token>, depth: usize, out: &mut Vec<Token>) -> ParseResult<()> {
+        let mut i = 0;
+        while i < tokens.len() {
+            // Only the macros of this file expand; any other `name!(...)` is left for the
+            // parser to report.
+            let (Some(TokenKind::Ident(name)), Some(TokenKind::Bang), Some(open)) = (
+                tokens.get(i).map(Token::kind),
+                tokens.get(i + 1).map(Token::kind),
+                tokens.get(i + 2).map(Token::kind),
+            ) else {
+                out.push(tokens[i].clone());
+                i += 1;
+                continue;
+            };
+            let Some(def) = self.macros.get(name.inner()) else {
+                out.push(tokens[i].clone());
+                i += 1;
+                continue;
+            };
+            if closing(open).is_none() {
+                out.push(tokens[i].clone());
+                i += 1;
+                continue;
+            }
+
+            let len = tree_len(&tokens[i + 2..]).ok_or_else(|| {
+                ParseError::unexpected_eof(
+                    "the end of the macro call",
+                    tokens[i + 2].span().into_range(),
+                    self.source_id.clone(),
+                )
+            })?;
+            let end = i + 2 + len;
+            let call_span = tokens[i].span().to(tokens[end - 1].span().clone());
+            let args = tokens[i + 3..end - 1].to_vec();
+
+            let expansion = self.expand_call(def, args, call_span, depth)?;
+            i = end;
+            if self.is_expr(&expansion) {
+                // Keeps `2 * double!(x)` from binding as `2 * x + x`.
+                let span = tokens[i - len - 2].span();
+                out.push(Token::new(TokenKind::LeftParen, span.clone()));
+                out.extend(expansion);
+                out.push(Token::new(TokenKind::RightParen, span.clone()));
+                continue;
+            }
+
+            // In `stmts!(...);` the expansion already ends its last statement.
+            if expansion.last().is_some_and(|tok| {
+                token_matches!(tok, TokenKind::Semicolon | TokenKind::RightBrace)
+            }) && tokens
+                .get(i)
+                .is_some_and(|tok| token_matches!(tok, TokenKind::Semicolon))
+            {
+                i += 1;
+            }
+            out.extend(expansion);
+        }
+        Ok(())
+    }
+
+    fn expand_call(
+        &self,
+        def: &MacroDef,
+        args: Vec<Token>,
+        call_span: Span,
+        depth: usize,
+    ) -> ParseResult<Vec<Token>> {
+        let name = &def.name;
+        if depth >= RECURSION_LIMIT {
+            return Err(ParseError::macro_recursion_limit(
+                name.inner(),
+                RECURSION_LIMIT,
+                call_span.with_expansion(def.name.span().clone()),
+            ));
+        }
+
+        let mut expanded_args = Vec::new();
+        self.expand(args, depth + 1, &mut expanded_args)?;
+
+        let (rule, captures) = def
+            .rules
+            .iter()
+            .find_map(|rule| {
+                let mut captures = Captures::new();
+                let end = self.match_seq(&rule.pattern, &expanded_args, 0, &mut captures)?;
+                (end == expanded_args.len()).then_some((rule, captures))
+            })
+            .ok_or_else(|| {
+                ParseError::no_matching_macro_rule(
+                    name.inner(),
+                    call_span.with_expansion(def.name.span().clone()),
+                )
+            })?;
+
+        self.expansions.set(self.expansions.get() + 1);
+        let mut transcribed = Vec::new();
+        self.transcribe(rule, &rule.body, &captures, &call_span, &mut transcribed)?;
+
+        let mut expansion = Vec::new();
+        self.expand(transcribed, depth + 1, &mut expansion)?;
+        Ok(expansion)
+    }
+
+    /// Matches `matchers` against `tokens` from `pos`, returning the position after the match.
+    fn match_seq(
+        &self,
+        matchers: &[Matcher],
+        tokens: &[Token],
+        mut pos: usize,
+        captures: &mut Captures,
+    ) -> Option<usize> {
+        for matcher in matchers {
+            match matcher {
+                Matcher::Token(kind) => {
+                    if tokens.get(pos)?.kind() != kind {
+                        return None;
+                    }
+                    pos += 1;
+                }
+                Matcher::Fragment(name, fragment) => {
+                    let len = self.fragment_len(*fragment, &tokens[pos..])?;
+                    captures.insert(
+                        name.clone(),
+                        Capture::Tokens(tokens[pos..pos + len].to_vec(), *fragment),
+                    );
+                    pos += len;
+                }
+                Matcher::Repeat {
+                    inner,
+                    separator,
+                    at_least_one,
+                } => {
+                    let mut iterations = Vec::new();
+                    loop {
+                        let mut next = pos;
+                        if !iterations.is_empty()
+                            && let Some(separator) = separator
+                        {
+                            if tokens.get(next).map(Token::kind) != Some(separator) {
+                                break;
+                            }
+                            next += 1;
+                        }
+                        let mut iteration = Captures::new();
+                        match self.match_seq(inner, tokens, next, &mut iteration) {
+                            Some(end) if end > pos => {
+                                pos = end;
+                                iterations.push(iteration);
+                            }
+                            _ => break,
+                        }
+                    }
+                    if *at_least_one && iterations.is_empty() {
+                        return None;
+                    }
+
+                    let mut names = Vec::new();
+                    pattern_vars(inner, &mut names);
+                    for name in names {
+                        let repeated = iterations
+                            .iter_mut()
+                            .filter_map(|iteration| iteration.remove(&name))
+                            .collect();
+                        captures.insert(name, Capture::Repeated(repeated));
+                    }
+                }
+            }
+        }
+        Some(pos)
+    }
+
+    fn fragment_len(&self, fragment: Fragment, tokens: &[Token]) -> Option<usize> {
+        let first = tokens.first()?;
+        match fragment {
+            Fragment::Ident => token_matches!(first, TokenKind::Ident(_)).then_some(1),
+            Fragment::Literal => match (first.kind(), tokens.get(1).map(Token::kind)) {
+                (
+                    TokenKind::Number(_)
+                    | TokenKind::Float(_)
+                    | TokenKind::String(_)
+                    | TokenKind::Char(_)
+                    | TokenKind::True
+                    | TokenKind::False
+                    | TokenKind::Null,
+                    _,
+                ) => Some(1),
+                (TokenKind::Minus, Some(TokenKind::Number(_) | TokenKind::Float(_))) => Some(2),
+                _ => None,
+            },
+            Fragment::Tt => tree_len(tokens),
+            Fragment::Block => token_matches!(first, TokenKind::LeftBrace)
+                .then(|| tree_len(tokens))
+                .flatten(),
+            Fragment::Expr => self.parsed_len(tokens, |ctx| Expr::parse(ctx).map(|_| ())),
+            Fragment::Ty => self.parsed_len(tokens, |ctx| ctx.expect_type().map(|_| ())),
+        }
+    }
+
+    /// How many tokens `parse` consumes from the front of `tokens`, if it succeeds.
+    fn parsed_len(
+        &self,
+        tokens: &[Token],
+        parse: impl FnOnce(&mut ParserCtx) -> ParseResult<()>,
+    ) -> Option<usize> {
+        let last = tokens.last()?.span().shrink_to_hi();
+        // A trailing `;` stops the parser at the end of the tokens instead of running out.
+        let mut sentinel = tokens.to_vec();
+        sentinel.push(Token::new(TokenKind::Semicolon, last));
+        let mut ctx = ParserCtx::new(sentinel, self.source_id.clone());
+        parse(&mut ctx).ok()?;
+        (ctx.pos > 0 && ctx.pos <= tokens.len()).then_some(ctx.pos)
+    }
+
+    fn is_expr(&self, tokens: &[Token]) -> bool {
+        self.parsed_len(tokens, |ctx| Expr::parse(ctx).map(|_| ())) == Some(tokens.len())
+    }
+
+    fn transcribe(
+        &self,
+        rule: &MacroRule,
+        body: &[Transcriber],
+        captures: &Captures,
+        call_span: &Span,
+        out: &mut Vec<Token>,
+    ) -> ParseResult<()> {
+        for part in body {
+            match part {
+                Transcriber::Token(tok) => out.push(self.hygienic(rule, tok, call_span)),
+                Transcriber::Var(name) => match captures.get(name.inner()) {
+                    Some(Capture::Tokens(tokens, Fragment::Expr)) if tokens.len() > 1 => {
+                        // Keeps `$x * $x` with `x = 1 + 1` from binding as `1 + 1 * 1 + 1`.
+                        let span = tokens[0].span();
+                        out.push(Token::new(TokenKind::LeftParen, span.clone()));
+                        out.extend(tokens.iter().cloned());
+                        out.push(Token::new(TokenKind::RightParen, span.clone()));
+                    }
+                    Some(Capture::Tokens(tokens, _)) => out.extend(tokens.iter().cloned()),
+                    Some(Capture::Repeated(_)) => {
+                        return Err(ParseError::invalid_macro(
+                            format!("${name} repeats, so it must be used inside $( ... )*"),
+                            call_span.with_expansion(name.span().clone()),
+                        ));
+                    }
+                    None => {
+                        return Err(ParseError::invalid_macro(
+                            format!("${name} is not bound by the macro's pattern"),
+                            call_span.with_expansion(name.span().clone()),
+                        ));
+                    }
+                },
+                Transcriber::Repeat { inner, separator } => {
+                    let mut names = Vec::new();
+                    body_vars(inner, &mut names);
+                    let repeated: Vec<_> = names
+                        .iter()
+                        .filter_map(|name| match captures.get(name.inner()) {
+                            Some(Capture::Repeated(items)) => Some((*name, items)),
+                            _ => None,
+                        })
+                        .collect();
+                    let Some((first, first_items)) = repeated.first() else {
+                        return Err(ParseError::invalid_macro(
+                            "A repetition in a macro body must use a repeating variable"
+                                .to_string(),
+                            call_span.clone(),
+                        ));
+                    };
+                    if let Some((name, _)) = repeated
+                        .iter()
+                        .find(|(_, items)| items.len() != first_items.len())
+                    {
+                        return Err(ParseError::invalid_macro(
+                            format!("${first} and ${name} repeat a different number of times"),
+                            call_span.with_expansion(name.span().clone()),
+                        ));
+                    }
+
+                    for index in 0..first_items.len() {
+                        if index > 0
+                            && let Some(separator) = separator
+                        {
+                            out.push(self.hygienic(rule, separator, call_span));
+                        }
+                        let mut iteration = captures.clone();
+                        for (name, items) in &repeated {
+                            iteration.insert(name.inner().to_string(), items[index].clone());
+                        }
+                        self.transcribe(rule, inner, &iteration, call_span, out)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A body token placed at the call site. Names the body binds itself get a suffix unique
+    /// to this expansion, which source code cannot spell.
+    fn hygienic(&self, rule: &MacroRule, tok: &Token, call_span: &Span) -> Token {
+        let span = call_span.with_expansion(tok.span().clone());
+        let kind = match tok.kind() {
+            TokenKind::Ident(ident) if rule.hygienic.contains(ident.inner()) => {
+                TokenKind::Ident(Ident::new(
+                    format!("{}#{}", ident.inner(), self.expansions.get()),
+                    span.clone(),
+                ))
+            }
+            TokenKind::Ident(ident) => {
+                TokenKind::Ident(Ident::new(ident.inner().to_string(), span.clone()))
+            }
+            other => other.clone(),
+        };
+        Token::new(kind, span)
+    }
+}
@@ -1,7 +1,9 @@
+mod attr;
 mod defer;
 mod error;
 mod expr;
 mod func;
+mod macros;
 mod module;
 mod stmt;
 mod struct_;
@@ -36,6 +38,10 @@ impl ParserCtx {
     }
 
     pub fn parse(&mut self) -> FinalParseResult<Program> {
+        if let Err(e) = self.expand_macros() {
+            return Err(error::FinalParseError::new(vec![e]));
+        }
+
         let mut stmts = Vec::new();
         let mut errors = Vec::new();
 
@@ -44,7 +50,8 @@ impl ParserCtx {
             match stmt {
                 Ok(s) => stmts.push(s),
                 Err(e) => {
-                    errors.push(e);
+                    let at = self.pos.saturating_sub(1);
+                    errors.push(e.at_token(self.tokens.get(at)));
                     self.skip_to_recovery_point();
                 }
             }
@@ -67,6 +74,7 @@ impl ParserCtx {
                             | TokenKind::RightBrace
                             | TokenKind::Let
                             | TokenKind::Fn
+                            | TokenKind::Pub
                             | TokenKind::Extern
                             | TokenKind::Intrinsic
                             | TokenKind::Struct
@@ -92,6 +100,10 @@ impl ParserCtx {
         self.tokens.get(self.pos)
     }
 
+    pub(crate) fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n)
+    }
+
     pub(crate) fn next(&mut self) -> Option<Token> {
         if let Some(tok) = self.tokens.get(self.pos).cloned() {
             self.pos += 1;
@@ -128,6 +140,10 @@ impl ParserCtx {
         self.tokens[self.pos - 1].span().clone()
     }
 
+    pub(crate) fn prev_is(&self, kind: &TokenKind) -> bool {
+        self.pos > 0 && self.tokens[self.pos - 1].kind() == kind
+    }
+
     pub(crate) fn expect(&mut self, expected: &TokenKind) -> ParseResult<()> {
         let source_id = self.source_id.clone();
         let token = self.next();
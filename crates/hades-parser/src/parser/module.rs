@@ -1,7 +1,7 @@
 use crate::parser::Parse;
 use crate::parser::ParserCtx;
 use crate::parser::error::ParseResult;
-use hades_ast::{Import, ImportPrefix, ModuleDecl, Stmt};
+use hades_ast::{Import, ImportItems, ImportName, ImportPrefix, ModuleDecl, Stmt};
 use hades_common::token_matches;
 use hades_tokens::TokenKind;
 
@@ -37,10 +37,28 @@ impl Parse for Import {
                 ctx.next();
                 ImportPrefix::Local
             }
+            Some(tok) if matches!(tok.kind(), TokenKind::Ident(name) if name.inner() == "c") => {
+                ctx.next();
+                let header = ctx.expect_string_literal()?;
+                let end = ctx.prev_span();
+                return Ok(Stmt::Import(Import {
+                    prefix: ImportPrefix::C,
+                    parents: Vec::new(),
+                    module: header,
+                    alias: None,
+                    items: ImportItems::Module,
+                    public: false,
+                    span: start_tok.to(end),
+                }));
+            }
+            Some(tok) if matches!(tok.kind(), TokenKind::Ident(_)) => {
+                let package = ctx.expect_identifier()?;
+                ImportPrefix::Package(package.inner().to_string())
+            }
             _ => {
                 return Err(crate::parser::error::ParseError::unexpected_token(
                     ctx.peek().cloned(),
-                    "std or self",
+                    "std, self, c or a package name",
                     ctx.current_span().into_range(),
                     ctx.source_id.clone(),
                 ));
@@ -48,13 +66,67 @@ impl Parse for Import {
         };
 
         ctx.expect(&TokenKind::DoubleColon)?;
-        let module_name = ctx.expect_identifier()?;
+        let mut parents = Vec::new();
+        let mut module_name = ctx.expect_identifier()?;
+
+        let mut items = None;
+        while ctx.consume_if(&TokenKind::DoubleColon) {
+            if ctx.consume_if(&TokenKind::Multiply) {
+                items = Some(ImportItems::Glob);
+                break;
+            }
+            if ctx
+                .peek()
+                .is_some_and(|tok| token_matches!(tok, TokenKind::LeftBrace))
+            {
+                items = Some(ImportItems::Names(parse_import_names(ctx)?));
+                break;
+            }
+            parents.push(module_name.inner().to_string());
+            module_name = ctx.expect_identifier()?;
+        }
+
+        let (alias, items) = match items {
+            Some(items) => (None, items),
+            None if ctx.consume_if(&TokenKind::As) => {
+                (Some(ctx.expect_identifier()?), ImportItems::Module)
+            }
+            None => (None, ImportItems::Module),
+        };
         let end = ctx.prev_span();
 
         Ok(Stmt::Import(Import {
             prefix,
+            parents,
             module: module_name.inner().to_string(),
+            alias,
+            items,
+            public: false,
             span: start_tok.to(end),
         }))
     }
 }
+
+/// `{printf, fopen as open}` after `import std::io::`.
+fn parse_import_names(ctx: &mut ParserCtx) -> ParseResult<Vec<ImportName>> {
+    ctx.expect(&TokenKind::LeftBrace)?;
+    let mut names = Vec::new();
+    while !ctx
+        .peek()
+        .is_some_and(|tok| token_matches!(tok, TokenKind::RightBrace))
+    {
+        let name = ctx.expect_identifier()?;
+        let alias = if ctx.consume_if(&TokenKind::As) {
+            Some(ctx.expect_identifier()?)
+        } else {
+            None
+        };
+        names.push(ImportName { name, alias });
+
+        if !ctx.consume_if(&TokenKind::Comma) {
+            break;
+        }
+    }
+    ctx.expect(&TokenKind::RightBrace)?;
+    Ok(names)
+}
@@ -1,13 +1,27 @@
 use crate::parser::Parse;
 use crate::parser::ParserCtx;
+use crate::parser::attr::parse_attributed_item;
 use crate::parser::error::ParseResult;
 use crate::parser::expr::parse_assignment;
 use crate::parser::func::{FuncDef, parse_extern_fn, parse_intrinsic_fn};
-use crate::parser::struct_::StructDef;
+use crate::parser::struct_::{StructDef, is_extern_type, parse_extern_type};
 use hades_ast::*;
 use hades_common::token_matches;
+use hades_error::Span;
 use hades_tokens::TokenKind;
 
+/// The statements of a block and the trailing expression that yields its value.
+pub(super) struct BlockBody {
+    pub stmts: Vec<Stmt>,
+    pub tail: Option<ExprAst>,
+}
+
+impl BlockBody {
+    pub fn into_block(self, span: Span) -> Block {
+        Block::with_tail(self.stmts.into(), self.tail, span)
+    }
+}
+
 impl Parse for Stmt {
     type Output = Stmt;
 
@@ -15,7 +29,13 @@ impl Parse for Stmt {
         match ctx.peek() {
             Some(tok) if token_matches!(tok, TokenKind::Struct) => StructDef::parse(ctx),
             Some(tok) if token_matches!(tok, TokenKind::Fn) => FuncDef::parse(ctx),
+            Some(tok) if token_matches!(tok, TokenKind::Extern) && is_extern_type(ctx) => {
+                parse_extern_type(ctx)
+            }
             Some(tok) if token_matches!(tok, TokenKind::Extern) => parse_extern_fn(ctx),
+            Some(tok) if token_matches!(tok, TokenKind::At) => parse_attributed_item(ctx),
+            Some(tok) if token_matches!(tok, TokenKind::Export) => parse_export(ctx),
+            Some(tok) if token_matches!(tok, TokenKind::Pub) => parse_pub(ctx),
             Some(tok) if token_matches!(tok, TokenKind::Intrinsic) => parse_intrinsic_fn(ctx),
             Some(tok) if token_matches!(tok, TokenKind::Let) => Let::parse(ctx),
             Some(tok) if token_matches!(tok, TokenKind::If) => If::parse(ctx),
@@ -32,6 +52,67 @@ impl Parse for Stmt {
     }
 }
 
+/// `export fn` / `export struct`: the item keeps its source name for C callers.
+pub(super) fn parse_export(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
+    let start_tok = ctx.current_span();
+    ctx.expect(&TokenKind::Export)?;
+    let mut stmt = match ctx.peek() {
+        Some(tok) if token_matches!(tok, TokenKind::Struct) => StructDef::parse(ctx)?,
+        _ => FuncDef::parse(ctx)?,
+    };
+
+    match &mut stmt {
+        Stmt::FuncDef(func) => {
+            func.export = true;
+            func.span = start_tok.to(func.span.clone());
+        }
+        Stmt::StructDef(def) => {
+            def.export = true;
+            def.span = start_tok.to(def.span.clone());
+        }
+        _ => {}
+    }
+    Ok(stmt)
+}
+
+/// `pub fn`, `pub struct`, `pub extern fn` / `pub intrinsic fn`: the item can be used by
+/// importing modules.
+pub(super) fn parse_pub(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
+    let start_tok = ctx.current_span();
+    ctx.expect(&TokenKind::Pub)?;
+    let mut stmt = match ctx.peek() {
+        Some(tok) if token_matches!(tok, TokenKind::Struct) => StructDef::parse(ctx)?,
+        Some(tok) if token_matches!(tok, TokenKind::Extern) && is_extern_type(ctx) => {
+            parse_extern_type(ctx)?
+        }
+        Some(tok) if token_matches!(tok, TokenKind::Extern) => parse_extern_fn(ctx)?,
+        Some(tok) if token_matches!(tok, TokenKind::Intrinsic) => parse_intrinsic_fn(ctx)?,
+        Some(tok) if token_matches!(tok, TokenKind::Import) => Import::parse(ctx)?,
+        _ => FuncDef::parse(ctx)?,
+    };
+
+    match &mut stmt {
+        Stmt::FuncDef(func) => {
+            func.public = true;
+            func.span = start_tok.to(func.span.clone());
+        }
+        Stmt::StructDef(def) => {
+            def.public = true;
+            def.span = start_tok.to(def.span.clone());
+        }
+        Stmt::Import(import) => {
+            import.public = true;
+            import.span = start_tok.to(import.span.clone());
+        }
+        Stmt::ExternType(ext) => {
+            ext.public = true;
+            ext.span = start_tok.to(ext.span.clone());
+        }
+        _ => {}
+    }
+    Ok(stmt)
+}
+
 impl Parse for Let {
     type Output = Stmt;
 
@@ -72,27 +153,48 @@ impl Parse for If {
     type Output = Stmt;
 
     fn parse(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
+        parse_if(ctx).map(Stmt::If)
+    }
+}
+
+pub(super) fn parse_if(ctx: &mut ParserCtx) -> ParseResult<If> {
+    let start_tok = ctx.current_span();
+    ctx.expect(&TokenKind::If)?;
+    let cond = parse_if_expr(ctx)?;
+    let then_branch = parse_stmt_or_block(ctx)?;
+    let else_branch = if ctx.consume_if(&TokenKind::Else) {
+        Some(parse_stmt_or_block(ctx)?)
+    } else {
+        None
+    };
+    let end = ctx.prev_span();
+    let span = start_tok.to(end);
+
+    Ok(If {
+        cond: ExprAst {
+            expr: cond,
+            span: span.clone(),
+        },
+        then_branch: then_branch.into_block(span.clone()),
+        else_branch: else_branch.map(|p| p.into_block(span.clone())),
+        span,
+    })
+}
+
+impl Parse for Loop {
+    type Output = Expr;
+
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Expr> {
         let start_tok = ctx.current_span();
-        ctx.expect(&TokenKind::If)?;
-        let cond = parse_if_expr(ctx)?;
-        let then_branch = parse_stmt_or_block(ctx)?;
-        let else_branch = if ctx.consume_if(&TokenKind::Else) {
-            Some(parse_stmt_or_block(ctx)?)
-        } else {
-            None
-        };
+        ctx.expect(&TokenKind::Loop)?;
+        let body = parse_block(ctx)?;
         let end = ctx.prev_span();
         let span = start_tok.to(end);
 
-        Ok(Stmt::If(If {
-            cond: ExprAst {
-                expr: cond,
-                span: span.clone(),
-            },
-            then_branch: Block::new(then_branch.into(), span.clone()),
-            else_branch: else_branch.map(|p| Block::new(p.into(), span.clone())),
+        Ok(Expr::Loop(Box::new(Loop {
+            body: body.into_block(span.clone()),
             span,
-        }))
+        })))
     }
 }
 
@@ -109,7 +211,7 @@ impl Parse for While {
 
         Ok(Stmt::While(While {
             cond,
-            body: Block::new(body.into(), span.clone()),
+            body: body.into_block(span.clone()),
             span,
         }))
     }
@@ -121,6 +223,12 @@ impl Parse for For {
     fn parse(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
         let start_tok = ctx.current_span();
         ctx.expect(&TokenKind::For)?;
+        if ctx
+            .peek_nth(1)
+            .is_some_and(|tok| token_matches!(tok, TokenKind::In))
+        {
+            return parse_for_each(ctx, start_tok);
+        }
         let init = Let::parse(ctx)?.unwrap_let();
         let cond = parse_while_expr(ctx)?.unwrap_binary();
         ctx.expect(&TokenKind::Semicolon)?;
@@ -133,12 +241,33 @@ impl Parse for For {
             init,
             cond: cond.clone(),
             update: update.clone(),
-            body: Block::new(body.into(), span.clone()),
+            body: body.into_block(span.clone()),
             span,
         }))
     }
 }
 
+fn parse_for_each(ctx: &mut ParserCtx, start_tok: Span) -> ParseResult<Stmt> {
+    let binding = ctx.expect_identifier()?;
+    ctx.expect(&TokenKind::In)?;
+    let iterable_start = ctx.current_span();
+    let iterable = parse_while_expr(ctx)?;
+    let iterable_span = iterable_start.to(ctx.prev_span());
+    let body = parse_stmt_or_block(ctx)?;
+    let end = ctx.prev_span();
+    let span = start_tok.to(end);
+
+    Ok(Stmt::ForEach(ForEach {
+        binding,
+        iterable: ExprAst {
+            expr: iterable,
+            span: iterable_span,
+        },
+        body: body.into_block(span.clone()),
+        span,
+    }))
+}
+
 impl Parse for Return {
     type Output = Stmt;
 
@@ -185,21 +314,35 @@ impl Parse for Break {
     fn parse(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
         let start_tok = ctx.current_span();
         ctx.expect(&TokenKind::Break)?;
+        let value = if !ctx
+            .peek()
+            .is_some_and(|tok| token_matches!(tok, TokenKind::Semicolon))
+        {
+            let value_start = ctx.current_span();
+            let expr = parse_assignment(ctx)?;
+            Some(ExprAst {
+                expr,
+                span: value_start.to(ctx.prev_span()),
+            })
+        } else {
+            None
+        };
         ctx.expect(&TokenKind::Semicolon)?;
         let end = ctx.prev_span();
         Ok(Stmt::Break(Break {
+            value,
             span: start_tok.to(end),
         }))
     }
 }
 
-pub(super) fn parse_block(ctx: &mut ParserCtx) -> ParseResult<Vec<Stmt>> {
+pub(super) fn parse_block(ctx: &mut ParserCtx) -> ParseResult<BlockBody> {
     if !ctx
         .peek()
         .map(|t| t.kind().eq(&TokenKind::LeftBrace))
         .unwrap_or_default()
     {
-        return Ok(vec![Stmt::parse(ctx)?]);
+        return Ok(single_stmt_body(Stmt::parse(ctx)?));
     }
 
     ctx.expect(&TokenKind::LeftBrace)?;
@@ -212,25 +355,70 @@ pub(super) fn parse_block(ctx: &mut ParserCtx) -> ParseResult<Vec<Stmt>> {
         stmts.push(Stmt::parse(ctx)?);
     }
 
+    let tail = match stmts.pop() {
+        Some(Stmt::Expr(expr)) if !ctx.prev_is(&TokenKind::Semicolon) => Some(expr),
+        Some(last) => match into_tail(last) {
+            Ok(tail) => Some(tail),
+            Err(last) => {
+                stmts.push(*last);
+                None
+            }
+        },
+        None => None,
+    };
+
     ctx.expect(&TokenKind::RightBrace)?;
-    Ok(stmts)
+    Ok(BlockBody { stmts, tail })
 }
 
-pub(super) fn parse_stmt_or_block(ctx: &mut ParserCtx) -> ParseResult<Vec<Stmt>> {
+pub(super) fn parse_stmt_or_block(ctx: &mut ParserCtx) -> ParseResult<BlockBody> {
     if ctx
         .peek()
         .is_some_and(|tok| token_matches!(tok, TokenKind::LeftBrace))
     {
         parse_block(ctx)
     } else {
-        Ok(vec![Stmt::parse(ctx)?])
+        Ok(single_stmt_body(Stmt::parse(ctx)?))
+    }
+}
+
+fn single_stmt_body(stmt: Stmt) -> BlockBody {
+    match into_tail(stmt) {
+        Ok(tail) => BlockBody {
+            stmts: Vec::new(),
+            tail: Some(tail),
+        },
+        Err(stmt) => BlockBody {
+            stmts: vec![*stmt],
+            tail: None,
+        },
+    }
+}
+
+/// An `if` with an `else` whose branches yield values is an expression when it ends a block.
+fn into_tail(stmt: Stmt) -> Result<ExprAst, Box<Stmt>> {
+    match stmt {
+        Stmt::If(if_stmt)
+            if if_stmt.else_branch.is_some() && if_stmt.then_branch.tail.is_some() =>
+        {
+            Ok(ExprAst {
+                span: if_stmt.span.clone(),
+                expr: Expr::If(Box::new(if_stmt)),
+            })
+        }
+        other => Err(Box::new(other)),
     }
 }
 
 fn parse_expr_stmt(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
     let start_tok = ctx.current_span();
     let expr = parse_assignment(ctx)?;
-    ctx.expect(&TokenKind::Semicolon)?;
+    let ends_block = ctx
+        .peek()
+        .is_some_and(|tok| token_matches!(tok, TokenKind::RightBrace));
+    if !ctx.consume_if(&TokenKind::Semicolon) && !ends_block && !expr.is_block_like() {
+        ctx.expect(&TokenKind::Semicolon)?;
+    }
     let end = ctx.prev_span();
     Ok(Stmt::Expr(ExprAst {
         expr,
@@ -1,5 +1,6 @@
 use crate::parser::Parse;
 use crate::parser::ParserCtx;
+use crate::parser::attr::parse_attributes;
 use crate::parser::error::ParseResult;
 use crate::parser::expr::parse_assignment;
 use crate::parser::func::FuncDef;
@@ -7,7 +8,7 @@ use hades_ast::*;
 use hades_common::token_matches;
 use hades_tokens::Name;
 use hades_tokens::{Ident, ParamKind, TokenKind};
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 
 pub(super) struct StructDef;
 
@@ -19,17 +20,47 @@ impl Parse for StructDef {
         ctx.expect(&TokenKind::Struct)?;
         let ident = ctx.expect_identifier()?;
         let name = Name::new(ident.to_string(), ident.span().clone());
-        let fields = parse_field_list(ctx, name.clone())?;
+        let FieldList {
+            fields,
+            field_attributes,
+            public_fields,
+        } = parse_field_list(ctx, name.clone())?;
         let end = ctx.prev_span();
 
         Ok(Stmt::StructDef(hades_ast::StructDef {
             name,
             fields,
+            export: false,
+            public: false,
+            attributes: Attributes::default(),
+            field_attributes,
+            public_fields,
             span: start_tok.to(end),
         }))
     }
 }
 
+/// `extern type Name;` — `type` is only a keyword in this position.
+pub(super) fn parse_extern_type(ctx: &mut ParserCtx) -> ParseResult<Stmt> {
+    let start_tok = ctx.current_span();
+    ctx.expect(&TokenKind::Extern)?;
+    ctx.expect_identifier()?;
+    let ident = ctx.expect_identifier()?;
+    ctx.expect(&TokenKind::Semicolon)?;
+    let end = ctx.prev_span();
+
+    Ok(Stmt::ExternType(ExternType {
+        name: Name::new(ident.to_string(), ident.span().clone()),
+        public: false,
+        span: start_tok.to(end),
+    }))
+}
+
+pub(super) fn is_extern_type(ctx: &ParserCtx) -> bool {
+    ctx.peek_nth(1)
+        .is_some_and(|tok| matches!(tok.kind(), TokenKind::Ident(name) if name.inner() == "type"))
+}
+
 pub(super) fn parse_struct_literal(ctx: &mut ParserCtx, path: Vec<Ident>) -> ParseResult<Expr> {
     ctx.expect(&TokenKind::LeftBrace)?;
     let mut fields = IndexMap::new();
@@ -56,17 +87,29 @@ pub(super) fn parse_struct_literal(ctx: &mut ParserCtx, path: Vec<Ident>) -> Par
     Ok(Expr::StructInit(StructInitExpr { path, fields }))
 }
 
+/// The fields and methods of a struct, along with the attributes of each field and the
+/// data fields declared `pub`.
+pub(super) struct FieldList {
+    pub fields: IndexMap<Ident, FieldKind>,
+    pub field_attributes: IndexMap<Ident, Attributes>,
+    pub public_fields: IndexSet<Ident>,
+}
+
 pub(super) fn parse_field_list(
     ctx: &mut ParserCtx,
     struct_name: hades_tokens::Name,
-) -> ParseResult<IndexMap<hades_tokens::Ident, FieldKind>> {
+) -> ParseResult<FieldList> {
     ctx.expect(&TokenKind::LeftBrace)?;
     let mut fields = IndexMap::new();
+    let mut field_attributes = IndexMap::new();
+    let mut public_fields = IndexSet::new();
 
     while !ctx
         .peek()
         .is_some_and(|tok| token_matches!(tok, TokenKind::RightBrace))
     {
+        let attrs = parse_attributes(ctx)?;
+        let public = ctx.consume_if(&TokenKind::Pub);
         let field = ctx.peek().ok_or_else(|| {
             let span = ctx.eof_span().into_range();
             crate::parser::error::ParseError::unexpected_token(
@@ -80,6 +123,8 @@ pub(super) fn parse_field_list(
         match field.kind() {
             TokenKind::Fn => {
                 let mut func = FuncDef::parse(ctx)?.unwrap_func_def();
+                func.attributes = attrs.check(ctx, AttributeTarget::Function)?;
+                func.public = public;
                 let kind = func
                     .params
                     .iter()
@@ -103,6 +148,13 @@ pub(super) fn parse_field_list(
                 ctx.next();
                 ctx.expect(&TokenKind::Colon)?;
                 let field_type = ctx.expect_type()?;
+                let attributes = attrs.check(ctx, AttributeTarget::Field)?;
+                if !attributes.is_empty() {
+                    field_attributes.insert(field_name.clone(), attributes);
+                }
+                if public {
+                    public_fields.insert(field_name.clone());
+                }
                 fields.insert(field_name, FieldKind::Var(field_type));
 
                 if !ctx.consume_if(&TokenKind::Comma)
@@ -123,5 +175,9 @@ pub(super) fn parse_field_list(
     }
 
     ctx.expect(&TokenKind::RightBrace)?;
-    Ok(fields)
+    Ok(FieldList {
+        fields,
+        field_attributes,
+        public_fields,
+    })
 }
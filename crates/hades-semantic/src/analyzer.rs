@@ -9,6 +9,7 @@ use crate::lint::{LintDiagnostic, LintRunner};
 use hades_ast::{CompilerContext, ModulePath as AstModulePath, WalkAst};
 use hades_error::{SemanticError, Span};
 use hades_module::{Module, ModulePath, ModuleSignatures, TypedModule};
+use hades_tokens::Name;
 
 pub struct Unprepared;
 pub struct Prepared;
@@ -37,12 +38,92 @@ fn to_ast_path(path: &ModulePath) -> AstModulePath {
     match path {
         ModulePath::Std(name) => AstModulePath::Std(name.clone()),
         ModulePath::Local(name) => AstModulePath::Local(name.clone()),
+        ModulePath::C(header) => AstModulePath::C(header.clone()),
+        ModulePath::Package(package, segments) => {
+            AstModulePath::Package(package.clone(), segments.clone())
+        }
+    }
+}
+
+/// Brings an imported module's signatures into `ctx`. Through an import cycle they can include
+/// the items of `ctx`'s own module, which it declares itself.
+fn seed(ctx: &mut CompilerContext, sigs: &ModuleSignatures) {
+    let own = ctx.module_name().map(str::to_string);
+    let foreign = |name: &Name| name.module() != own.as_deref();
+
+    for (name, sig) in sigs.functions.iter().filter(|(name, _)| foreign(name)) {
+        ctx.import_function(name.clone(), sig.clone());
+    }
+    for (name, fields) in sigs.structs.iter().filter(|(name, _)| foreign(name)) {
+        ctx.insert_struct(name.clone(), fields.clone());
+    }
+    for name in sigs.structs.opaque_types().filter(|name| foreign(name)) {
+        ctx.import_opaque(name.clone());
+    }
+    for name in sigs.structs.packed() {
+        ctx.pack_struct(name.clone());
+    }
+    for (name, field, note) in sigs.structs.deprecations() {
+        ctx.deprecate_struct(name.clone(), field.cloned(), note.cloned());
+    }
+    for (name, field) in sigs.structs.private_fields() {
+        ctx.hide_field(name.clone(), field.clone());
+    }
+    for (name, declared) in &sigs.private {
+        ctx.mark_private(name.clone(), declared.clone());
+    }
+    for (key, item) in &sigs.reexports {
+        ctx.import_reexport(key.clone(), item.clone());
+    }
+}
+
+/// Collects the signatures of every module before any body is checked, so modules can import
+/// each other.
+///
+/// A module in an import cycle is declared before some of the modules it imports, so items
+/// using their structs fail to register in that round. Rounds repeat until no module's
+/// signatures change. Each round sees at least the items the previous one did, so this stops
+/// after at most one round per module in the longest import chain, plus the round that finds
+/// nothing changed. Running past that bound is reported rather than looping forever.
+///
+/// Errors from `declare` are not reported here: those of early rounds may only mean a cycle is
+/// not resolved yet, and the full walk in [`Analyzer::prepare`] registers every item again
+/// against the final signatures, reporting whatever still fails.
+fn declare(modules: &[Module]) -> Result<IndexMap<ModulePath, ModuleSignatures>, SemanticError> {
+    let mut sig_cache: IndexMap<ModulePath, ModuleSignatures> = IndexMap::new();
+    let max_rounds = modules.len() + 1;
+    let mut rounds = 0;
+    let mut changed = true;
+    while changed {
+        if rounds == max_rounds {
+            return Err(SemanticError::unsettled_signatures(rounds));
+        }
+        rounds += 1;
+        changed = false;
+        for module in modules {
+            let mut ctx = CompilerContext::new();
+            ctx.set_module_path(to_ast_path(&module.path));
+            for dep_path in &module.imports {
+                if let Some(sigs) = sig_cache.get(dep_path) {
+                    seed(&mut ctx, sigs);
+                }
+            }
+            module.ast.mark_overloads(&mut ctx);
+            module.ast.declare(&mut ctx);
+
+            let signatures = ModuleSignatures::from_context(ctx, to_ast_path(&module.path));
+            if sig_cache.get(&module.path) != Some(&signatures) {
+                sig_cache.insert(module.path.clone(), signatures);
+                changed = true;
+            }
+        }
     }
+    Ok(sig_cache)
 }
 
 impl Analyzer<Unprepared> {
     pub fn prepare(self, modules: Vec<Module>) -> Result<Analyzer<Prepared>, SemanticError> {
-        let mut sig_cache: IndexMap<ModulePath, ModuleSignatures> = IndexMap::new();
+        let sig_cache = declare(&modules)?;
         let mut typed_modules = Vec::with_capacity(modules.len());
 
         for module in modules {
@@ -51,22 +132,16 @@ impl Analyzer<Unprepared> {
 
             for dep_path in &module.imports {
                 if let Some(sigs) = sig_cache.get(dep_path) {
-                    for (name, sig) in &sigs.functions {
-                        ctx.register_function(name.clone(), sig.clone())?;
-                    }
-                    for (name, fields) in sigs.structs.iter() {
-                        ctx.insert_struct(name.clone(), fields.clone());
-                    }
+                    seed(&mut ctx, sigs);
                 }
             }
 
+            module.ast.mark_overloads(&mut ctx);
             let program = module.ast.walk(&mut ctx, Span::default())?;
             let imports = module.imports.clone();
             let path = module.path.clone();
-            let ast_path = to_ast_path(&path);
 
-            let signatures = ModuleSignatures::from_context(ctx.clone(), ast_path);
-            sig_cache.insert(path.clone(), signatures.clone());
+            let signatures = ModuleSignatures::from_context(ctx.clone(), to_ast_path(&path));
 
             typed_modules.push(TypedModule {
                 path: to_ast_path(&path),
@@ -101,7 +176,12 @@ impl Analyzer<Prepared> {
         runner.register(ArrayBoundsLint);
         runner.register(NullDerefLint);
 
-        let mut all_diags = Vec::new();
+        let mut all_diags: Vec<_> = self
+            .modules
+            .iter()
+            .flat_map(|m| m.ctx.warnings())
+            .map(|warning| LintDiagnostic::warning("deprecated", warning.clone().into_error()))
+            .collect();
         for typed_module in &self.modules {
             let mir = hades_mir::lower(typed_module.clone());
             let diags = runner.run(&mir);
@@ -25,6 +25,11 @@ impl Lint for ArrayBoundsLint {
             let mut const_map: HashMap<usize, i64> = HashMap::new();
 
             for stmt in &block.stmts {
+                if let StatementKind::InlineAsm { outputs, .. } = &stmt.kind {
+                    for place in outputs {
+                        const_map.remove(&place.local);
+                    }
+                }
                 if let StatementKind::Assign(place, rvalue) = &stmt.kind {
                     if place.projection.is_empty()
                         && let Rvalue::Use(Operand::Const(MirConst::Int(n))) = rvalue.as_ref()
@@ -21,6 +21,13 @@ impl LintDiagnostic {
         Self { lint_name, error }
     }
 
+    pub fn warning(lint_name: &'static str, error: Error) -> Self {
+        Self {
+            lint_name,
+            error: error.with_severity(ErrorSeverity::Warning),
+        }
+    }
+
     pub fn is_error(&self) -> bool {
         self.error.severity == ErrorSeverity::Error
     }
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use hades_ast::Types;
-use hades_error::{Error, ErrorSeverity, Span};
+use hades_error::{Error, Span};
 use hades_mir::mir::block::BasicBlockData;
 use hades_mir::mir::func::MirFunction;
 use hades_mir::mir::operand::{MirConst, Operand};
@@ -80,10 +80,10 @@ impl Lint for NullDerefLint {
             let mut state = block_in[block_idx].clone();
 
             for stmt in &block.stmts {
-                if let StatementKind::Assign(place, rvalue) = &stmt.kind {
+                if let StatementKind::Assign(_, rvalue) = &stmt.kind {
                     check_deref_in_rvalue(rvalue, &state, &stmt.span, self.name(), &mut diags);
-                    apply_stmt_to_state(place.local, rvalue, &mut state);
                 }
+                apply_stmt_to_state(&stmt.kind, &mut state);
             }
 
             if let Some(term) = &block.terminator {
@@ -97,14 +97,25 @@ impl Lint for NullDerefLint {
 
 fn transfer(block: &BasicBlockData, mut state: State) -> State {
     for stmt in &block.stmts {
-        if let StatementKind::Assign(place, rvalue) = &stmt.kind {
-            apply_stmt_to_state(place.local, rvalue, &mut state);
-        }
+        apply_stmt_to_state(&stmt.kind, &mut state);
     }
     state
 }
 
-fn apply_stmt_to_state(dest_local: usize, rvalue: &Rvalue, state: &mut State) {
+fn apply_stmt_to_state(kind: &StatementKind, state: &mut State) {
+    match kind {
+        StatementKind::Assign(place, rvalue) => apply_assign_to_state(place.local, rvalue, state),
+        // Nothing is known about what the assembly wrote.
+        StatementKind::InlineAsm { outputs, .. } => {
+            for place in outputs {
+                state.insert(place.local, NullState::Unknown);
+            }
+        }
+        StatementKind::Nop => {}
+    }
+}
+
+fn apply_assign_to_state(dest_local: usize, rvalue: &Rvalue, state: &mut State) {
     let new_state = match rvalue {
         Rvalue::Use(Operand::Const(MirConst::Null(_))) => NullState::DefinitelyNull,
         Rvalue::Use(Operand::Copy(p)) | Rvalue::Use(Operand::Ref(p)) => {
@@ -159,13 +170,12 @@ fn check_deref_in_rvalue(
                 ));
             }
             NullState::MaybeNull => {
-                diags.push(LintDiagnostic::error(
+                diags.push(LintDiagnostic::warning(
                     lint_name,
                     Error::new_with_span(
                         "potential null pointer dereference: pointer may be null".to_string(),
                         span.clone(),
-                    )
-                    .with_severity(ErrorSeverity::Warning),
+                    ),
                 ));
             }
             _ => {}
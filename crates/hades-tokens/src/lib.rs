@@ -59,6 +59,8 @@ pub enum TokenKind {
     Multiply,
     Divide,
     Percent,
+    At,
+    Dollar,
     MinusEqual,
     PlusEqual,
     Colon,
@@ -103,9 +105,15 @@ pub enum TokenKind {
     Null,
     As,
     Extern,
+    Export,
+    Asm,
     Intrinsic,
     Defer,
     Ellipsis,
+    Loop,
+    In,
+    Macro,
+    Pub,
 }
 
 impl std::fmt::Display for TokenKind {
@@ -126,6 +134,8 @@ impl std::fmt::Display for TokenKind {
             TokenKind::Multiply => write!(f, "*"),
             TokenKind::Divide => write!(f, "/"),
             TokenKind::Percent => write!(f, "%"),
+            TokenKind::At => write!(f, "@"),
+            TokenKind::Dollar => write!(f, "$"),
             TokenKind::MinusEqual => write!(f, "-="),
             TokenKind::PlusEqual => write!(f, "+="),
             TokenKind::Colon => write!(f, ":"),
@@ -167,9 +177,15 @@ impl std::fmt::Display for TokenKind {
             TokenKind::Char(c) => write!(f, "'{c}'"),
             TokenKind::As => write!(f, "as"),
             TokenKind::Extern => write!(f, "extern"),
+            TokenKind::Export => write!(f, "export"),
+            TokenKind::Asm => write!(f, "asm"),
             TokenKind::Intrinsic => write!(f, "intrinsic"),
             TokenKind::Ellipsis => write!(f, "..."),
             TokenKind::Defer => write!(f, "defer"),
+            TokenKind::Loop => write!(f, "loop"),
+            TokenKind::In => write!(f, "in"),
+            TokenKind::Macro => write!(f, "macro"),
+            TokenKind::Pub => write!(f, "pub"),
         }
     }
 }
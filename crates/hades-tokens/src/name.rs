@@ -77,6 +77,17 @@ impl Name {
         }
     }
 
+    /// The overload of `self` taking the parameters `signature` spells, as in `show.int`. Being
+    /// mangled from the parameter types, the name stays the same however the overloads are
+    /// ordered, and the `.` keeps it apart from any function declared as `show__int`.
+    pub fn overload(&self, signature: &str) -> Name {
+        Name::build(
+            self.module.clone(),
+            format!("{}.{}", self.name, signature),
+            self.span.clone(),
+        )
+    }
+
     pub fn full_name(&self, qualifier: &str) -> Name {
         Name::build(
             Some(qualifier.to_string()),
@@ -1,17 +1,79 @@
 use clap::Args;
+use hades_compiler::{CfgSet, LinkOptions};
 use std::path::PathBuf;
 
+#[derive(Debug, Args)]
+pub struct LinkArgs {
+    /// Link against a library, e.g. `-l sqlite3`
+    #[arg(short = 'l', long = "lib", value_name = "NAME")]
+    pub libraries: Vec<String>,
+    /// Add a directory to the library search path
+    #[arg(short = 'L', long = "lib-path", value_name = "DIR")]
+    pub search_paths: Vec<PathBuf>,
+    /// Extra C source or object file to compile and link in
+    #[arg(long = "link-input", value_name = "FILE")]
+    pub inputs: Vec<PathBuf>,
+}
+
+impl From<LinkArgs> for LinkOptions {
+    fn from(args: LinkArgs) -> Self {
+        Self {
+            libraries: args.libraries,
+            search_paths: args.search_paths,
+            inputs: args.inputs,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct CfgArgs {
+    /// Enable a `@cfg` condition, e.g. `--cfg debug` or `--cfg os=windows`. Flags like `debug`
+    /// are only ever set here, whatever the build profile
+    #[arg(long = "cfg", value_name = "SPEC")]
+    pub cfgs: Vec<String>,
+    /// Enable a feature for `@cfg(feature = "name")`
+    #[arg(long = "feature", value_name = "NAME")]
+    pub features: Vec<String>,
+}
+
+impl From<CfgArgs> for CfgSet {
+    fn from(args: CfgArgs) -> Self {
+        let cfg = args
+            .cfgs
+            .iter()
+            .fold(CfgSet::new(), |cfg, spec| cfg.with_cfg(spec));
+        args.features
+            .into_iter()
+            .fold(cfg, |cfg, name| cfg.with_feature(name))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct StdlibArgs {
+    /// Directory of std modules overriding or extending the bundled ones, `$HADES_STDLIB` if unset
+    #[arg(long = "stdlib", value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+}
+
 #[derive(Debug, Args)]
 pub struct BuildArgs {
     #[arg(required = true)]
     pub source: PathBuf,
     pub output: Option<PathBuf>,
+    #[command(flatten)]
+    pub link: LinkArgs,
+    #[command(flatten)]
+    pub cfg: CfgArgs,
+    #[command(flatten)]
+    pub stdlib: StdlibArgs,
 }
 
 #[derive(Debug, Args)]
 pub struct CheckArgs {
     #[arg(required = true)]
     pub source: PathBuf,
+    #[command(flatten)]
+    pub cfg: CfgArgs,
 }
 
 #[derive(Debug, Args)]
@@ -19,18 +81,43 @@ pub struct RunArgs {
     #[arg(required = true)]
     pub source: PathBuf,
     pub output: Option<PathBuf>,
+    #[command(flatten)]
+    pub link: LinkArgs,
+    #[command(flatten)]
+    pub cfg: CfgArgs,
+    #[command(flatten)]
+    pub stdlib: StdlibArgs,
 }
 
 #[derive(Debug, Args)]
 pub struct EmitLlvmArgs {
     #[arg(required = true)]
     pub source: PathBuf,
+    #[command(flatten)]
+    pub cfg: CfgArgs,
+    #[command(flatten)]
+    pub stdlib: StdlibArgs,
 }
 
 #[derive(Debug, Args)]
 pub struct EmitMirArgs {
     #[arg(required = true)]
     pub source: PathBuf,
+    #[command(flatten)]
+    pub cfg: CfgArgs,
+    #[command(flatten)]
+    pub stdlib: StdlibArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct HeaderArgs {
+    #[arg(required = true)]
+    pub source: PathBuf,
+    pub output: Option<PathBuf>,
+    #[command(flatten)]
+    pub cfg: CfgArgs,
+    #[command(flatten)]
+    pub stdlib: StdlibArgs,
 }
 
 #[derive(Debug, Args)]
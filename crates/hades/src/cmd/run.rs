@@ -1,4 +1,6 @@
-use super::args::{BuildArgs, CheckArgs, EmitLlvmArgs, EmitMirArgs, PrintAstArgs, RunArgs};
+use super::args::{
+    BuildArgs, CheckArgs, EmitLlvmArgs, EmitMirArgs, HeaderArgs, PrintAstArgs, RunArgs,
+};
 use clap::{Parser, Subcommand};
 
 #[derive(Debug, Parser)]
@@ -21,4 +23,5 @@ pub enum Commands {
     EmitLlvm(EmitLlvmArgs),
     PrintAst(PrintAstArgs),
     EmitMir(EmitMirArgs),
+    Header(HeaderArgs),
 }
@@ -9,7 +9,10 @@ fn main() {
 
     match cmd.command {
         cmd::Commands::Build(args) => {
-            let compiler = Compiler::new();
+            let compiler = Compiler::new()
+                .with_link_options(args.link.into())
+                .with_cfg(args.cfg.into())
+                .with_stdlib(args.stdlib.dir);
             compiler.prepare();
 
             let path = args.output.unwrap_or_else(|| {
@@ -23,7 +26,7 @@ fn main() {
 
         cmd::Commands::Check(args) => {
             let source = std::fs::read_to_string(&args.source).expect("Failed to read source file");
-            let compiler = Compiler::new();
+            let compiler = Compiler::new().with_cfg(args.cfg.into());
 
             compiler.prepare();
             if !compiler.check(&source, args.source.to_str().unwrap()) {
@@ -32,7 +35,10 @@ fn main() {
         }
 
         cmd::Commands::Run(args) => {
-            let compiler = Compiler::new();
+            let compiler = Compiler::new()
+                .with_link_options(args.link.into())
+                .with_cfg(args.cfg.into())
+                .with_stdlib(args.stdlib.dir);
             compiler.prepare();
 
             let path = args
@@ -49,7 +55,9 @@ fn main() {
         }
 
         cmd::Commands::EmitLlvm(args) => {
-            let compiler = Compiler::new();
+            let compiler = Compiler::new()
+                .with_cfg(args.cfg.into())
+                .with_stdlib(args.stdlib.dir);
             compiler.prepare();
 
             let context = Context::create();
@@ -66,8 +74,31 @@ fn main() {
             compiler.print_ast(&source, args.source.to_str().unwrap());
         }
 
+        cmd::Commands::Header(args) => {
+            let compiler = Compiler::new()
+                .with_cfg(args.cfg.into())
+                .with_stdlib(args.stdlib.dir);
+            compiler.prepare();
+
+            let path = args.output.unwrap_or_else(|| {
+                let stem = args
+                    .source
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy();
+                PathBuf::from(format!("{}/{stem}.h", hades_common::BUILD_PATH))
+            });
+
+            if let Err(e) = compiler.emit_header(&args.source, &path) {
+                eprintln!("Failed to emit C header: {e}");
+                std::process::exit(1);
+            }
+        }
+
         cmd::Commands::EmitMir(args) => {
-            let compiler = Compiler::new();
+            let compiler = Compiler::new()
+                .with_cfg(args.cfg.into())
+                .with_stdlib(args.stdlib.dir);
             compiler.prepare();
 
             if let Err(e) = compiler.emit_mir(&args.source) {
@@ -0,0 +1,5 @@
+#ifndef CONFIG_READY
+#error "define CONFIG_READY before including config.h"
+#endif
+
+long config_value(void);
@@ -0,0 +1,6 @@
+#define VERSION(major, minor) ((major) * 100 + (minor))
+
+/* Function-like macros are not expanded, so this condition can't be evaluated. */
+#if VERSION(1, 2) > 100
+long version_major(void);
+#endif
@@ -0,0 +1,49 @@
+#include <stdint.h>
+
+/* Two INTEGER eightbytes: passed in two general purpose registers. */
+typedef struct {
+    int64_t x;
+    int64_t y;
+} Pair;
+
+/* Two SSE eightbytes: passed in two xmm registers. */
+typedef struct {
+    double x;
+    double y;
+} Vec2;
+
+/* One INTEGER and one SSE eightbyte. */
+typedef struct {
+    char tag;
+    double weight;
+} Tagged;
+
+/* Larger than 16 bytes: passed byval and returned through sret. */
+typedef struct {
+    int64_t a;
+    int64_t b;
+    int64_t c;
+} Triple;
+
+int64_t pair_sum(Pair p) { return p.x + p.y; }
+
+Pair pair_swap(Pair p) {
+    Pair r = {p.y, p.x};
+    return r;
+}
+
+double vec2_dot(Vec2 a, Vec2 b) { return a.x * b.x + a.y * b.y; }
+
+Vec2 vec2_scale(Vec2 v, double k) {
+    Vec2 r = {v.x * k, v.y * k};
+    return r;
+}
+
+double tagged_weight(Tagged t) { return t.tag == 'h' ? t.weight * 2.0 : t.weight; }
+
+int64_t triple_sum(Triple t) { return t.a + t.b + t.c; }
+
+Triple triple_make(int64_t a, int64_t b, int64_t c) {
+    Triple t = {a, b, c};
+    return t;
+}
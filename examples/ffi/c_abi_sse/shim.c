@@ -0,0 +1,48 @@
+#include <stdint.h>
+
+typedef double v2d __attribute__((vector_size(16)));
+typedef int64_t v2i __attribute__((vector_size(16)));
+
+/* Two SSE eightbytes, one double each. */
+typedef struct {
+    double xs[2];
+} Span;
+
+/* A single 16-byte vector: SSE + SSEUP, one xmm register. */
+typedef struct {
+    v2d v;
+} Lanes;
+
+/* Integer lanes are still SSE. */
+typedef struct {
+    v2i v;
+} Counts;
+
+/* The misaligned `len` puts the struct in MEMORY despite its 9 bytes. */
+typedef struct __attribute__((packed)) {
+    char tag;
+    int64_t len;
+} Header;
+
+double span_sum(Span s) { return s.xs[0] + s.xs[1]; }
+
+Span span_flip(Span s) {
+    Span r = {{s.xs[1], s.xs[0]}};
+    return r;
+}
+
+double lanes_sum(Lanes l) { return l.v[0] + l.v[1]; }
+
+Lanes lanes_scale(Lanes l, double k) {
+    Lanes r = {l.v * k};
+    return r;
+}
+
+int64_t counts_total(Counts c) { return c.v[0] + c.v[1]; }
+
+int64_t header_len(Header h) { return h.tag == 'h' ? h.len * 2 : h.len; }
+
+Header header_make(char tag, int64_t len) {
+    Header h = {tag, len};
+    return h;
+}
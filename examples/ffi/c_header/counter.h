@@ -0,0 +1,62 @@
+#ifndef COUNTER_H
+#define COUNTER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Largest step counter_add accepts. */
+#define COUNTER_MAX_STEP 100
+#define COUNTER_SQUARE(x) ((x) * (x))
+
+typedef enum {
+    COUNTER_UP,
+    COUNTER_DOWN = 4,
+    COUNTER_HOLD,
+} counter_mode;
+
+/* Only ever handled through a pointer. */
+typedef struct counter counter;
+
+typedef struct {
+    int64_t x;
+    int64_t y;
+} point;
+
+struct range {
+    long lo;
+    long hi;
+};
+
+counter *counter_new(int64_t start);
+void counter_add(counter *c, int64_t step);
+int64_t counter_get(const counter *c);
+void counter_free(counter *c);
+
+point point_add(point a, point b);
+long range_len(const struct range *r);
+int64_t sum_ints(int64_t count, ...);
+
+/* Only the branch the conditions select is imported. */
+#if defined(COUNTER_MAX_STEP) && COUNTER_MAX_STEP >= 100
+int64_t counter_limit(void);
+#else
+double counter_limit(void);
+#endif
+
+#ifdef COUNTER_LEGACY
+#error "COUNTER_LEGACY is not supported"
+#endif
+
+static inline int counter_twice(int x) {
+    return 2 * x;
+}
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
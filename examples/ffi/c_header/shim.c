@@ -0,0 +1,44 @@
+#include <stdarg.h>
+#include <stdlib.h>
+
+#include "counter.h"
+
+struct counter {
+    int64_t value;
+};
+
+counter *counter_new(int64_t start) {
+    counter *c = malloc(sizeof(counter));
+    c->value = start;
+    return c;
+}
+
+void counter_add(counter *c, int64_t step) {
+    if (step <= COUNTER_MAX_STEP) {
+        c->value += step;
+    }
+}
+
+int64_t counter_get(const counter *c) { return c->value; }
+
+void counter_free(counter *c) { free(c); }
+
+point point_add(point a, point b) {
+    point p = {a.x + b.x, a.y + b.y};
+    return p;
+}
+
+long range_len(const struct range *r) { return r->hi - r->lo; }
+
+int64_t sum_ints(int64_t count, ...) {
+    va_list args;
+    va_start(args, count);
+    int64_t total = 0;
+    for (int64_t i = 0; i < count; i++) {
+        total += va_arg(args, int64_t);
+    }
+    va_end(args);
+    return total;
+}
+
+int64_t counter_limit(void) { return COUNTER_MAX_STEP; }
@@ -0,0 +1,8 @@
+#include <stdint.h>
+
+/* Narrower than Hades' `int` and `float`; calls convert at the boundary. */
+int negate(int x);
+float halve(float x);
+uint8_t next_byte(uint8_t b);
+int16_t wrap16(int16_t x);
+uint32_t big(void);
@@ -0,0 +1,11 @@
+#include "narrow.h"
+
+int negate(int x) { return -x; }
+
+float halve(float x) { return x / 2.0f; }
+
+uint8_t next_byte(uint8_t b) { return b + 1; }
+
+int16_t wrap16(int16_t x) { return x + 1; }
+
+uint32_t big(void) { return 4000000000u; }
@@ -0,0 +1,32 @@
+/* Generated by `hades header`. Do not edit. */
+#ifndef EXPORT_H
+#define EXPORT_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+typedef struct Segment Segment;
+typedef struct Point Point;
+typedef struct Header Header;
+
+struct Point {
+    int64_t x;
+    int64_t y;
+};
+
+struct Segment {
+    Point start;
+    Point end;
+    Segment *next;
+};
+
+struct __attribute__((packed)) Header {
+    char tag;
+    int64_t len;
+};
+
+int64_t header_len(Header *h);
+int64_t point_len2(Point *p);
+int64_t scale(int64_t v, int64_t k);
+
+#endif /* EXPORT_H */
@@ -0,0 +1,6 @@
+#include "export.h"
+
+int64_t shim_run(Point *p) { return scale(point_len2(p), 2); }
+
+/* 14 only if the generated header lays Header out packed, as Hades does. */
+int64_t shim_header_size(Header *h) { return header_len(h) + (int64_t)sizeof(Header); }
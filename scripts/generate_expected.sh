@@ -30,8 +30,16 @@ while IFS= read -r file; do
         continue
     fi
 
+    # C shims next to an example are compiled and linked in with it.
+    link_args=()
+    for shim in "$dir"/*.c; do
+        if [ -f "$shim" ]; then
+            link_args+=(--link-input "$shim")
+        fi
+    done
+
     set +e
-    OUTPUT=$("$HADES" run "$file" 2>&1)
+    OUTPUT=$("$HADES" run "$file" "${link_args[@]}" 2>&1)
     EXIT_CODE=$?
     set -e
 
@@ -39,10 +39,97 @@ test_example() {
 
     echo -n "Testing $name... "
 
+    # A `.expected.h` is the golden output of `hades header`. The header is generated into a
+    # scratch directory named after the example, and the shims are built against that copy.
+    local header_dir=""
+    if [ -f "${dir}/.expected.h" ]; then
+        header_dir=$(mktemp -d)
+        local header="$header_dir/$(basename "$dir").h"
+        local header_diff
+        if ! header_diff=$("$HADES" header "$file" "$header" 2>&1 && diff -u "${dir}/.expected.h" "$header"); then
+            echo -e "${RED}✗${NC}"
+            echo "  Header mismatch:"
+            echo "$header_diff"
+            FAILED=$((FAILED + 1))
+            rm -rf "$header_dir"
+            return
+        fi
+    fi
+
+    # C shims next to an example are compiled and linked in with it.
+    local link_args=()
+    for shim in "$dir"/*.c; do
+        if [ ! -f "$shim" ]; then
+            continue
+        fi
+        if [ -n "$header_dir" ]; then
+            local object="$header_dir/$(basename "$shim" .c).o"
+            if ! clang -c -I "$header_dir" "$shim" -o "$object"; then
+                echo -e "${RED}✗${NC}"
+                echo "  $shim does not compile against the generated header"
+                FAILED=$((FAILED + 1))
+                rm -rf "$header_dir"
+                return
+            fi
+            link_args+=(--link-input "$object")
+        else
+            link_args+=(--link-input "$shim")
+        fi
+    done
+
+    # A `.stdlib` file names a std directory, relative to the example, passed with `--stdlib`.
+    # A `.stdlib_env` file does the same through `HADES_STDLIB`.
+    local stdlib_args=()
+    if [ -f "${dir}/.stdlib" ]; then
+        stdlib_args+=(--stdlib "$dir/$(cat "${dir}/.stdlib")")
+    fi
+    local stdlib_env=""
+    if [ -f "${dir}/.stdlib_env" ]; then
+        stdlib_env="$dir/$(cat "${dir}/.stdlib_env")"
+    fi
+
+    # Each line of a `.cfg` file is passed as a `--cfg`, e.g. `debug` or `os=plan9`.
+    local cfg_args=()
+    if [ -f "${dir}/.cfg" ]; then
+        while IFS= read -r spec; do
+            if [ -n "$spec" ]; then
+                cfg_args+=(--cfg "$spec")
+            fi
+        done < "${dir}/.cfg"
+    fi
+
     set +e
-    OUTPUT=$("$HADES" $command "$file" 2>&1)
+    if [ -n "$stdlib_env" ]; then
+        OUTPUT=$(HADES_STDLIB="$stdlib_env" "$HADES" $command "$file" "${link_args[@]}" "${stdlib_args[@]}" "${cfg_args[@]}" 2>&1)
+    else
+        OUTPUT=$("$HADES" $command "$file" "${link_args[@]}" "${stdlib_args[@]}" "${cfg_args[@]}" 2>&1)
+    fi
     EXIT_CODE=$?
     set -e
+    if [ -n "$header_dir" ]; then
+        rm -rf "$header_dir"
+    fi
+
+    # Each line of `.expected_warnings` has to appear in the output, which includes the
+    # compiler's stderr.
+    local warnings_file="${dir}/.expected_warnings"
+    if [ -f "$warnings_file" ]; then
+        local missing=()
+        while IFS= read -r warning; do
+            if [[ "$OUTPUT" != *"$warning"* ]]; then
+                missing+=("$warning")
+            fi
+        done < "$warnings_file"
+        if [ ${#missing[@]} -gt 0 ]; then
+            echo -e "${RED}✗${NC}"
+            for warning in "${missing[@]}"; do
+                echo "  Missing warning: $warning"
+            done
+            echo "  Output: $OUTPUT"
+            FAILED=$((FAILED + 1))
+            return
+        fi
+    fi
 
     if [ -f "$expected_file" ]; then
         local expected